@@ -12,6 +12,10 @@ pub struct Cli {
     #[arg(short, long, default_value_t = false)]
     pub safe: bool,
     
+    /// When run as root, keep the decoded memory slot list for later unprivileged runs
+    #[arg(long, default_value_t = false)]
+    pub cache_dmi: bool,
+    
     #[arg(short, long, default_value_t = 1000)]
     pub refresh: u64,
     
@@ -33,6 +37,14 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub auto_scroll: bool,
     
+    /// Seconds of steady RSS growth before a process is flagged as leaking
+    #[arg(long, default_value_t = 300)]
+    pub leak_window: u64,
+    
+    /// Monthly data cap for a metered interface in GiB, e.g. `usb0=15`. Repeatable
+    #[arg(long, value_name = "IFACE=GIB", value_parser = parse_data_cap)]
+    pub data_cap: Vec<(String, u64)>,
+    
     #[arg(long, default_value = "en")]
     pub lang: String,
     
@@ -43,6 +55,17 @@ pub struct Cli {
     pub verbose: bool,
 }
 
+fn parse_data_cap(arg: &str) -> Result<(String, u64), String> {
+    let (iface, gib) = arg
+        .split_once('=')
+        .ok_or_else(|| "expected IFACE=GIB, e.g. usb0=15".to_string())?;
+    let gib: f64 = gib.trim().parse().map_err(|_| format!("'{}' is not a number of GiB", gib))?;
+    if iface.is_empty() || gib <= 0.0 {
+        return Err("expected IFACE=GIB with a positive cap".to_string());
+    }
+    Ok((iface.to_string(), (gib * 1024.0 * 1024.0 * 1024.0) as u64))
+}
+
 impl From<Cli> for AppConfig {
     fn from(cli: Cli) -> Self {
         let language = if cli.tr {
@@ -53,11 +76,14 @@ impl From<Cli> for AppConfig {
         
         Self {
             safe_mode: cli.safe,
+            cache_dmi: cli.cache_dmi,
             refresh_rate_ms: cli.refresh.max(100).min(10000), 
             history_length: cli.history.max(10).min(300),     
             enable_docker: !cli.safe && !cli.no_docker,
             enable_gpu_monitoring: !cli.safe && !cli.no_gpu,
             enable_network_monitoring: !cli.safe && !cli.no_network,
+            leak_window_secs: cli.leak_window.max(30),
+            data_caps: cli.data_cap,
             language,
         }
     }
@@ -94,11 +120,14 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             safe_mode: false,
+            cache_dmi: false,
             refresh_rate_ms: 1000,
             history_length: 60,
             enable_docker: true,
             enable_gpu_monitoring: true,
             enable_network_monitoring: true,
+            leak_window_secs: 300,
+            data_caps: Vec::new(),
             language: Language::English,
         }
     }
@@ -0,0 +1,358 @@
+use serde_json::Value;
+
+use crate::types::{FirewallChain, FirewallRule, FirewallTable, FirewalldZone};
+
+/// Parses `nft -j list ruleset`. Objects arrive flat (tables, then chains, then
+/// rules) and are grouped by family/table/chain.
+pub fn parse_nft_json(content: &str) -> Result<Vec<FirewallTable>, String> {
+    let root: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let items = root
+        .get("nftables")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "missing \"nftables\" array".to_string())?;
+
+    let mut tables: Vec<FirewallTable> = Vec::new();
+    let str_field = |obj: &Value, key: &str| obj.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+
+    for item in items {
+        if let Some(table) = item.get("table") {
+            tables.push(FirewallTable {
+                family: str_field(table, "family"),
+                name: str_field(table, "name"),
+                chains: Vec::new(),
+            });
+        } else if let Some(chain) = item.get("chain") {
+            let (family, table) = (str_field(chain, "family"), str_field(chain, "table"));
+            if let Some(t) = tables.iter_mut().find(|t| t.family == family && t.name == table) {
+                t.chains.push(FirewallChain {
+                    name: str_field(chain, "name"),
+                    hook: str_field(chain, "hook"),
+                    policy: str_field(chain, "policy"),
+                    ..Default::default()
+                });
+            }
+        } else if let Some(rule) = item.get("rule") {
+            let (family, table, chain) = (str_field(rule, "family"), str_field(rule, "table"), str_field(rule, "chain"));
+            let target = tables
+                .iter_mut()
+                .find(|t| t.family == family && t.name == table)
+                .and_then(|t| t.chains.iter_mut().find(|c| c.name == chain));
+            if let Some(c) = target {
+                c.rules.push(nft_rule(rule));
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+fn nft_rule(rule: &Value) -> FirewallRule {
+    let exprs = rule.get("expr").and_then(Value::as_array).cloned().unwrap_or_default();
+    let counter = exprs.iter().find_map(|e| e.get("counter")).filter(|c| c.is_object());
+    let text: Vec<String> = exprs
+        .iter()
+        .filter(|e| e.get("counter").is_none())
+        .map(render_statement)
+        .collect();
+
+    FirewallRule {
+        handle: rule.get("handle").map(|h| h.to_string()).unwrap_or_default(),
+        text: text.join(" "),
+        comment: rule.get("comment").and_then(Value::as_str).unwrap_or("").to_string(),
+        packets: counter.and_then(|c| c.get("packets")).and_then(Value::as_u64),
+        bytes: counter.and_then(|c| c.get("bytes")).and_then(Value::as_u64),
+    }
+}
+
+/// Approximates nft's own rule syntax for the common statements.
+fn render_statement(stmt: &Value) -> String {
+    let (key, body) = match stmt.as_object().and_then(|o| o.iter().next()) {
+        Some(kv) => kv,
+        None => return String::new(),
+    };
+    match key.as_str() {
+        "match" => {
+            let left = body.get("left").map(render_value).unwrap_or_default();
+            let right = body.get("right").map(render_value).unwrap_or_default();
+            match body.get("op").and_then(Value::as_str) {
+                Some("==") | Some("in") | None => format!("{} {}", left, right),
+                Some(op) => format!("{} {} {}", left, op, right),
+            }
+        }
+        "jump" | "goto" => format!("{} {}", key, body.get("target").and_then(Value::as_str).unwrap_or("")),
+        "log" => match body.get("prefix").and_then(Value::as_str) {
+            Some(prefix) => format!("log prefix \"{}\"", prefix),
+            None => "log".to_string(),
+        },
+        "limit" => format!(
+            "limit rate {}/{}",
+            body.get("rate").map(render_value).unwrap_or_default(),
+            body.get("per").and_then(Value::as_str).unwrap_or("second")
+        ),
+        "snat" | "dnat" => {
+            let addr = body.get("addr").map(render_value).unwrap_or_default();
+            match body.get("port").map(render_value) {
+                Some(port) => format!("{} to {}:{}", key, addr, port),
+                None => format!("{} to {}", key, addr),
+            }
+        }
+        _ => key.clone(),
+    }
+}
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().map(render_value).collect::<Vec<_>>().join(","),
+        Value::Object(obj) => {
+            let (key, body) = match obj.iter().next() {
+                Some(kv) => kv,
+                None => return String::new(),
+            };
+            let field = |k: &str| body.get(k).and_then(Value::as_str).unwrap_or("");
+            match key.as_str() {
+                "payload" => format!("{} {}", field("protocol"), field("field")),
+                "meta" => match field("key") {
+                    k @ ("iifname" | "oifname" | "iif" | "oif") => k.to_string(),
+                    k => format!("meta {}", k),
+                },
+                "ct" => format!("ct {}", field("key")),
+                "set" => match body {
+                    Value::Array(items) => format!("{{ {} }}", items.iter().map(render_value).collect::<Vec<_>>().join(", ")),
+                    other => render_value(other),
+                },
+                "range" => match body.as_array() {
+                    Some(r) if r.len() == 2 => format!("{}-{}", render_value(&r[0]), render_value(&r[1])),
+                    _ => String::new(),
+                },
+                "prefix" => format!("{}/{}", body.get("addr").map(render_value).unwrap_or_default(), body.get("len").map(render_value).unwrap_or_default()),
+                _ => key.clone(),
+            }
+        }
+        Value::Null => String::new(),
+    }
+}
+
+/// Parses `iptables-save -c` output: `*table`, `:CHAIN POLICY [pkts:bytes]` and
+/// `[pkts:bytes] -A CHAIN ...` lines.
+pub fn parse_iptables_save(content: &str, family: &str) -> Vec<FirewallTable> {
+    let mut tables: Vec<FirewallTable> = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('*') {
+            tables.push(FirewallTable {
+                family: family.to_string(),
+                name: name.to_string(),
+                chains: Vec::new(),
+            });
+            continue;
+        }
+        let table = match tables.last_mut() {
+            Some(t) => t,
+            None => continue,
+        };
+
+        if let Some(rest) = line.strip_prefix(':') {
+            let mut parts = rest.split_whitespace();
+            let name = parts.next().unwrap_or("").to_string();
+            let policy = parts.next().filter(|p| *p != "-").unwrap_or("").to_string();
+            let (packets, bytes) = parse_counters(parts.next().unwrap_or(""));
+            table.chains.push(FirewallChain {
+                hook: if policy.is_empty() { String::new() } else { name.to_lowercase() },
+                name,
+                policy,
+                packets: packets.filter(|_| !rest.contains(" - ")),
+                bytes: bytes.filter(|_| !rest.contains(" - ")),
+                rules: Vec::new(),
+            });
+            continue;
+        }
+
+        let (counters, rule) = match line.strip_prefix('[').and_then(|l| l.split_once("] ")) {
+            Some((counters, rule)) => (format!("[{}]", counters), rule),
+            None => (String::new(), line),
+        };
+        let rest = match rule.strip_prefix("-A ") {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let (chain, text) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (packets, bytes) = parse_counters(&counters);
+        if let Some(c) = table.chains.iter_mut().find(|c| c.name == chain) {
+            c.rules.push(FirewallRule {
+                handle: (c.rules.len() + 1).to_string(),
+                text: text.to_string(),
+                comment: iptables_comment(text),
+                packets,
+                bytes,
+            });
+        }
+    }
+
+    tables
+}
+
+fn parse_counters(field: &str) -> (Option<u64>, Option<u64>) {
+    field
+        .strip_prefix('[')
+        .and_then(|f| f.strip_suffix(']'))
+        .and_then(|f| f.split_once(':'))
+        .map(|(p, b)| (p.parse().ok(), b.parse().ok()))
+        .unwrap_or((None, None))
+}
+
+fn iptables_comment(rule: &str) -> String {
+    let rest = match rule.split_once("--comment ") {
+        Some((_, rest)) => rest,
+        None => return String::new(),
+    };
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or("").to_string(),
+        None => rest.split_whitespace().next().unwrap_or("").to_string(),
+    }
+}
+
+/// Parses `firewall-cmd --list-all-zones`: an unindented `name (default, active)`
+/// header followed by indented `key: values` lines.
+pub fn parse_firewalld_zones(content: &str) -> Vec<FirewalldZone> {
+    let mut zones: Vec<FirewalldZone> = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            let (name, flags) = line.split_once(' ').unwrap_or((line, ""));
+            zones.push(FirewalldZone {
+                name: name.to_string(),
+                active: flags.contains("active"),
+                default: flags.contains("default"),
+                ..Default::default()
+            });
+            continue;
+        }
+        let zone = match zones.last_mut() {
+            Some(z) => z,
+            None => continue,
+        };
+        let (key, value) = match line.trim().split_once(':') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        let values: Vec<String> = value.split_whitespace().map(str::to_string).collect();
+        match key {
+            "target" => zone.target = value.trim().to_string(),
+            "interfaces" => zone.interfaces = values,
+            "sources" => zone.sources = values,
+            "services" => zone.services = values,
+            "ports" => zone.ports = values,
+            _ => {}
+        }
+    }
+
+    zones
+}
+
+/// firewalld accepts service names (`ssh`) and `port/proto` or `from-to/proto` ports.
+pub fn validate_firewalld_value(kind: &str, value: &str) -> Result<(), String> {
+    match kind {
+        "service" if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) => Ok(()),
+        "service" => Err(format!("'{}' is not a valid service name", value)),
+        "port" => {
+            let (ports, proto) = value
+                .split_once('/')
+                .ok_or_else(|| format!("'{}' must look like 8080/tcp", value))?;
+            let valid_port = |p: &str| p.parse::<u16>().is_ok_and(|p| p > 0);
+            let ports_ok = match ports.split_once('-') {
+                Some((from, to)) => valid_port(from) && valid_port(to),
+                None => valid_port(ports),
+            };
+            if !ports_ok || !matches!(proto, "tcp" | "udp" | "sctp" | "dccp") {
+                return Err(format!("'{}' must look like 8080/tcp or 6000-6010/udp", value));
+            }
+            Ok(())
+        }
+        _ => Err(format!("unknown firewalld item '{}'", kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nft_json() {
+        let content = r#"{"nftables": [
+            {"metainfo": {"version": "1.0.9", "json_schema_version": 1}},
+            {"table": {"family": "inet", "name": "filter", "handle": 1}},
+            {"chain": {"family": "inet", "table": "filter", "name": "input", "handle": 1, "type": "filter", "hook": "input", "prio": 0, "policy": "drop"}},
+            {"chain": {"family": "inet", "table": "filter", "name": "services", "handle": 2}},
+            {"rule": {"family": "inet", "table": "filter", "chain": "input", "handle": 4, "expr": [
+                {"match": {"op": "in", "left": {"ct": {"key": "state"}}, "right": ["established", "related"]}},
+                {"counter": {"packets": 1200, "bytes": 98000}},
+                {"accept": null}]}},
+            {"rule": {"family": "inet", "table": "filter", "chain": "input", "handle": 5, "comment": "web", "expr": [
+                {"match": {"op": "==", "left": {"payload": {"protocol": "tcp", "field": "dport"}}, "right": {"set": [80, 443, {"range": [8000, 8080]}]}}},
+                {"match": {"op": "!=", "left": {"payload": {"protocol": "ip", "field": "saddr"}}, "right": {"prefix": {"addr": "10.0.0.0", "len": 8}}}},
+                {"counter": {"packets": 7, "bytes": 420}},
+                {"jump": {"target": "services"}}]}}
+        ]}"#;
+        let tables = parse_nft_json(content).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].family, "inet");
+        let input = &tables[0].chains[0];
+        assert_eq!((input.hook.as_str(), input.policy.as_str()), ("input", "drop"));
+        assert_eq!(input.rules.len(), 2);
+        assert_eq!(input.rules[0].text, "ct state established,related accept");
+        assert_eq!(input.rules[0].packets, Some(1200));
+        assert_eq!(input.rules[1].text, "tcp dport { 80, 443, 8000-8080 } ip saddr != 10.0.0.0/8 jump services");
+        assert_eq!(input.rules[1].comment, "web");
+        assert_eq!(input.rules[1].bytes, Some(420));
+        assert_eq!(tables[0].chains[1].hook, "");
+        assert!(parse_nft_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_iptables_save() {
+        let content = "# Generated by iptables-save v1.8.9\n\
+                       *filter\n\
+                       :INPUT DROP [120:10400]\n\
+                       :FORWARD ACCEPT [0:0]\n\
+                       :DOCKER - [0:0]\n\
+                       [50:4000] -A INPUT -i lo -j ACCEPT\n\
+                       [3:180] -A INPUT -p tcp -m tcp --dport 22 -m comment --comment \"ssh in\" -j ACCEPT\n\
+                       COMMIT\n";
+        let tables = parse_iptables_save(content, "ip");
+        assert_eq!(tables.len(), 1);
+        let chains = &tables[0].chains;
+        assert_eq!(chains.len(), 3);
+        assert_eq!((chains[0].hook.as_str(), chains[0].policy.as_str()), ("input", "DROP"));
+        assert_eq!(chains[0].packets, Some(120));
+        assert_eq!(chains[2].policy, "");
+        assert_eq!(chains[2].packets, None);
+        assert_eq!(chains[0].rules[0].text, "-i lo -j ACCEPT");
+        assert_eq!(chains[0].rules[0].bytes, Some(4000));
+        assert_eq!(chains[0].rules[1].comment, "ssh in");
+    }
+
+    #[test]
+    fn test_parse_firewalld_zones() {
+        let content = "block\n  target: %%REJECT%%\n  interfaces: \n  services: \n\n\
+                       public (default, active)\n  target: default\n  icmp-block-inversion: no\n  \
+                       interfaces: eth0 wlan0\n  sources: \n  services: dhcpv6-client ssh\n  ports: 8080/tcp 6000-6010/udp\n";
+        let zones = parse_firewalld_zones(content);
+        assert_eq!(zones.len(), 2);
+        assert!(!zones[0].active);
+        assert_eq!(zones[0].target, "%%REJECT%%");
+        assert!(zones[1].active && zones[1].default);
+        assert_eq!(zones[1].interfaces, vec!["eth0", "wlan0"]);
+        assert_eq!(zones[1].services, vec!["dhcpv6-client", "ssh"]);
+        assert_eq!(zones[1].ports, vec!["8080/tcp", "6000-6010/udp"]);
+
+        assert!(validate_firewalld_value("port", "6000-6010/udp").is_ok());
+        assert!(validate_firewalld_value("port", "70000/tcp").is_err());
+        assert!(validate_firewalld_value("service", "ssh; rm").is_err());
+    }
+}
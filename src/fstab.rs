@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use crate::types::FstabEntry;
+
+const SPEC_PREFIXES: [&str; 4] = ["UUID=", "LABEL=", "PARTUUID=", "PARTLABEL="];
+
+/// Parses fstab content, skipping comments and blank lines. Lines that don't split
+/// into at least four fields are kept with what was found so validation can report them.
+pub fn parse_fstab(content: &str) -> Vec<FstabEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with('#')
+        })
+        .map(|(idx, line)| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let field = |i: usize| fields.get(i).map(|f| f.to_string()).unwrap_or_default();
+            FstabEntry {
+                line: idx + 1,
+                spec: field(0),
+                file: field(1),
+                vfstype: field(2),
+                mntops: field(3),
+                freq: field(4),
+                passno: field(5),
+                extra_fields: fields.len() > 6,
+            }
+        })
+        .collect()
+}
+
+pub fn validate_entry(entry: &FstabEntry) -> Result<(), String> {
+    let at = format!("line {}", entry.line);
+
+    if entry.extra_fields {
+        return Err(format!("{}: more than 6 fields (escape spaces as \\040)", at));
+    }
+    if entry.spec.is_empty() || entry.file.is_empty() || entry.vfstype.is_empty() || entry.mntops.is_empty() {
+        return Err(format!("{}: expected at least 4 fields (device, mount point, type, options)", at));
+    }
+
+    let spec_ok = entry.spec.starts_with('/')
+        || SPEC_PREFIXES.iter().any(|p| entry.spec.strip_prefix(p).is_some_and(|v| !v.is_empty()))
+        || entry.spec.contains(":/")
+        || entry.spec.starts_with("//")
+        || !entry.spec.contains('=');
+    if !spec_ok {
+        return Err(format!("{}: unrecognised device '{}'", at, entry.spec));
+    }
+
+    if entry.vfstype == "swap" {
+        if entry.file != "none" && entry.file != "swap" {
+            return Err(format!("{}: swap entries must use 'none' as mount point", at));
+        }
+    } else if !entry.file.starts_with('/') && entry.file != "none" {
+        return Err(format!("{}: mount point '{}' must be an absolute path", at, entry.file));
+    }
+
+    if entry.mntops.split(',').any(|o| o.is_empty()) {
+        return Err(format!("{}: empty option in '{}'", at, entry.mntops));
+    }
+    if !entry.freq.is_empty() && entry.freq.parse::<u32>().is_err() {
+        return Err(format!("{}: dump field '{}' is not a number", at, entry.freq));
+    }
+    if !entry.passno.is_empty() && !matches!(entry.passno.as_str(), "0" | "1" | "2") {
+        return Err(format!("{}: fsck pass '{}' must be 0, 1 or 2", at, entry.passno));
+    }
+
+    Ok(())
+}
+
+/// All problems in the file; an empty result means it is safe to write.
+pub fn validate_fstab(entries: &[FstabEntry]) -> Vec<String> {
+    let mut errors: Vec<String> = entries
+        .iter()
+        .filter_map(|e| validate_entry(e).err())
+        .collect();
+
+    let mut seen = HashSet::new();
+    for entry in entries.iter().filter(|e| e.file.starts_with('/')) {
+        if !seen.insert(entry.file.as_str()) {
+            errors.push(format!("line {}: mount point '{}' is listed more than once", entry.line, entry.file));
+        }
+    }
+
+    if !entries.iter().any(|e| e.file == "/") {
+        errors.push("no entry mounts the root filesystem '/'".to_string());
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FSTAB: &str = "# /etc/fstab\n\
+                         UUID=1234-abcd /               ext4    defaults,noatime 0 1\n\
+                         UUID=9F2C-11AA /boot/efi       vfat    umask=0077       0 2\n\
+                         /swapfile      none            swap    sw               0 0\n\
+                         \n\
+                         server:/export /mnt/nfs        nfs     _netdev\n";
+
+    #[test]
+    fn test_parse_fstab() {
+        let entries = parse_fstab(FSTAB);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].line, 2);
+        assert_eq!(entries[0].spec, "UUID=1234-abcd");
+        assert_eq!(entries[0].mntops, "defaults,noatime");
+        assert_eq!(entries[3].passno, "");
+        assert!(validate_fstab(&entries).is_empty());
+    }
+
+    #[test]
+    fn test_validate_fstab_errors() {
+        let content = "UUID=1234 / ext4 defaults 0 1\n\
+                       UUID= /data ext4 defaults 0 2\n\
+                       /dev/sdb1 data ext4 defaults 0 2\n\
+                       /dev/sdc1 /srv xfs defaults 0 3\n\
+                       /dev/sdd1 /srv ext4 defaults 0 0\n\
+                       /dev/sde1 /my data ext4 defaults 0 0\n\
+                       /dev/sdf1 /opt\n";
+        let errors = validate_fstab(&parse_fstab(content));
+        assert_eq!(errors.len(), 6);
+        assert!(errors[0].contains("line 2"));
+        assert!(errors[1].contains("absolute path"));
+        assert!(errors[2].contains("fsck pass"));
+        assert!(errors[3].contains("more than 6 fields"));
+        assert!(errors[4].contains("at least 4 fields"));
+        assert!(errors[5].contains("more than once"));
+    }
+}
@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::Path;
+
+use parking_lot::Mutex;
+
+use crate::system_service::SystemManager;
+use crate::types::{AmdGpuControls, FanCurve, NvidiaGpuControls};
+
+pub const PERFORMANCE_LEVELS: &[&str] = &[
+    "auto", "low", "high", "manual",
+    "profile_standard", "profile_min_sclk", "profile_min_mclk", "profile_peak",
+];
+
+const FAN_CURVE_FILE: &str = "gpu_od/fan_ctrl/fan_curve";
+
+/// A value to write to the hardware. The same type records what a change replaced
+/// so it can be put back.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GpuSetting {
+    Sysfs(Vec<(String, String)>),
+    NvidiaPowerLimit { index: u32, milliwatts: u32 },
+    NvidiaPersistence { index: u32, enabled: bool },
+}
+
+// Original values keyed by the control they belong to; restored on exit
+static ORIGINALS: Mutex<Vec<(String, GpuSetting)>> = Mutex::new(Vec::new());
+
+fn read_u32(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok().and_then(|s| s.trim().parse().ok())
+}
+
+pub fn read_amd_controls(device_path: &str) -> AmdGpuControls {
+    let device = Path::new(device_path);
+    let hwmon = fs::read_dir(device.join("hwmon")).ok()
+        .and_then(|mut d| d.next())
+        .and_then(|e| e.ok())
+        .map(|e| e.path());
+    // hwmon reports power in µW
+    let hwmon_mw = |name: &str| hwmon.as_ref().and_then(|h| read_u32(&h.join(name))).map(|uw| uw / 1000);
+
+    AmdGpuControls {
+        device_path: device_path.to_string(),
+        hwmon_path: hwmon.as_ref().map(|h| h.to_string_lossy().to_string()),
+        performance_level: fs::read_to_string(device.join("power_dpm_force_performance_level")).ok().map(|s| s.trim().to_string()),
+        power_cap: hwmon_mw("power1_cap"),
+        power_cap_min: hwmon_mw("power1_cap_min"),
+        power_cap_max: hwmon_mw("power1_cap_max"),
+        power_cap_default: hwmon_mw("power1_cap_default"),
+        pwm_enable: hwmon.as_ref().and_then(|h| read_u32(&h.join("pwm1_enable"))),
+        pwm: hwmon.as_ref().and_then(|h| read_u32(&h.join("pwm1"))),
+        fan_curve: fs::read_to_string(device.join(FAN_CURVE_FILE)).ok().and_then(|c| parse_fan_curve(&c)),
+    }
+}
+
+pub fn read_nvidia_controls(index: u32) -> Result<NvidiaGpuControls, String> {
+    crate::monitors::nvml::Nvml::load()?.controls(index)
+}
+
+/// Parses the OD_FAN_CURVE / OD_RANGE listing from amdgpu's fan_curve attribute.
+pub fn parse_fan_curve(content: &str) -> Option<FanCurve> {
+    let number = |s: &str| s.trim_end_matches(['C', '%']).parse::<u32>().ok();
+    let mut curve = FanCurve::default();
+    for line in content.lines().map(str::trim) {
+        if let Some(range) = line.strip_prefix("FAN_CURVE(") {
+            let (kind, values) = range.split_once("):")?;
+            let mut v = values.split_whitespace().filter_map(number);
+            let pair = (v.next()?, v.next()?);
+            if kind.contains("temp") {
+                curve.temp_range = pair;
+            } else {
+                curve.speed_range = pair;
+            }
+        } else if let Some((idx, rest)) = line.split_once(':') {
+            if idx.parse::<u32>().is_err() {
+                continue;
+            }
+            let mut v = rest.split_whitespace().filter_map(number);
+            curve.points.push((v.next()?, v.next()?));
+        }
+    }
+    (!curve.points.is_empty()).then_some(curve)
+}
+
+/// Parses "40:30, 60:50, ..." (°C:%) for a curve with the card's fixed point count.
+pub fn parse_curve_points(spec: &str, curve: &FanCurve) -> Result<Vec<(u32, u32)>, String> {
+    let points = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (t, s) = p.split_once(':').ok_or_else(|| format!("'{}' is not temp:percent", p))?;
+            let t = t.trim().trim_end_matches('C').parse::<u32>().map_err(|_| format!("bad temperature '{}'", t))?;
+            let s = s.trim().trim_end_matches('%').parse::<u32>().map_err(|_| format!("bad speed '{}'", s))?;
+            Ok((t, s))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if points.len() != curve.points.len() {
+        return Err(format!("the card takes exactly {} points, got {}", curve.points.len(), points.len()));
+    }
+    let ((tmin, tmax), (smin, smax)) = (curve.temp_range, curve.speed_range);
+    for (t, s) in &points {
+        if *t < tmin || *t > tmax {
+            return Err(format!("{}°C is outside {}-{}°C", t, tmin, tmax));
+        }
+        if *s < smin || *s > smax {
+            return Err(format!("{}% is outside {}-{}%", s, smin, smax));
+        }
+    }
+    if points.windows(2).any(|w| w[1].0 < w[0].0 || w[1].1 < w[0].1) {
+        return Err("temperatures and speeds must not decrease".to_string());
+    }
+    Ok(points)
+}
+
+pub fn performance_level_setting(c: &AmdGpuControls, level: &str) -> GpuSetting {
+    GpuSetting::Sysfs(vec![(format!("{}/power_dpm_force_performance_level", c.device_path), level.to_string())])
+}
+
+pub fn power_cap_setting(c: &AmdGpuControls, milliwatts: u32) -> Result<GpuSetting, String> {
+    let hwmon = c.hwmon_path.as_ref().ok_or("no hwmon device")?;
+    if let (Some(min), Some(max)) = (c.power_cap_min, c.power_cap_max) {
+        if milliwatts < min || milliwatts > max {
+            return Err(format!("{} W is outside {}-{} W", milliwatts / 1000, min / 1000, max / 1000));
+        }
+    }
+    Ok(GpuSetting::Sysfs(vec![(format!("{}/power1_cap", hwmon), (milliwatts as u64 * 1000).to_string())]))
+}
+
+/// `percent` of None hands the fan back to the firmware (pwm1_enable = 2).
+pub fn fan_setting(c: &AmdGpuControls, percent: Option<u32>) -> Result<GpuSetting, String> {
+    let hwmon = c.hwmon_path.as_ref().ok_or("no hwmon device")?;
+    let enable = format!("{}/pwm1_enable", hwmon);
+    Ok(GpuSetting::Sysfs(match percent {
+        Some(p) => vec![
+            (enable, "1".to_string()),
+            (format!("{}/pwm1", hwmon), (p.min(100) * 255 / 100).to_string()),
+        ],
+        None => vec![(enable, "2".to_string())],
+    }))
+}
+
+pub fn fan_curve_setting(c: &AmdGpuControls, points: &[(u32, u32)]) -> GpuSetting {
+    let path = format!("{}/{}", c.device_path, FAN_CURVE_FILE);
+    let mut writes: Vec<(String, String)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, (t, s))| (path.clone(), format!("{} {} {}", i, t, s)))
+        .collect();
+    writes.push((path, "c".to_string()));
+    GpuSetting::Sysfs(writes)
+}
+
+/// What each control looks like right now, so a change can be undone.
+pub fn original_fan_setting(c: &AmdGpuControls) -> Option<GpuSetting> {
+    let hwmon = c.hwmon_path.as_ref()?;
+    let enable = c.pwm_enable?;
+    let mut writes = vec![(format!("{}/pwm1_enable", hwmon), enable.to_string())];
+    if enable == 1 {
+        writes.push((format!("{}/pwm1", hwmon), c.pwm?.to_string()));
+    }
+    Some(GpuSetting::Sysfs(writes))
+}
+
+fn write(mgr: &SystemManager, setting: &GpuSetting) -> Result<(), String> {
+    match setting {
+        GpuSetting::Sysfs(writes) => mgr.write_sysfs(writes),
+        GpuSetting::NvidiaPowerLimit { index, milliwatts } => mgr.nvidia_set_power_limit(*index, *milliwatts),
+        GpuSetting::NvidiaPersistence { index, enabled } => mgr.nvidia_set_persistence(*index, *enabled),
+    }
+}
+
+/// Applies `setting` and, the first time `key` is changed, remembers `original`
+/// for [`restore_all`].
+pub fn apply(key: &str, setting: &GpuSetting, original: Option<GpuSetting>) -> Result<(), String> {
+    write(&SystemManager::new(), setting)?;
+    let mut originals = ORIGINALS.lock();
+    if let Some(original) = original {
+        if !originals.iter().any(|(k, _)| k == key) {
+            originals.push((key.to_string(), original));
+        }
+    }
+    Ok(())
+}
+
+pub fn has_changes() -> bool {
+    !ORIGINALS.lock().is_empty()
+}
+
+/// Puts back everything changed this session, newest first, with a single
+/// privilege prompt. Returns the failures.
+pub fn restore_all() -> Vec<String> {
+    let mut originals: Vec<(String, GpuSetting)> = std::mem::take(&mut *ORIGINALS.lock());
+    originals.reverse();
+    let mgr = SystemManager::new();
+    if !mgr.has_sudo_privileges() {
+        return mgr.write_gpu_settings(&originals);
+    }
+    originals
+        .iter()
+        .filter_map(|(key, setting)| write(&mgr, setting).err().map(|e| format!("{}: {}", key, e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fan_curve() {
+        let content = "OD_FAN_CURVE:\n0: 0C 0%\n1: 45C 15%\n2: 60C 35%\n3: 75C 60%\n4: 90C 100%\nOD_RANGE:\nFAN_CURVE(hotspot temp): 25C 100C\nFAN_CURVE(fan speed): 15% 100%\n";
+        let curve = parse_fan_curve(content).unwrap();
+        assert_eq!(curve.points, vec![(0, 0), (45, 15), (60, 35), (75, 60), (90, 100)]);
+        assert_eq!(curve.temp_range, (25, 100));
+        assert_eq!(curve.speed_range, (15, 100));
+
+        assert_eq!(
+            parse_curve_points("30:15, 50C:30%, 65:50, 80:80, 95:100", &curve),
+            Ok(vec![(30, 15), (50, 30), (65, 50), (80, 80), (95, 100)])
+        );
+        assert!(parse_curve_points("30:15, 50:30", &curve).is_err());
+        assert!(parse_curve_points("20:15, 50:30, 65:50, 80:80, 95:100", &curve).is_err());
+        assert!(parse_curve_points("30:15, 50:30, 45:50, 80:80, 95:100", &curve).is_err());
+
+        let controls = AmdGpuControls { device_path: "/sys/class/drm/card1/device".to_string(), ..Default::default() };
+        let GpuSetting::Sysfs(writes) = fan_curve_setting(&controls, &[(30, 15), (50, 30)]) else { panic!() };
+        let values: Vec<&str> = writes.iter().map(|(_, v)| v.as_str()).collect();
+        assert_eq!(values, vec!["0 30 15", "1 50 30", "c"]);
+        assert!(writes.iter().all(|(p, _)| p == "/sys/class/drm/card1/device/gpu_od/fan_ctrl/fan_curve"));
+    }
+}
@@ -0,0 +1,147 @@
+use gtk::prelude::*;
+use gtk::DrawingArea;
+use std::sync::Arc;
+use parking_lot::Mutex;
+use crate::types::AppState;
+
+pub type Rgb = (f64, f64, f64);
+
+pub const GREEN: Rgb = (0.0, 0.8, 0.45);
+pub const CYAN: Rgb = (0.0, 0.75, 0.85);
+pub const MAGENTA: Rgb = (0.85, 0.35, 0.85);
+pub const ORANGE: Rgb = (1.0, 0.6, 0.2);
+pub const RED: Rgb = (0.9, 0.25, 0.25);
+pub const YELLOW: Rgb = (0.9, 0.8, 0.2);
+pub const BLUE: Rgb = (0.3, 0.45, 0.95);
+pub const GREY: Rgb = (0.45, 0.45, 0.45);
+
+/// Builds a line chart that pulls its series from the shared state on every redraw.
+/// `max` fixes the vertical scale; `None` scales to the largest visible sample.
+pub fn build_history_chart<F>(
+    state: Arc<Mutex<AppState>>,
+    name: &str,
+    height: i32,
+    colors: &[Rgb],
+    max: Option<f64>,
+    series: F,
+) -> DrawingArea
+where
+    F: Fn(&AppState) -> Vec<Vec<f64>> + 'static,
+{
+    let area = DrawingArea::new();
+    area.set_widget_name(name);
+    area.set_size_request(-1, height);
+    area.set_hexpand(true);
+
+    let colors = colors.to_vec();
+    area.connect_draw(move |widget, cr| {
+        let data = {
+            let s = state.lock();
+            series(&s)
+        };
+        draw_series(widget, cr, &data, &colors, max);
+        glib::Propagation::Stop
+    });
+
+    area
+}
+
+/// Builds a horizontal bar split into proportional segments, drawn left to right.
+pub fn build_stacked_bar<F>(state: Arc<Mutex<AppState>>, name: &str, height: i32, segments: F) -> DrawingArea
+where
+    F: Fn(&AppState) -> Vec<(f64, Rgb)> + 'static,
+{
+    let area = DrawingArea::new();
+    area.set_widget_name(name);
+    area.set_size_request(-1, height);
+    area.set_hexpand(true);
+
+    area.connect_draw(move |widget, cr| {
+        let data = {
+            let s = state.lock();
+            segments(&s)
+        };
+        let width = widget.allocated_width() as f64;
+        let height = widget.allocated_height() as f64;
+        let total: f64 = data.iter().map(|(v, _)| v.max(0.0)).sum();
+
+        cr.set_source_rgba(0.5, 0.5, 0.5, 0.2);
+        cr.rectangle(0.0, 0.0, width, height);
+        let _ = cr.fill();
+
+        if total > 0.0 {
+            let mut x = 0.0;
+            for (value, (r, g, b)) in data {
+                let w = value.max(0.0) / total * width;
+                cr.set_source_rgb(r, g, b);
+                cr.rectangle(x, 0.0, w, height);
+                let _ = cr.fill();
+                x += w;
+            }
+        }
+        glib::Propagation::Stop
+    });
+
+    area
+}
+
+/// Queues a redraw for a chart or bar found by widget name.
+pub fn refresh_chart(container: &gtk::Container, name: &str) {
+    if let Some(w) = crate::gui::dashboard::find_widget_by_name(container, name) {
+        w.queue_draw();
+    }
+}
+
+fn draw_series(widget: &DrawingArea, cr: &gtk::cairo::Context, data: &[Vec<f64>], colors: &[Rgb], max: Option<f64>) {
+    let width = widget.allocated_width() as f64;
+    let height = widget.allocated_height() as f64;
+    if width <= 2.0 || height <= 2.0 {
+        return;
+    }
+
+    let scale_max = max.unwrap_or_else(|| {
+        data.iter()
+            .flat_map(|s| s.iter().copied())
+            .fold(0.0_f64, f64::max)
+    }).max(f64::EPSILON);
+
+    cr.set_source_rgba(0.5, 0.5, 0.5, 0.25);
+    cr.set_line_width(1.0);
+    for i in 1..4 {
+        let y = (height * i as f64 / 4.0).round() + 0.5;
+        cr.move_to(0.0, y);
+        cr.line_to(width, y);
+    }
+    let _ = cr.stroke();
+
+    for (idx, values) in data.iter().enumerate() {
+        if values.len() < 2 {
+            continue;
+        }
+        let (r, g, b) = colors.get(idx).copied().unwrap_or(GREEN);
+        let step = width / (values.len() - 1) as f64;
+        let point = |i: usize, v: f64| (i as f64 * step, height - (v / scale_max).clamp(0.0, 1.0) * (height - 1.0));
+
+        cr.move_to(0.0, height);
+        for (i, v) in values.iter().enumerate() {
+            let (x, y) = point(i, *v);
+            cr.line_to(x, y);
+        }
+        cr.line_to(width, height);
+        cr.close_path();
+        cr.set_source_rgba(r, g, b, 0.15);
+        let _ = cr.fill();
+
+        for (i, v) in values.iter().enumerate() {
+            let (x, y) = point(i, *v);
+            if i == 0 {
+                cr.move_to(x, y);
+            } else {
+                cr.line_to(x, y);
+            }
+        }
+        cr.set_source_rgb(r, g, b);
+        cr.set_line_width(1.5);
+        let _ = cr.stroke();
+    }
+}
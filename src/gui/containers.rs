@@ -3,6 +3,7 @@ use gtk::{Box, Orientation, TreeView, TreeViewColumn, CellRendererText, ListStor
 use std::sync::Arc;
 use parking_lot::Mutex;
 use crate::types::AppState;
+use crate::utils::format_pressure;
 
 pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 0);
@@ -18,6 +19,7 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
         glib::Type::STRING, // Status
         glib::Type::STRING, // CPU
         glib::Type::STRING, // MEM
+        glib::Type::STRING, // PSI
     ]);
 
     let tree = TreeView::with_model(&store);
@@ -30,6 +32,7 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
         ("Status", 3),
         ("CPU", 4),
         ("MEM", 5),
+        ("PSI cpu/mem/io", 6),
     ];
     
     for (title, col_id) in cols.iter() {
@@ -74,6 +77,10 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
             (3, &c.status), 
             (4, &c.cpu),
             (5, &c.mem),
+            (6, &c.pressure.map(|p| format!(
+                "{} / {} / {}",
+                format_pressure(p.cpu), format_pressure(p.memory), format_pressure(p.io)
+            )).unwrap_or_else(|| "-".to_string())),
         ]);
     }
 }
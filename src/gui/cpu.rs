@@ -2,8 +2,8 @@ use gtk::prelude::*;
 use gtk::{Box, Orientation, Label, ProgressBar, Frame, Widget, Grid, ScrolledWindow};
 use std::sync::Arc;
 use parking_lot::Mutex;
-use crate::types::AppState;
-use crate::utils::{format_frequency};
+use crate::types::{AppState, CoreKind, CpuDomain, PhysicalCore};
+use crate::utils::{format_frequency, format_size};
 
 pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
@@ -28,14 +28,10 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let grid_box = Box::new(Orientation::Vertical, 5);
     grid_box.set_border_width(8);
     
-    let grid = Grid::builder()
-        .row_spacing(5)
-        .column_spacing(5)
-        .column_homogeneous(true)
-        .build();
-    grid.set_widget_name("cores_grid");
+    let topology_box = Box::new(Orientation::Vertical, 8);
+    topology_box.set_widget_name("cores_topology_box");
     
-    grid_box.pack_start(&grid, true, true, 0);
+    grid_box.pack_start(&topology_box, true, true, 0);
     grid_frame.add(&grid_box);
     container.pack_start(&grid_frame, true, true, 0);
 
@@ -57,59 +53,117 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
     let info_lbl = crate::gui::dashboard::find_widget_by_name(&container.clone().upcast::<gtk::Container>(), "cpu_info_lbl")
         .and_then(|w| w.downcast::<Label>().ok());
 
-    let cores_grid = crate::gui::dashboard::find_widget_by_name(&container.clone().upcast::<gtk::Container>(), "cores_grid")
-        .and_then(|w| w.downcast::<Grid>().ok());
+    let topology_box = crate::gui::dashboard::find_widget_by_name(&container.clone().upcast::<gtk::Container>(), "cores_topology_box")
+        .and_then(|w| w.downcast::<Box>().ok());
 
     let s = state.lock();
     let usage = &s.dynamic_data.global_usage;
     let cores = &s.dynamic_data.cores;
+    let topology = &s.cpu_topology;
     let cpu_model = s.system_info.iter().find(|(k, _)| k == "CPU").map(|(_, v)| v.as_str()).unwrap_or("Unknown CPU");
 
+    let avg_usage = |threads: &mut dyn Iterator<Item = usize>| -> Option<f32> {
+        let values: Vec<f32> = threads.filter_map(|t| cores.get(t).map(|c| c.usage)).collect();
+        if values.is_empty() { None } else { Some(values.iter().sum::<f32>() / values.len() as f32) }
+    };
+
     if let Some(lbl) = info_lbl {
         let temp_str = s.dynamic_data.temperatures.cpu_temp
             .map(|t| format!("{:.1}°C", t))
             .unwrap_or_else(|| "N/A".to_string());
+
+        let hybrid_str = if topology.is_hybrid() {
+            let p = avg_usage(&mut topology.cpus.iter().filter(|c| c.kind == CoreKind::Performance).map(|c| c.id));
+            let e = avg_usage(&mut topology.cpus.iter().filter(|c| c.kind == CoreKind::Efficiency).map(|c| c.id));
+            format!("\nP-cores: {:.1}% | E-cores: {:.1}%", p.unwrap_or(0.0), e.unwrap_or(0.0))
+        } else {
+            String::new()
+        };
             
         lbl.set_text(&format!(
-            "Model: {}\nCores: {} Logical | Usage: {:.1}%\nEfficiency: {}\nTemperature: {}\nLoad Average: {:.2} {:.2} {:.2}",
+            "Model: {}\nCores: {} Logical | Usage: {:.1}%\nTopology: {}\nCaches: {}{}\nEfficiency: {}\nTemperature: {}\nLoad Average: {:.2} {:.2} {:.2}",
             cpu_model,
             cores.len(),
             usage.cpu,
+            topology.summary(),
+            topology.cache_summary(),
+            hybrid_str,
             crate::utils::get_cpu_efficiency(usage.cpu, usage.load_average.0, cores.len()),
             temp_str,
             usage.load_average.0, usage.load_average.1, usage.load_average.2
         ));
     }
 
-    if let Some(grid) = cores_grid {
-        grid.forall(|child| {
-            grid.remove(child);
+    if let Some(topology_box) = topology_box {
+        topology_box.forall(|child| {
+            topology_box.remove(child);
         });
 
-        let columns = 8;
-        for (i, core) in cores.iter().enumerate() {
-            let row = i as i32 / columns;
-            let col = i as i32 % columns;
-
-            let core_box = Box::new(Orientation::Vertical, 2);
-            let core_frame = Frame::new(None);
-            
-            let temp_display = core.temp.map(|t| format!("{:.0}°C", t)).unwrap_or_default();
-            let label_text = format!("C{} {} {:.1}% {}", i, format_frequency(core.freq), core.usage, temp_display);
-
-            let lbl = Label::new(Some(&label_text));
-            lbl.style_context().add_class("text-green");
-            lbl.set_halign(gtk::Align::Center);
-
-            let bar = ProgressBar::new();
-            bar.set_fraction((core.usage / 100.0).clamp(0.0, 1.0) as f64);
+        let mut domains = topology.domains();
+        if domains.is_empty() {
+            domains.push(CpuDomain {
+                label: "All Cores".to_string(),
+                cores: (0..cores.len()).map(|i| PhysicalCore {
+                    core_id: i as u32,
+                    threads: vec![i],
+                    ..Default::default()
+                }).collect(),
+                ..Default::default()
+            });
+        }
 
-            core_box.pack_start(&lbl, false, false, 0);
-            core_box.pack_start(&bar, false, false, 0);
-            core_frame.add(&core_box);
-            
-            grid.attach(&core_frame, col, row, 1, 1);
+        let columns = 8;
+        for domain in &domains {
+            let domain_avg = avg_usage(&mut domain.cores.iter().flat_map(|c| c.threads.iter().copied())).unwrap_or(0.0);
+            let l3_str = domain.l3_size.map(|l3| format!(" | L3 {}", format_size(l3))).unwrap_or_default();
+            let domain_frame = Frame::new(Some(&format!(" {}{} | {:.1}% ", domain.label, l3_str, domain_avg)));
+
+            let grid = Grid::builder()
+                .row_spacing(5)
+                .column_spacing(5)
+                .column_homogeneous(true)
+                .build();
+            grid.set_border_width(5);
+
+            for (i, core) in domain.cores.iter().enumerate() {
+                let row = i as i32 / columns;
+                let col = i as i32 % columns;
+
+                let kind_tag = match core.kind {
+                    CoreKind::Performance => " [P]",
+                    CoreKind::Efficiency => " [E]",
+                    CoreKind::Standard => "",
+                };
+                let core_frame = Frame::new(Some(&format!("Core {}{}", core.core_id, kind_tag)));
+                let core_box = Box::new(Orientation::Vertical, 2);
+                core_box.set_border_width(2);
+
+                for &thread in &core.threads {
+                    let info = match cores.get(thread) {
+                        Some(c) => c,
+                        None => continue,
+                    };
+                    let temp_display = info.temp.map(|t| format!("{:.0}°C", t)).unwrap_or_default();
+                    let label_text = format!("C{} {} {:.1}% {}", thread, format_frequency(info.freq), info.usage, temp_display);
+
+                    let lbl = Label::new(Some(&label_text));
+                    lbl.style_context().add_class(if core.kind == CoreKind::Efficiency { "text-cyan" } else { "text-green" });
+                    lbl.set_halign(gtk::Align::Center);
+
+                    let bar = ProgressBar::new();
+                    bar.set_fraction((info.usage / 100.0).clamp(0.0, 1.0) as f64);
+
+                    core_box.pack_start(&lbl, false, false, 0);
+                    core_box.pack_start(&bar, false, false, 0);
+                }
+                core_frame.add(&core_box);
+                
+                grid.attach(&core_frame, col, row, 1, 1);
+            }
+
+            domain_frame.add(&grid);
+            topology_box.pack_start(&domain_frame, false, false, 0);
         }
-        grid.show_all();
+        topology_box.show_all();
     }
 }
@@ -4,9 +4,10 @@ use gtk::{Box, Orientation, Label, Widget, Frame, ScrolledWindow,
 use std::sync::Arc;
 use parking_lot::Mutex;
 use crate::types::AppState;
-use crate::utils::{format_size, format_uptime};
+use crate::utils::{format_size, format_uptime, format_pressure, get_pressure_status, get_system_health};
+use crate::gui::charts;
 
-pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 4);
     container.set_border_width(6);
 
@@ -19,6 +20,43 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     status_frame.add(&status_lbl);
     container.pack_start(&status_frame, false, false, 0);
 
+    let storage_frame = Frame::new(Some(" Storage Health "));
+    storage_frame.set_widget_name("dashboard_storage_frame");
+    storage_frame.set_no_show_all(true);
+    let storage_lbl = Label::new(None);
+    storage_lbl.set_widget_name("dashboard_storage_lbl");
+    storage_lbl.set_line_wrap(true);
+    storage_lbl.set_xalign(0.0);
+    storage_lbl.set_margin_start(6);
+    storage_lbl.show();
+    storage_frame.add(&storage_lbl);
+    container.pack_start(&storage_frame, false, false, 0);
+
+    let psi_frame = Frame::new(Some(" Pressure Stall (some avg10 %) "));
+    let psi_box = Box::new(Orientation::Horizontal, 6);
+    psi_box.set_border_width(4);
+    let charts = [
+        ("CPU", "dashboard_psi_cpu_chart", charts::GREEN),
+        ("Memory", "dashboard_psi_mem_chart", charts::CYAN),
+        ("I/O", "dashboard_psi_io_chart", charts::ORANGE),
+    ];
+    for (idx, (title, name, color)) in charts.into_iter().enumerate() {
+        let frame = Frame::new(Some(title));
+        let chart = charts::build_history_chart(state.clone(), name, 60, &[color], Some(100.0), move |s| {
+            let usage = &s.dynamic_data.global_usage;
+            let history = match idx {
+                0 => &usage.cpu_pressure_history,
+                1 => &usage.mem_pressure_history,
+                _ => &usage.io_pressure_history,
+            };
+            vec![history.iter().map(|v| *v as f64).collect()]
+        });
+        frame.add(&chart);
+        psi_box.pack_start(&frame, true, true, 0);
+    }
+    psi_frame.add(&psi_box);
+    container.pack_start(&psi_frame, false, false, 0);
+
     let proc_frame = Frame::new(Some(" Processes "));
     let proc_scroll = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
     proc_scroll.set_vexpand(true);
@@ -97,15 +135,52 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         let mem_pct = if usage.mem_total > 0 { usage.mem_used as f64 / usage.mem_total as f64 * 100.0 } else { 0.0 };
         let swap_pct = if usage.swap_total > 0 { usage.swap_used as f64 / usage.swap_total as f64 * 100.0 } else { 0.0 };
         let temp = s.dynamic_data.temperatures.cpu_temp.map(|t| format!(" | {:.0}°C", t)).unwrap_or_default();
+        let (status, psi) = match &usage.pressure {
+            Some(p) => (
+                get_pressure_status(p).0,
+                format!(" | PSI cpu {} mem {} io {}", format_pressure(p.cpu), format_pressure(p.memory), format_pressure(p.io)),
+            ),
+            None => (
+                get_system_health(usage.load_average.0, cores, usage.mem_used, usage.mem_total).0,
+                String::new(),
+            ),
+        };
         lbl.set_text(&format!(
-            "Status [IDLE/HEALTHY] | CPU: {:.0}% (Eff: GOOD){} | Load: {:.2}/core | Mem: {:.0}% ({}) | Swap: {:.0}% | Up: {} | Procs: {}",
-            usage.cpu, temp,
+            "Status [{}]{} | CPU: {:.0}% (Eff: GOOD){} | Load: {:.2}/core | Mem: {:.0}% ({}) | Swap: {:.0}% | Up: {} | Procs: {}",
+            status, psi, usage.cpu, temp,
             usage.load_average.0 / cores.max(1) as f64,
             mem_pct, format_size(usage.mem_total.saturating_sub(usage.mem_used)),
             swap_pct, format_uptime(usage.uptime), s.dynamic_data.processes.len()
         ));
     }
 
+    // Only shown on hosts with md arrays, btrfs or ZFS
+    if let (Some(frame), Some(lbl)) = (
+        find_widget_by_name(&container, "dashboard_storage_frame"),
+        find_widget_by_name(&container, "dashboard_storage_lbl").and_then(|w| w.downcast::<Label>().ok()),
+    ) {
+        let health = &s.dynamic_data.storage_health;
+        let count = health.md_arrays.len() + health.btrfs.len() + health.zfs_pools.len();
+        frame.set_visible(count > 0);
+        let ctx = lbl.style_context();
+        if health.warnings.is_empty() {
+            lbl.set_text(&format!(
+                "[OK] {} md array(s), {} btrfs filesystem(s), {} ZFS pool(s) healthy",
+                health.md_arrays.len(), health.btrfs.len(), health.zfs_pools.len()
+            ));
+            ctx.remove_class("text-red");
+            ctx.add_class("text-green");
+        } else {
+            lbl.set_text(&format!("[FAIL] {}", health.warnings.join(" | ")));
+            ctx.remove_class("text-green");
+            ctx.add_class("text-red");
+        }
+    }
+
+    for name in ["dashboard_psi_cpu_chart", "dashboard_psi_mem_chart", "dashboard_psi_io_chart"] {
+        charts::refresh_chart(&container, name);
+    }
+
     if let Some(tree) = find_widget_by_name(&container, "dashboard_proc_tree")
         .and_then(|w| w.downcast::<TreeView>().ok())
     {
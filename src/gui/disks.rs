@@ -1,12 +1,27 @@
 use gtk::prelude::*;
-use gtk::{Box, Orientation, Widget, ScrolledWindow, Frame,
-          TreeView, TreeViewColumn, CellRendererText, ListStore};
+use gtk::{Box, Orientation, Widget, ScrolledWindow, Frame, Button, Label, TextView, ComboBoxText,
+          TreeView, TreeViewColumn, CellRendererText, ListStore, TreeStore, TreeIter};
+use glib::{clone, ControlFlow};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 use parking_lot::Mutex;
-use crate::types::AppState;
+use crate::monitors::dirsize::{self, DirScan};
+use crate::types::{AppState, BlockDevice, DirUsage};
 use crate::utils::format_size;
 
-pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
+thread_local! {
+    // Last block tree shown, so the TreeStore is only rebuilt (and re-expanded) on change
+    static SHOWN_BLOCK_TREE: RefCell<Vec<BlockDevice>> = const { RefCell::new(Vec::new()) };
+    static SHOWN_MOUNTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    // Last directory-size snapshot shown; a running scan updates its rows in place
+    static SHOWN_DIR_USAGE: RefCell<Option<DirUsage>> = const { RefCell::new(None) };
+}
+
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 5);
     container.set_border_width(10);
 
@@ -24,8 +39,14 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
         glib::Type::STRING, // Used
         glib::Type::STRING, // Free
         glib::Type::STRING, // Use%
+        glib::Type::STRING, // Inodes
+        glib::Type::STRING, // IUse%
         glib::Type::STRING, // Read Rate
         glib::Type::STRING, // Write Rate
+        glib::Type::STRING, // IOPS
+        glib::Type::STRING, // Latency
+        glib::Type::STRING, // Queue
+        glib::Type::STRING, // Util%
         glib::Type::STRING, // Temp
         glib::Type::STRING, // Health
         glib::Type::STRING, // Cycles
@@ -36,9 +57,10 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
 
     for (title, id) in &[
         ("Mount", 0), ("Device", 1), ("FS", 2), ("Total", 3),
-        ("Used", 4), ("Free", 5), ("Use%", 6), 
-        ("Read", 7), ("Write", 8),
-        ("Temp", 9), ("Health", 10), ("Cycles", 11), ("Type", 12),
+        ("Used", 4), ("Free", 5), ("Use%", 6), ("Inodes", 7), ("IUse%", 8),
+        ("Read", 9), ("Write", 10), ("IOPS R/W", 11), ("Latency R/W", 12),
+        ("Queue", 13), ("Util%", 14),
+        ("Temp", 15), ("Health", 16), ("Cycles", 17), ("Type", 18),
     ] {
         let col = TreeViewColumn::new();
         col.set_title(title);
@@ -52,10 +74,511 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     scrolled.add(&tree);
     frame.add(&scrolled);
     container.pack_start(&frame, true, true, 0);
+    container.pack_start(&build_space_explorer(), false, false, 0);
+
+    let block_frame = Frame::new(Some(" Block Devices "));
+    let block_store = TreeStore::new(&[
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Size
+        glib::Type::STRING, // FS
+        glib::Type::STRING, // Mount
+        glib::Type::STRING, // Model
+        glib::Type::STRING, // Serial
+        glib::Type::STRING, // Transport
+        glib::Type::STRING, // Scheduler
+    ]);
+    let block_tree = TreeView::with_model(&block_store);
+    block_tree.set_widget_name("block_devices_tree");
+    for (title, id) in &[
+        ("Name", 0), ("Type", 1), ("Size", 2), ("FS", 3), ("Mount", 4),
+        ("Model", 5), ("Serial", 6), ("Transport", 7), ("Scheduler", 8),
+    ] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        block_tree.append_column(&col);
+    }
+    let block_box = Box::new(Orientation::Vertical, 5);
+    block_box.set_border_width(5);
+    let action_box = Box::new(Orientation::Horizontal, 5);
+    let mount_btn = Button::with_label("Mount");
+    let unmount_btn = Button::with_label("Unmount");
+    let remount_ro_btn = Button::with_label("Remount Read-Only");
+    let eject_btn = Button::with_label("Eject");
+    mount_btn.style_context().add_class("suggested-action");
+    eject_btn.style_context().add_class("destructive-action");
+    action_box.pack_start(&mount_btn, false, false, 0);
+    action_box.pack_start(&unmount_btn, false, false, 0);
+    action_box.pack_start(&remount_ro_btn, false, false, 0);
+    action_box.pack_start(&eject_btn, false, false, 0);
+    block_box.pack_start(&action_box, false, false, 0);
+    super::disable_in_safe_mode(&state, &[&mount_btn, &unmount_btn, &remount_ro_btn, &eject_btn]);
+
+    let block_status_lbl = Label::new(Some("Select a device and use the buttons above."));
+    block_status_lbl.set_halign(gtk::Align::Start);
+    block_status_lbl.style_context().add_class("text-cyan");
+    block_box.pack_start(&block_status_lbl, false, false, 0);
+
+    let block_scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    block_scrolled.set_min_content_height(160);
+    block_scrolled.add(&block_tree);
+    block_box.pack_start(&block_scrolled, true, true, 0);
+    block_frame.add(&block_box);
+    container.pack_start(&block_frame, false, false, 0);
+
+    // (device path, first mount point) of the selected Block Devices row
+    fn get_selected_device(tree: &TreeView) -> Option<(String, Option<String>)> {
+        tree.selection().selected().and_then(|(model, iter)| {
+            let name = model.value(&iter, 0).get::<String>().ok()?;
+            let mounts = model.value(&iter, 4).get::<String>().unwrap_or_default();
+            let mount = mounts.split(", ").find(|m| m.starts_with('/')).map(|m| m.to_string());
+            Some((format!("/dev/{}", name), mount))
+        })
+    }
+
+    mount_btn.connect_clicked(clone!(@strong block_tree, @strong block_status_lbl => move |_| {
+        match get_selected_device(&block_tree) {
+            Some((_, Some(mount))) => block_status_lbl.set_text(&format!("Already mounted at {}", mount)),
+            Some((device, None)) => {
+                let mgr = crate::system_service::SystemManager::new();
+                match mgr.mount_device(&device) {
+                    Ok(target) => block_status_lbl.set_text(&format!("[OK] Mounted {} at {}", device, target)),
+                    Err(e) => block_status_lbl.set_text(&format!("[FAIL] Failed to mount {}: {}", device, e)),
+                }
+            }
+            None => block_status_lbl.set_text("No device selected"),
+        }
+    }));
+
+    unmount_btn.connect_clicked(clone!(@strong block_tree, @strong block_status_lbl => move |btn| {
+        match get_selected_device(&block_tree) {
+            Some((device, Some(mount))) => {
+                if !super::confirm(btn, &format!("Unmount {}?", mount), Some("Programs using files on it may lose unsaved data.")) {
+                    return;
+                }
+                let mgr = crate::system_service::SystemManager::new();
+                match mgr.unmount(&mount) {
+                    Ok(()) => block_status_lbl.set_text(&format!("[OK] Unmounted {}", mount)),
+                    Err(e) => block_status_lbl.set_text(&format!("[FAIL] Failed to unmount {}: {}", device, e)),
+                }
+            }
+            Some((device, None)) => block_status_lbl.set_text(&format!("{} is not mounted", device)),
+            None => block_status_lbl.set_text("No device selected"),
+        }
+    }));
+
+    remount_ro_btn.connect_clicked(clone!(@strong block_tree, @strong block_status_lbl => move |btn| {
+        match get_selected_device(&block_tree) {
+            Some((_, Some(mount))) => {
+                if !super::confirm(btn, &format!("Remount {} read-only?", mount), Some("Writes in progress will fail until it is remounted read-write.")) {
+                    return;
+                }
+                let mgr = crate::system_service::SystemManager::new();
+                match mgr.remount_read_only(&mount) {
+                    Ok(()) => block_status_lbl.set_text(&format!("[OK] Remounted {} read-only", mount)),
+                    Err(e) => block_status_lbl.set_text(&format!("[FAIL] Failed to remount {}: {}", mount, e)),
+                }
+            }
+            Some((device, None)) => block_status_lbl.set_text(&format!("{} is not mounted", device)),
+            None => block_status_lbl.set_text("No device selected"),
+        }
+    }));
+
+    eject_btn.connect_clicked(clone!(@strong block_tree, @strong block_status_lbl => move |btn| {
+        if let Some((device, _)) = get_selected_device(&block_tree) {
+            if !super::confirm(btn, &format!("Eject {}?", device), Some("Make sure nothing is still writing to it.")) {
+                return;
+            }
+            let mgr = crate::system_service::SystemManager::new();
+            match mgr.eject(&device) {
+                Ok(()) => block_status_lbl.set_text(&format!("[OK] Ejected {}", device)),
+                Err(e) => block_status_lbl.set_text(&format!("[FAIL] Failed to eject {}: {}", device, e)),
+            }
+        } else {
+            block_status_lbl.set_text("No device selected");
+        }
+    }));
+
+    let fstab_frame = Frame::new(Some(" /etc/fstab "));
+    let fstab_box = Box::new(Orientation::Vertical, 5);
+    fstab_box.set_border_width(5);
+    let fstab_actions = Box::new(Orientation::Horizontal, 5);
+    let reload_btn = Button::with_label("Reload");
+    let validate_btn = Button::with_label("Validate");
+    let save_btn = Button::with_label("Save");
+    save_btn.style_context().add_class("suggested-action");
+    fstab_actions.pack_start(&reload_btn, false, false, 0);
+    fstab_actions.pack_start(&validate_btn, false, false, 0);
+    fstab_actions.pack_start(&save_btn, false, false, 0);
+    fstab_box.pack_start(&fstab_actions, false, false, 0);
+    super::disable_in_safe_mode(&state, &[&save_btn]);
+
+    let fstab_status_lbl = Label::new(Some("Edits are validated and /etc/fstab is backed up before saving."));
+    fstab_status_lbl.set_halign(gtk::Align::Start);
+    fstab_status_lbl.set_line_wrap(true);
+    fstab_status_lbl.style_context().add_class("text-cyan");
+    fstab_box.pack_start(&fstab_status_lbl, false, false, 0);
+
+    let fstab_view = TextView::new();
+    fstab_view.set_monospace(true);
+    let fstab_scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    fstab_scrolled.set_min_content_height(140);
+    fstab_scrolled.add(&fstab_view);
+    fstab_box.pack_start(&fstab_scrolled, true, true, 0);
+    fstab_frame.add(&fstab_box);
+    container.pack_start(&fstab_frame, false, false, 0);
+
+    fn fstab_text(view: &TextView) -> String {
+        view.buffer()
+            .and_then(|b| b.text(&b.start_iter(), &b.end_iter(), false))
+            .map(|t| t.to_string())
+            .unwrap_or_default()
+    }
+
+    let load_fstab = clone!(@strong fstab_view, @strong fstab_status_lbl => move || {
+        let mgr = crate::system_service::SystemManager::new();
+        match mgr.get_fstab() {
+            Ok(content) => {
+                if let Some(buffer) = fstab_view.buffer() {
+                    buffer.set_text(&content);
+                }
+            }
+            Err(e) => fstab_status_lbl.set_text(&format!("[FAIL] Cannot read /etc/fstab: {}", e)),
+        }
+    });
+    load_fstab();
+
+    reload_btn.connect_clicked(clone!(@strong fstab_status_lbl => move |_| {
+        load_fstab();
+        fstab_status_lbl.set_text("Reloaded /etc/fstab");
+    }));
+
+    validate_btn.connect_clicked(clone!(@strong fstab_view, @strong fstab_status_lbl => move |_| {
+        let entries = crate::fstab::parse_fstab(&fstab_text(&fstab_view));
+        let errors = crate::fstab::validate_fstab(&entries);
+        if errors.is_empty() {
+            fstab_status_lbl.set_text(&format!("[OK] {} entries, no problems found", entries.len()));
+        } else {
+            fstab_status_lbl.set_text(&format!("[FAIL] {}", errors.join("; ")));
+        }
+    }));
+
+    save_btn.connect_clicked(clone!(@strong fstab_view, @strong fstab_status_lbl => move |btn| {
+        if !super::confirm(btn, "Overwrite /etc/fstab?", Some("The current file is backed up first. A wrong entry can stop the system from booting.")) {
+            return;
+        }
+        let mgr = crate::system_service::SystemManager::new();
+        match mgr.set_fstab(&fstab_text(&fstab_view)) {
+            Ok(backup) => fstab_status_lbl.set_text(&format!("[OK] Saved /etc/fstab (backup: {})", backup)),
+            Err(e) => fstab_status_lbl.set_text(&format!("[FAIL] Not saved: {}", e.replace('\n', "; "))),
+        }
+    }));
+
+    let health_frame = Frame::new(Some(" Drive Health (SMART / NVMe) "));
+    let health_store = ListStore::new(&[
+        glib::Type::STRING, // Drive
+        glib::Type::STRING, // Model
+        glib::Type::STRING, // Verdict
+        glib::Type::STRING, // Power-On
+        glib::Type::STRING, // Cycles
+        glib::Type::STRING, // Unsafe Shutdowns
+        glib::Type::STRING, // Errors
+        glib::Type::STRING, // Written
+        glib::Type::STRING, // Spare / Used
+        glib::Type::STRING, // Self-Test
+        glib::Type::STRING, // Notes
+    ]);
+    let health_tree = TreeView::with_model(&health_store);
+    health_tree.set_widget_name("drive_health_tree");
+    for (title, id) in &[
+        ("Drive", 0), ("Model", 1), ("Verdict", 2), ("Power-On", 3), ("Cycles", 4),
+        ("Unsafe Shutdowns", 5), ("Media / Realloc / Pending", 6), ("Written", 7),
+        ("Spare / Used", 8), ("Self-Test", 9), ("Notes", 10),
+    ] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        health_tree.append_column(&col);
+    }
+    let health_scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    health_scrolled.set_min_content_height(120);
+    health_scrolled.add(&health_tree);
+    health_frame.add(&health_scrolled);
+    container.pack_start(&health_frame, false, false, 0);
+
+    let fs_health_frame = Frame::new(Some(" Filesystem Health (mdraid / btrfs / ZFS) "));
+    let fs_health_store = ListStore::new(&[
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // State
+        glib::Type::STRING, // Devices
+        glib::Type::STRING, // Detail
+        glib::Type::STRING, // Errors
+    ]);
+    let fs_health_tree = TreeView::with_model(&fs_health_store);
+    fs_health_tree.set_widget_name("fs_health_tree");
+    for (title, id) in &[
+        ("Name", 0), ("Type", 1), ("State", 2), ("Devices", 3), ("Detail", 4), ("Errors", 5),
+    ] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        fs_health_tree.append_column(&col);
+    }
+    let fs_health_scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    fs_health_scrolled.set_min_content_height(100);
+    fs_health_scrolled.add(&fs_health_tree);
+    fs_health_frame.add(&fs_health_scrolled);
+    container.pack_start(&fs_health_frame, false, false, 0);
+
+    let quota_frame = Frame::new(Some(" Quotas "));
+    let quota_box = Box::new(Orientation::Vertical, 5);
+    quota_box.set_border_width(5);
+    let quota_status_lbl = Label::new(Some("No filesystems with quotas enabled"));
+    quota_status_lbl.set_widget_name("quota_status_lbl");
+    quota_status_lbl.set_halign(gtk::Align::Start);
+    quota_box.pack_start(&quota_status_lbl, false, false, 0);
+    let quota_store = ListStore::new(&[
+        glib::Type::STRING, // Mount
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Space Used
+        glib::Type::STRING, // Space Soft / Hard
+        glib::Type::STRING, // Files Used
+        glib::Type::STRING, // Files Soft / Hard
+        glib::Type::STRING, // Status
+    ]);
+    let quota_tree = TreeView::with_model(&quota_store);
+    quota_tree.set_widget_name("quota_tree");
+    for (title, id) in &[
+        ("Mount", 0), ("Type", 1), ("Name", 2), ("Space Used", 3), ("Space Soft / Hard", 4),
+        ("Files Used", 5), ("Files Soft / Hard", 6), ("Status", 7),
+    ] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        quota_tree.append_column(&col);
+    }
+    let quota_scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    quota_scrolled.set_min_content_height(100);
+    quota_scrolled.add(&quota_tree);
+    quota_box.pack_start(&quota_scrolled, true, true, 0);
+    quota_frame.add(&quota_box);
+    container.pack_start(&quota_frame, false, false, 0);
 
     container.upcast::<Widget>()
 }
 
+/// "What's using space?" explorer: scans the selected mount on the tokio runtime and
+/// shows the largest directories and files, refreshing while the scan streams in.
+fn build_space_explorer() -> Frame {
+    let frame = Frame::new(Some(" What's Using Space? "));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let controls = Box::new(Orientation::Horizontal, 5);
+    let mount_combo = ComboBoxText::new();
+    mount_combo.set_widget_name("space_mount_combo");
+    let scan_btn = Button::with_label("Scan");
+    scan_btn.style_context().add_class("suggested-action");
+    let cancel_btn = Button::with_label("Cancel");
+    cancel_btn.set_sensitive(false);
+    let up_btn = Button::with_label("Up");
+    up_btn.set_sensitive(false);
+    controls.pack_start(&Label::new(Some("Mount:")), false, false, 0);
+    controls.pack_start(&mount_combo, false, false, 0);
+    controls.pack_start(&scan_btn, false, false, 0);
+    controls.pack_start(&cancel_btn, false, false, 0);
+    controls.pack_start(&up_btn, false, false, 0);
+    vbox.pack_start(&controls, false, false, 0);
+
+    let status_lbl = Label::new(Some("Pick a mount and press Scan. Double-click a directory to drill down."));
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.style_context().add_class("text-cyan");
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    let store = TreeStore::new(&[
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Size
+        glib::Type::U64,    // Size (sort key)
+        glib::Type::STRING, // Files
+        glib::Type::U64,    // Files (sort key)
+        glib::Type::STRING, // Share
+        glib::Type::STRING, // Path
+        glib::Type::BOOL,   // Is directory
+    ]);
+    let tree = TreeView::with_model(&store);
+    for (title, id, sort_id) in &[("Name", 0, 0), ("Size", 1, 2), ("Files", 3, 4), ("Share", 5, 2)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        col.set_sort_column_id(*sort_id);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+    store.set_sort_column_id(gtk::SortColumn::Index(2), gtk::SortType::Descending);
+
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_min_content_height(200);
+    scrolled.add(&tree);
+    vbox.pack_start(&scrolled, true, true, 0);
+    frame.add(&vbox);
+
+    let scan: Rc<RefCell<Option<Arc<DirScan>>>> = Rc::new(RefCell::new(None));
+    let current_path: Rc<RefCell<String>> = Rc::new(RefCell::new(String::new()));
+
+    let show = clone!(@strong scan, @strong current_path, @strong store, @strong status_lbl, @strong up_btn => move || {
+        let scan = match scan.borrow().clone() {
+            Some(scan) => scan,
+            None => return,
+        };
+        let path = current_path.borrow().clone();
+        let usage = scan.snapshot(&path, 2);
+        SHOWN_DIR_USAGE.with(|shown| {
+            let mut shown = shown.borrow_mut();
+            if *shown == usage {
+                return;
+            }
+            // Only a different directory starts from scratch; growing sizes keep selection and scroll
+            if shown.as_ref().map(|s| &s.path) != usage.as_ref().map(|u| &u.path) {
+                store.clear();
+            }
+            let (children, total) = usage.as_ref().map(|u| (u.children.as_slice(), u.size)).unwrap_or_default();
+            sync_dir_usage(&store, None, children, total);
+            *shown = usage;
+        });
+        up_btn.set_sensitive(Path::new(&path) != scan.root);
+
+        let (bytes, files, dirs, errors) = scan.progress();
+        let state = if scan.is_finished() {
+            if scan.is_cancelled() { "[FAIL] Cancelled" } else { "[OK] Done" }
+        } else {
+            "Scanning"
+        };
+        let mut text = format!("{}: {} in {} files, {} directories — {}", state, format_size(bytes), files, dirs, path);
+        if errors > 0 {
+            text.push_str(&format!(" ({} unreadable)", errors));
+        }
+        status_lbl.set_text(&text);
+    });
+
+    scan_btn.connect_clicked(clone!(@strong scan, @strong current_path, @strong mount_combo,
+                                    @strong cancel_btn, @strong show => move |btn| {
+        let mount = match mount_combo.active_text() {
+            Some(mount) => mount.to_string(),
+            None => return,
+        };
+        if let Some(old) = scan.borrow().as_ref() {
+            old.cancel();
+        }
+        *scan.borrow_mut() = Some(dirsize::start_scan(Path::new(&mount)));
+        *current_path.borrow_mut() = mount;
+        btn.set_sensitive(false);
+        cancel_btn.set_sensitive(true);
+        show();
+
+        glib::timeout_add_local(Duration::from_millis(500), clone!(@strong scan, @strong btn,
+                                                                    @strong cancel_btn, @strong show => move || {
+            show();
+            let finished = scan.borrow().as_ref().is_none_or(|s| s.is_finished());
+            if finished {
+                btn.set_sensitive(true);
+                cancel_btn.set_sensitive(false);
+                ControlFlow::Break
+            } else {
+                ControlFlow::Continue
+            }
+        }));
+    }));
+
+    cancel_btn.connect_clicked(clone!(@strong scan => move |_| {
+        if let Some(scan) = scan.borrow().as_ref() {
+            scan.cancel();
+        }
+    }));
+
+    tree.connect_row_activated(clone!(@strong current_path, @strong show => move |tree, path, _| {
+        let model = match tree.model() {
+            Some(model) => model,
+            None => return,
+        };
+        if let Some(iter) = model.iter(path) {
+            if model.value(&iter, 7).get::<bool>().unwrap_or(false) {
+                if let Ok(dir) = model.value(&iter, 6).get::<String>() {
+                    *current_path.borrow_mut() = dir;
+                    show();
+                }
+            }
+        }
+    }));
+
+    up_btn.connect_clicked(clone!(@strong current_path, @strong show => move |_| {
+        let parent = Path::new(current_path.borrow().as_str())
+            .parent()
+            .map(|p| p.to_string_lossy().to_string());
+        if let Some(parent) = parent {
+            *current_path.borrow_mut() = parent;
+            show();
+        }
+    }));
+
+    frame
+}
+
+/// Brings the rows under `parent` in line with `children`, matched by path.
+fn sync_dir_usage(store: &TreeStore, parent: Option<&TreeIter>, children: &[DirUsage], total: u64) {
+    let mut rows = HashMap::new();
+    if let Some(iter) = store.iter_children(parent) {
+        loop {
+            if let Ok(path) = store.value(&iter, 6).get::<String>() {
+                rows.insert(path, iter);
+            }
+            if !store.iter_next(&iter) {
+                break;
+            }
+        }
+    }
+    for usage in children {
+        let iter = rows.remove(&usage.path).unwrap_or_else(|| store.append(parent));
+        let share = if total > 0 {
+            format!("{:.1}%", usage.size as f64 / total as f64 * 100.0)
+        } else {
+            "-".to_string()
+        };
+        let name = if usage.is_dir { format!("{}/", usage.name) } else { usage.name.clone() };
+        let files = if usage.is_dir { usage.files.to_string() } else { "-".to_string() };
+        store.set(&iter, &[
+            (0, &name),
+            (1, &format_size(usage.size)),
+            (2, &usage.size),
+            (3, &files),
+            (4, &usage.files),
+            (5, &share),
+            (6, &usage.path),
+            (7, &usage.is_dir),
+        ]);
+        sync_dir_usage(store, Some(&iter), &usage.children, total);
+    }
+    for iter in rows.values() {
+        store.remove(iter);
+    }
+}
+
 pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
     let container = match tab.clone().downcast::<gtk::Container>() {
         Ok(c) => c,
@@ -74,7 +597,32 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         None => return,
     };
 
+    let health_tree = crate::gui::dashboard::find_widget_by_name(&container, "drive_health_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok());
+    let block_tree = crate::gui::dashboard::find_widget_by_name(&container, "block_devices_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok());
+
     let s = state.lock();
+
+    if let Some(combo) = crate::gui::dashboard::find_widget_by_name(&container, "space_mount_combo")
+        .and_then(|w| w.downcast::<ComboBoxText>().ok())
+    {
+        let mounts: Vec<String> = s.dynamic_data.disks.iter().map(|d| d.name.clone()).collect();
+        SHOWN_MOUNTS.with(|shown| {
+            let mut shown = shown.borrow_mut();
+            if *shown != mounts {
+                let active = combo.active_text().map(|t| t.to_string());
+                combo.remove_all();
+                for mount in &mounts {
+                    combo.append_text(mount);
+                }
+                let idx = active.and_then(|a| mounts.iter().position(|m| *m == a)).unwrap_or(0);
+                combo.set_active(Some(idx as u32));
+                *shown = mounts;
+            }
+        });
+    }
+
     store.clear();
 
     for disk in &s.dynamic_data.disks {
@@ -83,8 +631,19 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         } else {
             "-".to_string()
         };
+        let (inodes_str, inode_pct) = if disk.inodes_total > 0 {
+            (
+                format!("{} / {}", disk.inodes_used, disk.inodes_total),
+                format!("{:.1}%", disk.inodes_used as f64 / disk.inodes_total as f64 * 100.0),
+            )
+        } else {
+            ("-".to_string(), "-".to_string())
+        };
         let temp_str = disk.temp.map(|t| format!("{:.0}°C", t)).unwrap_or_else(|| "-".to_string());
-        let health_str = disk.health_pct.map(|h| format!("{}%", h)).unwrap_or_else(|| "-".to_string());
+        let health_str = match disk.health_pct {
+            Some(h) => format!("{} ({}%)", disk.verdict.as_str(), h),
+            None => disk.verdict.as_str().to_string(),
+        };
         let cycles_str = disk.power_cycles.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string());
 
         store.insert_with_values(None, &[
@@ -95,16 +654,223 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
             (4, &format_size(disk.used)),
             (5, &format_size(disk.free)),
             (6, &use_pct),
-            (7, &crate::utils::format_rate(disk.read_rate)),
-            (8, &crate::utils::format_rate(disk.write_rate)),
-            (9, &temp_str),
-            (10, &health_str),
-            (11, &cycles_str),
-            (12, &if disk.is_nvme { 
+            (7, &inodes_str),
+            (8, &inode_pct),
+            (9, &crate::utils::format_rate(disk.read_rate)),
+            (10, &crate::utils::format_rate(disk.write_rate)),
+            (11, &format!("{} / {}", disk.read_ops, disk.write_ops)),
+            (12, &format!("{:.1} / {:.1} ms", disk.read_latency_ms, disk.write_latency_ms)),
+            (13, &format!("{:.2}", disk.queue_depth)),
+            (14, &format!("{:.1}%", disk.util_pct)),
+            (15, &temp_str),
+            (16, &health_str),
+            (17, &cycles_str),
+            (18, &if disk.is_nvme { 
                 "NVMe".to_string() 
             } else {
                 disk.is_ssd.map(|ssd| if ssd { "SSD".to_string() } else { "HDD".to_string() }).unwrap_or_else(|| "-".to_string())
             }),
         ]);
     }
+
+    if let Some(store) = health_tree.and_then(|t| t.model()).and_then(|m| m.downcast::<ListStore>().ok()) {
+        store.clear();
+        let opt = |v: Option<u64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+        for h in &s.dynamic_data.drive_health {
+            let errors = format!("{} / {} / {}", opt(h.media_errors), opt(h.reallocated_sectors), opt(h.pending_sectors));
+            let spare = format!(
+                "{} / {}",
+                h.available_spare.map(|v| format!("{}%", v)).unwrap_or_else(|| "-".to_string()),
+                h.percentage_used.map(|v| format!("{}%", v)).unwrap_or_else(|| "-".to_string()),
+            );
+            let notes = if h.notes.is_empty() { h.source.clone() } else { h.notes.join("; ") };
+            store.insert_with_values(None, &[
+                (0, &format!("/dev/{}", h.device)),
+                (1, &h.model),
+                (2, &h.verdict.as_str().to_string()),
+                (3, &h.power_on_hours.map(|v| format!("{} h", v)).unwrap_or_else(|| "-".to_string())),
+                (4, &opt(h.power_cycles)),
+                (5, &opt(h.unsafe_shutdowns)),
+                (6, &errors),
+                (7, &h.data_written.map(format_size).unwrap_or_else(|| "-".to_string())),
+                (8, &spare),
+                (9, &h.self_test.clone().unwrap_or_else(|| "-".to_string())),
+                (10, &notes),
+            ]);
+        }
+    }
+
+    if let Some(store) = crate::gui::dashboard::find_widget_by_name(&container, "fs_health_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+        .and_then(|t| t.model())
+        .and_then(|m| m.downcast::<ListStore>().ok())
+    {
+        store.clear();
+        let health = &s.dynamic_data.storage_health;
+        for md in &health.md_arrays {
+            let mut state = if !md.active {
+                "inactive".to_string()
+            } else if md.is_degraded() {
+                "DEGRADED".to_string()
+            } else {
+                "active".to_string()
+            };
+            if md.read_only {
+                state.push_str(" (read-only)");
+            }
+            let devices: Vec<String> = md.devices.iter().map(|d| {
+                if md.failed_devices.contains(d) {
+                    format!("{} (failed)", d)
+                } else if md.spare_devices.contains(d) {
+                    format!("{} (spare)", d)
+                } else {
+                    d.clone()
+                }
+            }).collect();
+            let mut detail = if md.total_disks > 0 {
+                format!("{}/{} disks [{}]", md.active_disks, md.total_disks, md.member_status)
+            } else {
+                String::new()
+            };
+            if let Some(action) = &md.sync_action {
+                detail.push_str(&format!(" {}", action));
+                if let Some(pct) = md.sync_progress {
+                    detail.push_str(&format!(" {:.1}%", pct));
+                }
+                if let Some(finish) = &md.sync_finish {
+                    detail.push_str(&format!(" ({})", finish));
+                }
+            }
+            store.insert_with_values(None, &[
+                (0, &format!("/dev/{}", md.name)),
+                (1, &if md.level.is_empty() { "md".to_string() } else { md.level.clone() }),
+                (2, &state),
+                (3, &devices.join(", ")),
+                (4, &detail),
+                (5, &md.failed_devices.len().to_string()),
+            ]);
+        }
+        for fs in &health.btrfs {
+            let name = match (fs.label.is_empty(), fs.mountpoint.is_empty()) {
+                (true, true) => fs.uuid.clone(),
+                (true, false) => fs.mountpoint.clone(),
+                (false, true) => fs.label.clone(),
+                (false, false) => format!("{} ({})", fs.label, fs.mountpoint),
+            };
+            let errors: u64 = fs.devices.iter().map(|d| d.total_errors()).sum();
+            let state = if fs.devices.iter().any(|d| d.missing) {
+                "DEGRADED"
+            } else if errors > 0 {
+                "ERRORS"
+            } else {
+                "OK"
+            };
+            let alloc = |(total, used): (u64, u64)| format!("{} / {}", format_size(used), format_size(total));
+            store.insert_with_values(None, &[
+                (0, &name),
+                (1, &"btrfs".to_string()),
+                (2, &state.to_string()),
+                (3, &fs.disks.join(", ")),
+                (4, &format!("Data {}, Metadata {}, System {}", alloc(fs.data), alloc(fs.metadata), alloc(fs.system))),
+                (5, &errors.to_string()),
+            ]);
+        }
+        for pool in &health.zfs_pools {
+            store.insert_with_values(None, &[
+                (0, &pool.name),
+                (1, &"zfs".to_string()),
+                (2, &pool.state),
+                (3, &"-".to_string()),
+                (4, &"-".to_string()),
+                (5, &"-".to_string()),
+            ]);
+        }
+    }
+
+    if let Some(store) = crate::gui::dashboard::find_widget_by_name(&container, "quota_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+        .and_then(|t| t.model())
+        .and_then(|m| m.downcast::<ListStore>().ok())
+    {
+        store.clear();
+        let status_lbl = crate::gui::dashboard::find_widget_by_name(&container, "quota_status_lbl")
+            .and_then(|w| w.downcast::<Label>().ok());
+        match &s.dynamic_data.quotas {
+            Ok(quotas) => {
+                let limit = |v: u64, fmt: &dyn Fn(u64) -> String| if v == 0 { "none".to_string() } else { fmt(v) };
+                for q in quotas {
+                    store.insert_with_values(None, &[
+                        (0, &q.mount_point),
+                        (1, &q.kind),
+                        (2, &q.name),
+                        (3, &format_size(q.space_used)),
+                        (4, &format!("{} / {}", limit(q.space_soft, &format_size), limit(q.space_hard, &format_size))),
+                        (5, &q.files_used.to_string()),
+                        (6, &format!("{} / {}", limit(q.files_soft, &|v| v.to_string()), limit(q.files_hard, &|v| v.to_string()))),
+                        (7, &if q.over_limit { "OVER LIMIT".to_string() } else { "OK".to_string() }),
+                    ]);
+                }
+                if let Some(lbl) = status_lbl {
+                    let over = quotas.iter().filter(|q| q.over_limit).count();
+                    if quotas.is_empty() {
+                        lbl.set_text("No filesystems with quotas enabled");
+                    } else if over > 0 {
+                        lbl.set_text(&format!("[FAIL] {} of {} quota entries over their soft limit", over, quotas.len()));
+                    } else {
+                        lbl.set_text(&format!("[OK] {} quota entries within limits", quotas.len()));
+                    }
+                }
+            }
+            Err(e) => {
+                if let Some(lbl) = status_lbl {
+                    lbl.set_text(&format!("[FAIL] Cannot read quotas (root required): {}", e));
+                }
+            }
+        }
+    }
+
+    if let Some(tree) = block_tree {
+        let devices = &s.dynamic_data.block_devices;
+        let changed = SHOWN_BLOCK_TREE.with(|shown| {
+            let mut shown = shown.borrow_mut();
+            if *shown != *devices {
+                *shown = devices.clone();
+                true
+            } else {
+                false
+            }
+        });
+        if let Some(store) = changed.then(|| tree.model()).flatten().and_then(|m| m.downcast::<TreeStore>().ok()) {
+            store.clear();
+            for dev in devices {
+                insert_block_device(&store, None, dev);
+            }
+            tree.expand_all();
+        }
+    }
+}
+
+fn insert_block_device(store: &TreeStore, parent: Option<&TreeIter>, dev: &BlockDevice) {
+    let fs = if dev.label.is_empty() { dev.fs_type.clone() } else { format!("{} ({})", dev.fs_type, dev.label) };
+    let transport = match (dev.transport.as_str(), dev.rotational, dev.removable) {
+        ("", _, _) => String::new(),
+        (t, Some(true), _) => format!("{} HDD", t),
+        (t, _, true) => format!("{} removable", t),
+        (t, _, _) => t.to_string(),
+    };
+    let kind = if dev.read_only { format!("{} (ro)", dev.kind) } else { dev.kind.clone() };
+    let iter = store.insert_with_values(parent, None, &[
+        (0, &dev.display_name),
+        (1, &kind),
+        (2, &format_size(dev.size)),
+        (3, &fs),
+        (4, &dev.mountpoints.join(", ")),
+        (5, &dev.model),
+        (6, &dev.serial),
+        (7, &transport),
+        (8, &dev.scheduler),
+    ]);
+    for child in &dev.children {
+        insert_block_device(store, Some(&iter), child);
+    }
 }
@@ -0,0 +1,303 @@
+use gtk::prelude::*;
+use gtk::{Box, Orientation, TreeView, TreeViewColumn, CellRendererText, ListStore, TreeStore, Widget, ScrolledWindow, Button, Frame, Label, Entry};
+use std::cell::RefCell;
+use std::sync::Arc;
+use parking_lot::Mutex;
+use glib::clone;
+use crate::types::{AppState, FirewallRuleset, FirewalldZone};
+use crate::utils::format_size;
+
+thread_local! {
+    static SHOWN_RULESET: RefCell<FirewallRuleset> = RefCell::new(FirewallRuleset::default());
+    static SHOWN_ZONES: RefCell<Option<Vec<FirewalldZone>>> = const { RefCell::new(None) };
+}
+
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
+    let container = Box::new(Orientation::Vertical, 5);
+    container.set_border_width(10);
+
+    let frame = Frame::new(Some(" Firewall Rules "));
+    let inner = Box::new(Orientation::Vertical, 5);
+    inner.set_border_width(5);
+
+    let status_lbl = Label::new(Some("Loading ruleset..."));
+    status_lbl.set_widget_name("firewall_status_lbl");
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.set_line_wrap(true);
+    inner.pack_start(&status_lbl, false, false, 0);
+
+    let store = TreeStore::new(&[
+        glib::Type::STRING, // Table / Chain / Rule
+        glib::Type::STRING, // Hook
+        glib::Type::STRING, // Policy
+        glib::Type::STRING, // Packets
+        glib::Type::STRING, // Bytes
+        glib::Type::STRING, // Comment
+        glib::Type::STRING, // Handle
+    ]);
+    let tree = TreeView::with_model(&store);
+    tree.set_widget_name("firewall_tree");
+    for (title, id) in &[("Rule", 0), ("Hook", 1), ("Policy", 2), ("Packets", 3), ("Bytes", 4), ("Comment", 5), ("Handle", 6)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_vexpand(true);
+    scrolled.set_hexpand(true);
+    scrolled.add(&tree);
+    inner.pack_start(&scrolled, true, true, 0);
+    frame.add(&inner);
+    container.pack_start(&frame, true, true, 0);
+
+    container.pack_start(&build_zones_frame(state), false, false, 0);
+
+    container.upcast::<Widget>()
+}
+
+fn build_zones_frame(state: Arc<Mutex<AppState>>) -> Frame {
+    let frame = Frame::new(Some(" firewalld Zones "));
+    frame.set_widget_name("firewalld_frame");
+    frame.set_no_show_all(true);
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let action_box = Box::new(Orientation::Horizontal, 5);
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("ssh or 8080/tcp"));
+    let add_service_btn = Button::with_label("Add Service");
+    let remove_service_btn = Button::with_label("Remove Service");
+    let add_port_btn = Button::with_label("Add Port");
+    let remove_port_btn = Button::with_label("Remove Port");
+    add_service_btn.style_context().add_class("suggested-action");
+    add_port_btn.style_context().add_class("suggested-action");
+    remove_service_btn.style_context().add_class("destructive-action");
+    remove_port_btn.style_context().add_class("destructive-action");
+    action_box.pack_start(&entry, false, false, 0);
+    action_box.pack_start(&add_service_btn, false, false, 0);
+    action_box.pack_start(&remove_service_btn, false, false, 0);
+    action_box.pack_start(&add_port_btn, false, false, 0);
+    action_box.pack_start(&remove_port_btn, false, false, 0);
+    vbox.pack_start(&action_box, false, false, 0);
+    super::disable_in_safe_mode(&state, &[&add_service_btn, &remove_service_btn, &add_port_btn, &remove_port_btn]);
+
+    let status_lbl = Label::new(Some("Select a zone, enter a service or port and use the buttons above."));
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.style_context().add_class("text-cyan");
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    let store = ListStore::new(&[
+        glib::Type::STRING, // Zone
+        glib::Type::STRING, // State
+        glib::Type::STRING, // Target
+        glib::Type::STRING, // Interfaces
+        glib::Type::STRING, // Sources
+        glib::Type::STRING, // Services
+        glib::Type::STRING, // Ports
+    ]);
+    let tree = TreeView::with_model(&store);
+    tree.set_widget_name("firewalld_tree");
+    for (title, id) in &[("Zone", 0), ("State", 1), ("Target", 2), ("Interfaces", 3), ("Sources", 4), ("Services", 5), ("Ports", 6)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_min_content_height(150);
+    scrolled.add(&tree);
+    vbox.pack_start(&scrolled, true, true, 0);
+    vbox.show_all();
+    frame.add(&vbox);
+
+    fn get_selected_zone(tree: &TreeView) -> Option<String> {
+        tree.selection().selected().and_then(|(model, iter)| {
+            model.value(&iter, 0).get::<String>().ok()
+        })
+    }
+
+    let connect_change = |btn: &Button, add: bool, kind: &'static str| {
+        btn.connect_clicked(clone!(@strong tree, @strong entry, @strong status_lbl, @strong state => move |btn| {
+            let zone = match get_selected_zone(&tree) {
+                Some(z) => z,
+                None => {
+                    status_lbl.set_text("No zone selected");
+                    return;
+                }
+            };
+            let value = entry.text().trim().to_string();
+            let (verb, prep) = if add { ("Added", "to") } else { ("Removed", "from") };
+            let question = format!("{} {} {} {} zone {}?", if add { "Add" } else { "Remove" }, kind, value, prep, zone);
+            if !super::confirm(btn, &question, Some("The change is applied now and saved to the permanent firewalld configuration.")) {
+                return;
+            }
+            let mgr = crate::system_service::SystemManager::new();
+            match mgr.firewalld_change(&zone, add, kind, &value) {
+                Ok(()) => {
+                    state.lock().firewalld_zones = mgr.get_firewalld_zones();
+                    status_lbl.set_text(&format!("[OK] {} {} {} {} {}", verb, kind, value, prep, zone));
+                }
+                Err(e) => status_lbl.set_text(&format!("[FAIL] {} {}: {}", kind, value, e)),
+            }
+        }));
+    };
+    connect_change(&add_service_btn, true, "service");
+    connect_change(&remove_service_btn, false, "service");
+    connect_change(&add_port_btn, true, "port");
+    connect_change(&remove_port_btn, false, "port");
+
+    frame
+}
+
+fn format_counter(value: Option<u64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn update_ruleset(container: &gtk::Container, ruleset: &FirewallRuleset) {
+    let changed = SHOWN_RULESET.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown == *ruleset {
+            return false;
+        }
+        *shown = ruleset.clone();
+        true
+    });
+    if !changed {
+        return;
+    }
+
+    if let Some(lbl) = crate::gui::dashboard::find_widget_by_name(container, "firewall_status_lbl")
+        .and_then(|w| w.downcast::<Label>().ok())
+    {
+        let ctx = lbl.style_context();
+        ctx.remove_class("text-red");
+        ctx.remove_class("text-cyan");
+        match &ruleset.error {
+            Some(e) => {
+                lbl.set_text(&format!("[FAIL] {}", e));
+                ctx.add_class("text-red");
+            }
+            None => {
+                let rules: usize = ruleset.tables.iter().flat_map(|t| &t.chains).map(|c| c.rules.len()).sum();
+                lbl.set_text(&format!("Backend: {} | {} table(s), {} rule(s)", ruleset.backend, ruleset.tables.len(), rules));
+                ctx.add_class("text-cyan");
+            }
+        }
+    }
+
+    let tree = match crate::gui::dashboard::find_widget_by_name(container, "firewall_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+    {
+        Some(t) => t,
+        None => return,
+    };
+    let store = match tree.model().and_then(|m| m.downcast::<TreeStore>().ok()) {
+        Some(s) => s,
+        None => return,
+    };
+
+    // Counters change on every refresh; keep whatever the user had expanded
+    let mut expanded = Vec::new();
+    tree.map_expanded_rows(|_, path| expanded.push(path.clone()));
+    let first_load = store.iter_first().is_none();
+    store.clear();
+
+    for table in &ruleset.tables {
+        let table_iter = store.insert_with_values(None, None, &[
+            (0, &format!("table {} {}", table.family, table.name)),
+        ]);
+        for chain in &table.chains {
+            let chain_iter = store.insert_with_values(Some(&table_iter), None, &[
+                (0, &format!("chain {}", chain.name)),
+                (1, &chain.hook),
+                (2, &chain.policy),
+                (3, &format_counter(chain.packets)),
+                (4, &chain.bytes.map(format_size).unwrap_or_default()),
+            ]);
+            for rule in &chain.rules {
+                store.insert_with_values(Some(&chain_iter), None, &[
+                    (0, &rule.text),
+                    (3, &format_counter(rule.packets)),
+                    (4, &rule.bytes.map(format_size).unwrap_or_default()),
+                    (5, &rule.comment),
+                    (6, &rule.handle),
+                ]);
+            }
+        }
+    }
+
+    if first_load {
+        for i in 0..ruleset.tables.len() as i32 {
+            tree.expand_row(&gtk::TreePath::from_indicesv(&[i]), false);
+        }
+    } else {
+        for path in &expanded {
+            tree.expand_row(path, false);
+        }
+    }
+}
+
+fn update_zones(container: &gtk::Container, zones: &Option<Vec<FirewalldZone>>) {
+    let changed = SHOWN_ZONES.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown == *zones {
+            return false;
+        }
+        *shown = zones.clone();
+        true
+    });
+    if !changed {
+        return;
+    }
+
+    if let Some(frame) = crate::gui::dashboard::find_widget_by_name(container, "firewalld_frame") {
+        frame.set_visible(zones.is_some());
+    }
+    let store = match crate::gui::dashboard::find_widget_by_name(container, "firewalld_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+        .and_then(|t| t.model())
+        .and_then(|m| m.downcast::<ListStore>().ok())
+    {
+        Some(s) => s,
+        None => return,
+    };
+
+    store.clear();
+    for zone in zones.iter().flatten() {
+        let mut flags = Vec::new();
+        if zone.default {
+            flags.push("default");
+        }
+        if zone.active {
+            flags.push("active");
+        }
+        store.insert_with_values(None, &[
+            (0, &zone.name),
+            (1, &flags.join(", ")),
+            (2, &zone.target),
+            (3, &zone.interfaces.join(" ")),
+            (4, &zone.sources.join(" ")),
+            (5, &zone.services.join(" ")),
+            (6, &zone.ports.join(" ")),
+        ]);
+    }
+}
+
+pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
+    let container = match tab.clone().downcast::<gtk::Container>() {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+
+    let s = state.lock();
+    update_ruleset(&container, &s.firewall);
+    update_zones(&container, &s.firewalld_zones);
+}
@@ -3,7 +3,7 @@ use gtk::{Box, Orientation, Label, ProgressBar, Frame, Widget};
 use std::sync::Arc;
 use parking_lot::Mutex;
 use crate::types::AppState;
-use crate::utils::{format_size, format_rate};
+use crate::utils::{format_size, format_rate, format_pressure, get_pressure_status};
 
 pub fn build_global_stats() -> Widget {
     let container = Box::new(Orientation::Horizontal, 5);
@@ -64,6 +64,15 @@ pub fn build_global_stats() -> Widget {
     disk_frame.add(&disk_lbl);
     container.pack_start(&disk_frame, true, true, 0);
 
+    let psi_frame = Frame::new(Some("Pressure"));
+    let psi_lbl = Label::new(Some("C:- M:- I:-"));
+    psi_lbl.set_widget_name("global_psi_lbl");
+    psi_lbl.style_context().add_class("text-cyan");
+    psi_lbl.set_margin_start(10);
+    psi_lbl.set_margin_end(10);
+    psi_frame.add(&psi_lbl);
+    container.pack_start(&psi_frame, true, true, 0);
+
     container.upcast::<Widget>()
 }
 
@@ -82,6 +91,7 @@ pub fn update_global_stats(container: &Widget, state: &Arc<Mutex<AppState>>) {
     let gpu_lbl = crate::gui::dashboard::find_widget_by_name(&cont, "global_gpu_lbl").and_then(|w| w.downcast::<Label>().ok());
     let net_lbl = crate::gui::dashboard::find_widget_by_name(&cont, "global_net_lbl").and_then(|w| w.downcast::<Label>().ok());
     let disk_lbl = crate::gui::dashboard::find_widget_by_name(&cont, "global_disk_lbl").and_then(|w| w.downcast::<Label>().ok());
+    let psi_lbl = crate::gui::dashboard::find_widget_by_name(&cont, "global_psi_lbl").and_then(|w| w.downcast::<Label>().ok());
 
     let s = state.lock();
     let usage = &s.dynamic_data.global_usage;
@@ -110,6 +120,23 @@ pub fn update_global_stats(container: &Widget, state: &Arc<Mutex<AppState>>) {
     }
 
     if let Some(lbl) = disk_lbl {
-        lbl.set_text(&format!("R:{} W:{}", format_rate(usage.disk_read), format_rate(usage.disk_write)));
+        lbl.set_text(&format!(
+            "R:{} W:{} IOPS:{}/{} {:.0}%",
+            format_rate(usage.disk_read), format_rate(usage.disk_write),
+            usage.disk_read_ops, usage.disk_write_ops, usage.disk_util
+        ));
+    }
+
+    if let Some(lbl) = psi_lbl {
+        match &usage.pressure {
+            Some(psi) => {
+                let (status, _) = get_pressure_status(psi);
+                lbl.set_text(&format!(
+                    "C:{} M:{} I:{} [{}]",
+                    format_pressure(psi.cpu), format_pressure(psi.memory), format_pressure(psi.io), status
+                ));
+            }
+            None => lbl.set_text("N/A"),
+        }
     }
 }
@@ -1,11 +1,33 @@
 use gtk::prelude::*;
-use gtk::{Box, Orientation, Label, ProgressBar, Frame, Widget, ScrolledWindow};
+use gtk::{Box, Orientation, Label, ProgressBar, Frame, Grid, Widget, ScrolledWindow, TreeView, TreeViewColumn, CellRendererText, ListStore};
+use std::cell::RefCell;
 use std::sync::Arc;
 use parking_lot::Mutex;
-use crate::types::AppState;
+use crate::types::{AppState, GpuInfo};
+use crate::gui::charts::{self, Rgb};
 use crate::utils::{format_size, format_frequency};
 
-pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
+thread_local! {
+    // GPU identity and engine names the cards were built for
+    static SHOWN_GPUS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+type ChartSeries = fn(&GpuInfo) -> Vec<f64>;
+
+// (key, title, color, fixed max, series)
+const HISTORY_CHARTS: [(&str, &str, Rgb, Option<f64>, ChartSeries); 5] = [
+    ("util", "Utilization", charts::GREEN, Some(100.0), |g| to_f64(&g.utilization_history, 1.0)),
+    ("vram", "VRAM", charts::CYAN, Some(100.0), |g| to_f64(&g.memory_history, 1.0)),
+    ("temp", "Temperature", charts::ORANGE, None, |g| to_f64(&g.temperature_history, 1.0)),
+    ("power", "Power", charts::MAGENTA, None, |g| to_f64(&g.power_history, 1000.0)),
+    ("clock", "Graphics Clock", charts::BLUE, None, |g| to_f64(&g.clock_history, 1.0)),
+];
+
+fn to_f64(history: &[u32], divisor: f64) -> Vec<f64> {
+    history.iter().map(|v| *v as f64 / divisor).collect()
+}
+
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
     let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
     scrolled.set_vexpand(true);
     scrolled.set_hexpand(true);
@@ -14,14 +36,51 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     container.set_border_width(10);
     container.set_widget_name("gpu_container");
 
+    container.pack_start(&crate::gui::gpu_controls::build_controls_frame(&state), false, false, 0);
+    container.pack_start(&build_overview(), false, false, 0);
+
+    // Cards are rebuilt when the set of GPUs changes and updated in place otherwise
+    let cards = Box::new(Orientation::Vertical, 10);
+    cards.set_widget_name("gpu_cards");
     let no_gpu_lbl = Label::new(Some("No GPU detected or loaded."));
     no_gpu_lbl.set_widget_name("no_gpu_lbl");
-    container.pack_start(&no_gpu_lbl, true, true, 0);
+    cards.pack_start(&no_gpu_lbl, true, true, 0);
+    container.pack_start(&cards, true, true, 0);
 
     scrolled.add(&container);
     scrolled.upcast::<Widget>()
 }
 
+fn build_overview() -> Frame {
+    let frame = Frame::new(Some(" Overview "));
+    frame.set_widget_name("gpu_overview_frame");
+    frame.set_no_show_all(true);
+
+    let store = ListStore::new(&[
+        glib::Type::STRING, // GPU
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Util
+        glib::Type::STRING, // VRAM
+        glib::Type::STRING, // Temp
+        glib::Type::STRING, // Power
+        glib::Type::STRING, // Clock
+    ]);
+    let tree = TreeView::with_model(&store);
+    tree.set_widget_name("gpu_overview_tree");
+    for (title, id) in &[("GPU", 0), ("Name", 1), ("Util", 2), ("VRAM", 3), ("Temp", 4), ("Power", 5), ("Clock", 6)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+    tree.show();
+    frame.add(&tree);
+    frame
+}
+
 pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
     let scrolled = match tab.clone().downcast::<ScrolledWindow>() {
         Ok(c) => c,
@@ -33,53 +92,320 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         None => return,
     };
 
+    let cards = match crate::gui::dashboard::find_widget_by_name(container.upcast_ref(), "gpu_cards")
+        .and_then(|w| w.downcast::<Box>().ok())
+    {
+        Some(b) => b,
+        None => return,
+    };
+
     let s = state.lock();
-    
-    if let Ok(gpus) = &s.dynamic_data.gpus {
-        if !gpus.is_empty() {
-            container.forall(|child| container.remove(child));
-
-            for (i, gpu) in gpus.iter().enumerate() {
-                let frame = Frame::new(Some(&format!(" GPU {} - {} ({}) - {}°C ", i, gpu.name, gpu.brand, gpu.temperature)));
-                let vbox = Box::new(Orientation::Vertical, 5);
-                vbox.set_border_width(5);
-
-                let util_lbl = Label::new(Some(&format!("Utilization: {}%", gpu.utilization)));
-                util_lbl.set_halign(gtk::Align::Start);
-                util_lbl.style_context().add_class("text-green");
-                let util_bar = ProgressBar::new();
-                util_bar.set_fraction((gpu.utilization as f64 / 100.0).clamp(0.0, 1.0));
-                vbox.pack_start(&util_lbl, false, false, 0);
-                vbox.pack_start(&util_bar, false, false, 0);
-
-                let mem_percent = if gpu.memory_total > 0 { (gpu.memory_used as f64 / gpu.memory_total as f64) * 100.0 } else { 0.0 };
-                let mem_lbl = Label::new(Some(&format!("Memory Usage: {:.1}%", mem_percent)));
-                mem_lbl.set_halign(gtk::Align::Start);
-                mem_lbl.style_context().add_class("text-cyan");
-                let mem_bar = ProgressBar::new();
-                mem_bar.set_fraction((mem_percent / 100.0).clamp(0.0, 1.0));
-                vbox.pack_start(&mem_lbl, false, false, 0);
-                vbox.pack_start(&mem_bar, false, false, 0);
-
-                let details = format!(
-                    "Memory: {} / {}\nPower: {:.2} W\nGraphics Clock: {}\nMemory Clock: {}\nMemory Temp: {}\nFan Speed: {}\nPCIe: {}",
-                    format_size(gpu.memory_used), format_size(gpu.memory_total),
-                    gpu.power_usage as f64 / 1000.0,
-                    format_frequency(gpu.graphics_clock as u64),
-                    format_frequency(gpu.memory_clock as u64),
-                    gpu.memory_temperature.map(|t| format!("{}°C", t)).unwrap_or_else(|| "N/A".to_string()),
-                    gpu.fan_speed.map(|f| format!("{} RPM", f)).unwrap_or_else(|| "N/A".to_string()),
-                    if let (Some(gen), Some(width)) = (gpu.pci_link_gen, gpu.pci_link_width) { format!("Gen {} x{}", gen, width) } else { "N/A".to_string() }
-                );
-
-                let details_lbl = Label::new(Some(&details));
-                details_lbl.set_halign(gtk::Align::Start);
-                vbox.pack_start(&details_lbl, false, false, 0);
-
-                frame.add(&vbox);
-                container.pack_start(&frame, false, false, 0);
-            }
-            container.show_all();
+    let gpus = match &s.dynamic_data.gpus {
+        Ok(gpus) if !gpus.is_empty() => gpus,
+        _ => return,
+    };
+
+    crate::gui::gpu_controls::update_controls(scrolled.upcast_ref(), gpus);
+    update_overview(container.upcast_ref(), gpus);
+
+    let shown: Vec<String> = gpus.iter().map(|gpu| {
+        let engines: Vec<&str> = gpu.engines.iter().map(|(engine, _)| engine.as_str()).collect();
+        format!("{} {} [{}]", gpu.brand, gpu.name, engines.join(","))
+    }).collect();
+    let changed = SHOWN_GPUS.with(|current| {
+        let mut current = current.borrow_mut();
+        if *current == shown {
+            return false;
+        }
+        *current = shown;
+        true
+    });
+    if changed {
+        cards.forall(|child| cards.remove(child));
+        for (i, gpu) in gpus.iter().enumerate() {
+            cards.pack_start(&build_card(state, i, gpu), false, false, 0);
+        }
+        cards.show_all();
+    }
+
+    for (i, gpu) in gpus.iter().enumerate() {
+        update_card(cards.upcast_ref(), i, gpu);
+    }
+}
+
+fn update_overview(container: &gtk::Container, gpus: &[GpuInfo]) {
+    let find = |name: &str| crate::gui::dashboard::find_widget_by_name(container, name);
+    // A single card already says everything the overview would
+    if let Some(frame) = find("gpu_overview_frame") {
+        frame.set_visible(gpus.len() > 1);
+    }
+    if gpus.len() < 2 {
+        return;
+    }
+    let Some(store) = find("gpu_overview_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+        .and_then(|t| t.model())
+        .and_then(|m| m.downcast::<ListStore>().ok())
+    else {
+        return;
+    };
+
+    store.clear();
+    for (i, gpu) in gpus.iter().enumerate() {
+        store.insert_with_values(None, &[
+            (0, &i.to_string()),
+            (1, &format!("{} ({})", gpu.name, gpu.brand)),
+            (2, &format!("{}%", gpu.utilization)),
+            (3, &format!("{} / {}", format_size(gpu.memory_used), format_size(gpu.memory_total))),
+            (4, &format!("{}°C", gpu.temperature)),
+            (5, &format!("{:.1} W", gpu.power_usage as f64 / 1000.0)),
+            (6, &format_frequency(gpu.graphics_clock as u64)),
+        ]);
+    }
+}
+
+fn build_card(state: &Arc<Mutex<AppState>>, i: usize, gpu: &GpuInfo) -> Frame {
+    let frame = Frame::new(None);
+    frame.set_widget_name(&format!("gpu{}_frame", i));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    for (key, class) in [("util", "text-green"), ("mem", "text-cyan")] {
+        let lbl = Label::new(None);
+        lbl.set_widget_name(&format!("gpu{}_{}_lbl", i, key));
+        lbl.set_halign(gtk::Align::Start);
+        lbl.style_context().add_class(class);
+        let bar = ProgressBar::new();
+        bar.set_widget_name(&format!("gpu{}_{}_bar", i, key));
+        vbox.pack_start(&lbl, false, false, 0);
+        vbox.pack_start(&bar, false, false, 0);
+    }
+
+    let grid = Grid::new();
+    grid.set_row_spacing(6);
+    grid.set_column_spacing(6);
+    grid.set_column_homogeneous(true);
+    for (idx, (key, _, color, max, series)) in HISTORY_CHARTS.into_iter().enumerate() {
+        let chart_frame = Frame::new(None);
+        chart_frame.set_widget_name(&format!("gpu{}_{}_chart_frame", i, key));
+        let chart = charts::build_history_chart(state.clone(), &format!("gpu{}_{}_chart", i, key), 70, &[color], max, move |s| {
+            let gpu = s.dynamic_data.gpus.as_ref().ok().and_then(|gpus| gpus.get(i));
+            vec![gpu.map(series).unwrap_or_default()]
+        });
+        chart_frame.add(&chart);
+        grid.attach(&chart_frame, (idx % 2) as i32, (idx / 2) as i32, 1, 1);
+    }
+    vbox.pack_start(&grid, false, false, 0);
+
+    // Per-engine busyness: render/3D, compute, video and copy on Intel,
+    // GFX and VCN on AMD, graphics and the media engines on NVIDIA
+    if !gpu.engines.is_empty() {
+        let engines_frame = Frame::new(Some(" Engines "));
+        let engines_grid = Grid::new();
+        engines_grid.set_border_width(4);
+        engines_grid.set_row_spacing(4);
+        engines_grid.set_column_spacing(8);
+        for (j, (engine, _)) in gpu.engines.iter().enumerate() {
+            let lbl = Label::new(Some(engine));
+            lbl.set_halign(gtk::Align::Start);
+            let bar = ProgressBar::new();
+            bar.set_widget_name(&format!("gpu{}_engine{}_bar", i, j));
+            bar.set_show_text(true);
+            bar.set_hexpand(true);
+            bar.set_valign(gtk::Align::Center);
+            engines_grid.attach(&lbl, 0, j as i32, 1, 1);
+            engines_grid.attach(&bar, 1, j as i32, 1, 1);
+        }
+        engines_frame.add(&engines_grid);
+        vbox.pack_start(&engines_frame, false, false, 0);
+    }
+
+    let details_lbl = Label::new(None);
+    details_lbl.set_widget_name(&format!("gpu{}_details", i));
+    details_lbl.set_halign(gtk::Align::Start);
+    vbox.pack_start(&details_lbl, false, false, 0);
+
+    for key in ["throttle", "warning"] {
+        let lbl = Label::new(None);
+        lbl.set_widget_name(&format!("gpu{}_{}_lbl", i, key));
+        lbl.set_halign(gtk::Align::Start);
+        lbl.set_no_show_all(true);
+        vbox.pack_start(&lbl, false, false, 0);
+    }
+
+    let procs = build_process_list(i);
+    procs.set_no_show_all(true);
+    vbox.pack_start(&procs, false, false, 0);
+
+    frame.add(&vbox);
+    frame
+}
+
+fn update_card(container: &gtk::Container, i: usize, gpu: &GpuInfo) {
+    let find = |key: &str| crate::gui::dashboard::find_widget_by_name(container, &format!("gpu{}_{}", i, key));
+    let label = |key: &str| find(key).and_then(|w| w.downcast::<Label>().ok());
+    let bar = |key: &str| find(key).and_then(|w| w.downcast::<ProgressBar>().ok());
+
+    if let Some(frame) = find("frame").and_then(|w| w.downcast::<Frame>().ok()) {
+        frame.set_label(Some(&format!(" GPU {} - {} ({}) - {}°C ", i, gpu.name, gpu.brand, gpu.temperature)));
+    }
+
+    let mem_percent = if gpu.memory_total > 0 { (gpu.memory_used as f64 / gpu.memory_total as f64) * 100.0 } else { 0.0 };
+    if let Some(l) = label("util_lbl") {
+        l.set_text(&format!("Utilization: {}%", gpu.utilization));
+    }
+    if let Some(b) = bar("util_bar") {
+        b.set_fraction((gpu.utilization as f64 / 100.0).clamp(0.0, 1.0));
+    }
+    if let Some(l) = label("mem_lbl") {
+        l.set_text(&format!("Memory Usage: {:.1}%", mem_percent));
+    }
+    if let Some(b) = bar("mem_bar") {
+        b.set_fraction((mem_percent / 100.0).clamp(0.0, 1.0));
+    }
+
+    let current = [
+        format!("{}%", gpu.utilization),
+        format!("{:.1}%", mem_percent),
+        format!("{}°C", gpu.temperature),
+        format!("{:.1} W", gpu.power_usage as f64 / 1000.0),
+        format_frequency(gpu.graphics_clock as u64),
+    ];
+    for ((key, title, ..), value) in HISTORY_CHARTS.iter().zip(current) {
+        if let Some(frame) = find(&format!("{}_chart_frame", key)).and_then(|w| w.downcast::<Frame>().ok()) {
+            frame.set_label(Some(&format!(" {}: {} ", title, value)));
+        }
+        charts::refresh_chart(container, &format!("gpu{}_{}_chart", i, key));
+    }
+
+    for (j, (_, busy)) in gpu.engines.iter().enumerate() {
+        if let Some(b) = bar(&format!("engine{}_bar", j)) {
+            b.set_fraction((*busy as f64 / 100.0).clamp(0.0, 1.0));
+            b.set_text(Some(&format!("{}%", busy)));
+        }
+    }
+
+    if let Some(l) = label("details") {
+        l.set_text(&details_text(gpu));
+    }
+
+    if let Some(l) = label("throttle_lbl") {
+        let shown = gpu.backend == "NVML" || !gpu.throttle_reasons.is_empty();
+        if shown {
+            // "Idle" alone is the normal resting state, not worth highlighting
+            let active: Vec<&str> = gpu.throttle_reasons.iter().map(|r| r.as_str()).filter(|r| *r != "Idle").collect();
+            l.set_text(&format!("Throttle: {}", if active.is_empty() { "None".to_string() } else { active.join(", ") }));
+            set_status_class(&l, if active.is_empty() { "text-green" } else { "text-orange" });
+        }
+        l.set_visible(shown);
+    }
+    if let Some(l) = label("warning_lbl") {
+        if let Some(err) = &gpu.telemetry_error {
+            l.set_text(&format!("[WARN] {}", err));
+            set_status_class(&l, "text-orange");
+        }
+        l.set_visible(gpu.telemetry_error.is_some());
+    }
+
+    if let Some(tree) = find("procs").and_then(|w| w.downcast::<TreeView>().ok()) {
+        if let Some(store) = tree.model().and_then(|m| m.downcast::<ListStore>().ok()) {
+            fill_process_list(&store, gpu);
         }
+        tree.set_visible(!gpu.processes.is_empty());
+    }
+}
+
+fn set_status_class(label: &Label, class: &str) {
+    let ctx = label.style_context();
+    for c in ["text-green", "text-orange", "text-red"] {
+        ctx.remove_class(c);
+    }
+    ctx.add_class(class);
+}
+
+fn details_text(gpu: &GpuInfo) -> String {
+    let power = match gpu.power_limit {
+        Some(limit) => format!("{:.2} W / {:.0} W", gpu.power_usage as f64 / 1000.0, limit as f64 / 1000.0),
+        None => format!("{:.2} W", gpu.power_usage as f64 / 1000.0),
+    };
+    // NVIDIA drivers report fan speed as a percentage of max, hwmon as RPM
+    let fan_unit = if gpu.brand == "NVIDIA" { "%" } else { " RPM" };
+    let mut details = format!(
+        "Memory: {} / {}\nPower: {}\nGraphics Clock: {}\nMemory Clock: {}\nMemory Temp: {}\nFan Speed: {}\nPCIe: {}\nDriver: {} ({})",
+        format_size(gpu.memory_used), format_size(gpu.memory_total),
+        power,
+        format_frequency(gpu.graphics_clock as u64),
+        format_frequency(gpu.memory_clock as u64),
+        gpu.memory_temperature.map(|t| format!("{}°C", t)).unwrap_or_else(|| "N/A".to_string()),
+        gpu.fan_speed.map(|f| format!("{}{}", f, fan_unit)).unwrap_or_else(|| "N/A".to_string()),
+        if let (Some(gen), Some(width)) = (gpu.pci_link_gen, gpu.pci_link_width) { format!("Gen {} x{}", gen, width) } else { "N/A".to_string() },
+        gpu.driver_version, gpu.backend
+    );
+    if let Some(hotspot) = gpu.hotspot_temperature {
+        details.push_str(&format!("\nJunction Temp: {}°C", hotspot));
+    }
+    if let Some(pwm) = gpu.fan_pwm {
+        details.push_str(&format!("\nFan PWM: {}%", pwm));
+    }
+    if let Some(activity) = gpu.memory_activity {
+        details.push_str(&format!("\nMemory Controller: {}% busy", activity));
+    }
+    if let Some(rc6) = gpu.rc6_residency {
+        details.push_str(&format!("\nRC6 Residency: {}%", rc6));
+    }
+    if gpu.gtt_total > 0 {
+        details.push_str(&format!("\nGTT: {} / {}", format_size(gpu.gtt_used), format_size(gpu.gtt_total)));
+    }
+    if gpu.power_rails.len() > 1 {
+        let rails: Vec<String> = gpu.power_rails.iter().map(|(rail, mw)| format!("{} {:.1} W", rail, *mw as f64 / 1000.0)).collect();
+        details.push_str(&format!("\nPower Rails: {}", rails.join(", ")));
+    }
+    for (label, levels) in [("GFX DPM", &gpu.sclk_levels), ("Memory DPM", &gpu.mclk_levels)] {
+        if !levels.is_empty() {
+            let levels: Vec<String> = levels.iter()
+                .map(|(mhz, active)| if *active { format!("[{} MHz]", mhz) } else { format!("{} MHz", mhz) })
+                .collect();
+            details.push_str(&format!("\n{}: {}", label, levels.join("  ")));
+        }
+    }
+    if let (Some(corrected), Some(uncorrected)) = (gpu.ecc_corrected, gpu.ecc_uncorrected) {
+        details.push_str(&format!("\nECC Errors: {} corrected, {} uncorrected", corrected, uncorrected));
+    }
+    details
+}
+
+fn build_process_list(i: usize) -> TreeView {
+    let store = ListStore::new(&[
+        glib::Type::STRING, // PID
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Memory
+        glib::Type::STRING, // SM
+    ]);
+
+    let tree = TreeView::with_model(&store);
+    tree.set_widget_name(&format!("gpu{}_procs", i));
+    for (title, id) in &[("PID", 0), ("Process", 1), ("Type", 2), ("GPU Memory", 3), ("SM", 4)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+    tree
+}
+
+fn fill_process_list(store: &ListStore, gpu: &GpuInfo) {
+    store.clear();
+    for p in &gpu.processes {
+        store.insert_with_values(None, &[
+            (0, &p.pid.to_string()),
+            (1, &p.name),
+            (2, &p.kind),
+            (3, &p.memory_used.map(format_size).unwrap_or_else(|| "N/A".to_string())),
+            (4, &p.sm_utilization.map(|u| format!("{}%", u)).unwrap_or_else(|| "N/A".to_string())),
+        ]);
     }
 }
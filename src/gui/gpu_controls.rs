@@ -0,0 +1,402 @@
+use gtk::prelude::*;
+use gtk::{Box, Orientation, Label, Frame, Button, ComboBoxText, SpinButton, CheckButton, Entry};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use glib::clone;
+use parking_lot::Mutex;
+use crate::gpu_control::{self, GpuSetting};
+use crate::types::{AmdGpuControls, AppState, GpuInfo, NvidiaGpuControls};
+
+thread_local! {
+    static SHOWN_CONTROL_GPUS: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+enum Selected {
+    Amd(AmdGpuControls),
+    Nvidia(u32, NvidiaGpuControls),
+}
+
+#[derive(Clone)]
+struct Controls {
+    combo: ComboBoxText,
+    perf_row: Box,
+    perf_combo: ComboBoxText,
+    power_row: Box,
+    power_spin: SpinButton,
+    power_range_lbl: Label,
+    fan_row: Box,
+    fan_mode: ComboBoxText,
+    fan_spin: SpinButton,
+    curve_row: Box,
+    curve_entry: Entry,
+    persist_row: Box,
+    persist_check: CheckButton,
+    status_lbl: Label,
+    selected: Rc<RefCell<Option<Selected>>>,
+}
+
+fn row(label: &str) -> Box {
+    let hbox = Box::new(Orientation::Horizontal, 5);
+    let lbl = Label::new(Some(label));
+    lbl.set_width_chars(20);
+    lbl.set_xalign(0.0);
+    hbox.pack_start(&lbl, false, false, 0);
+    hbox.set_no_show_all(true);
+    hbox
+}
+
+fn apply_button(hbox: &Box) -> Button {
+    let btn = Button::with_label("Apply");
+    btn.style_context().add_class("suggested-action");
+    hbox.pack_start(&btn, false, false, 0);
+    btn
+}
+
+/// Controls frame for AMD sysfs and NVIDIA power settings. Built once; the GPU list
+/// is filled in by [`update_controls`].
+pub fn build_controls_frame(state: &Arc<Mutex<AppState>>) -> Frame {
+    let frame = Frame::new(Some(" GPU Controls "));
+    frame.set_widget_name("gpu_controls_frame");
+    frame.set_no_show_all(true);
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let select_row = Box::new(Orientation::Horizontal, 5);
+    let select_lbl = Label::new(Some("GPU"));
+    select_lbl.set_width_chars(20);
+    select_lbl.set_xalign(0.0);
+    let combo = ComboBoxText::new();
+    combo.set_widget_name("gpu_control_combo");
+    let restore_btn = Button::with_label("Restore Defaults");
+    restore_btn.style_context().add_class("destructive-action");
+    select_row.pack_start(&select_lbl, false, false, 0);
+    select_row.pack_start(&combo, false, false, 0);
+    select_row.pack_end(&restore_btn, false, false, 0);
+    vbox.pack_start(&select_row, false, false, 0);
+
+    let perf_row = row("Performance Level");
+    let perf_combo = ComboBoxText::new();
+    for level in gpu_control::PERFORMANCE_LEVELS {
+        perf_combo.append(Some(level), level);
+    }
+    perf_row.pack_start(&perf_combo, false, false, 0);
+    let perf_btn = apply_button(&perf_row);
+    vbox.pack_start(&perf_row, false, false, 0);
+
+    let power_row = row("Power Limit (W)");
+    let power_spin = SpinButton::with_range(1.0, 1000.0, 1.0);
+    let power_range_lbl = Label::new(None);
+    power_row.pack_start(&power_spin, false, false, 0);
+    let power_btn = apply_button(&power_row);
+    power_row.pack_start(&power_range_lbl, false, false, 0);
+    vbox.pack_start(&power_row, false, false, 0);
+
+    let fan_row = row("Fan");
+    let fan_mode = ComboBoxText::new();
+    fan_mode.append(Some("auto"), "Automatic");
+    fan_mode.append(Some("fixed"), "Fixed speed (%)");
+    let fan_spin = SpinButton::with_range(0.0, 100.0, 5.0);
+    fan_row.pack_start(&fan_mode, false, false, 0);
+    fan_row.pack_start(&fan_spin, false, false, 0);
+    let fan_btn = apply_button(&fan_row);
+    vbox.pack_start(&fan_row, false, false, 0);
+
+    let curve_row = row("Fan Curve (°C:%)");
+    let curve_entry = Entry::new();
+    curve_entry.set_width_chars(40);
+    curve_row.pack_start(&curve_entry, false, false, 0);
+    let curve_btn = apply_button(&curve_row);
+    vbox.pack_start(&curve_row, false, false, 0);
+
+    let persist_row = row("Persistence Mode");
+    let persist_check = CheckButton::with_label("Keep the driver loaded when idle");
+    persist_row.pack_start(&persist_check, false, false, 0);
+    let persist_btn = apply_button(&persist_row);
+    vbox.pack_start(&persist_row, false, false, 0);
+    super::disable_in_safe_mode(state, &[&restore_btn, &perf_btn, &power_btn, &fan_btn, &curve_btn, &persist_btn]);
+
+    let status_lbl = Label::new(Some("Changes need root (pkexec) and are reverted when puls-g3 exits."));
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.style_context().add_class("text-cyan");
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    select_row.show_all();
+    status_lbl.show();
+    for r in [&perf_row, &power_row, &fan_row, &curve_row, &persist_row] {
+        for child in r.children() {
+            child.show_all();
+        }
+    }
+    vbox.show();
+    frame.add(&vbox);
+
+    let c = Controls {
+        combo: combo.clone(),
+        perf_row,
+        perf_combo,
+        power_row,
+        power_spin,
+        power_range_lbl,
+        fan_row,
+        fan_mode,
+        fan_spin,
+        curve_row,
+        curve_entry,
+        persist_row,
+        persist_check,
+        status_lbl,
+        selected: Rc::new(RefCell::new(None)),
+    };
+
+    combo.connect_changed(clone!(@strong c => move |_| refresh(&c)));
+
+    perf_btn.connect_clicked(clone!(@strong c => move |btn| {
+        let Some(amd) = selected_amd(&c) else { return };
+        let Some(level) = c.perf_combo.active_id() else { return };
+        let original = amd.performance_level.as_deref().map(|l| gpu_control::performance_level_setting(&amd, l));
+        let key = format!("{}:performance_level", amd.device_path);
+        let setting = gpu_control::performance_level_setting(&amd, &level);
+        let text = format!("Set the performance level to \"{}\"?", level);
+        apply(btn, &c, &key, &text, setting, original);
+    }));
+
+    power_btn.connect_clicked(clone!(@strong c => move |btn| {
+        let milliwatts = (c.power_spin.value() * 1000.0) as u32;
+        let text = format!("Set the power limit to {} W?", c.power_spin.value_as_int());
+        let (key, setting, original) = match &*c.selected.borrow() {
+            Some(Selected::Amd(amd)) => {
+                let setting = match gpu_control::power_cap_setting(amd, milliwatts) {
+                    Ok(s) => s,
+                    Err(e) => return set_status(&c.status_lbl, &format!("[FAIL] {}", e), "text-red"),
+                };
+                let original = amd.power_cap.and_then(|mw| gpu_control::power_cap_setting(amd, mw).ok());
+                (format!("{}:power_cap", amd.device_path), setting, original)
+            }
+            Some(Selected::Nvidia(index, nv)) => {
+                if let (Some(min), Some(max)) = (nv.power_limit_min, nv.power_limit_max) {
+                    if milliwatts < min || milliwatts > max {
+                        return set_status(&c.status_lbl, &format!("[FAIL] {} W is outside {}-{} W", milliwatts / 1000, min / 1000, max / 1000), "text-red");
+                    }
+                }
+                let original = nv.power_limit.map(|mw| GpuSetting::NvidiaPowerLimit { index: *index, milliwatts: mw });
+                (format!("nvidia{}:power_limit", index), GpuSetting::NvidiaPowerLimit { index: *index, milliwatts }, original)
+            }
+            None => return,
+        };
+        apply(btn, &c, &key, &text, setting, original);
+    }));
+
+    fan_btn.connect_clicked(clone!(@strong c => move |btn| {
+        let Some(amd) = selected_amd(&c) else { return };
+        let percent = (c.fan_mode.active_id().as_deref() == Some("fixed")).then(|| c.fan_spin.value_as_int() as u32);
+        let setting = match gpu_control::fan_setting(&amd, percent) {
+            Ok(s) => s,
+            Err(e) => return set_status(&c.status_lbl, &format!("[FAIL] {}", e), "text-red"),
+        };
+        let text = match percent {
+            Some(p) => format!("Lock the fan at {}%? A low fixed speed can overheat the GPU under load.", p),
+            None => "Return fan control to the firmware?".to_string(),
+        };
+        let key = format!("{}:fan", amd.device_path);
+        apply(btn, &c, &key, &text, setting, gpu_control::original_fan_setting(&amd));
+    }));
+
+    curve_btn.connect_clicked(clone!(@strong c => move |btn| {
+        let Some(amd) = selected_amd(&c) else { return };
+        let Some(curve) = &amd.fan_curve else { return };
+        let points = match gpu_control::parse_curve_points(&c.curve_entry.text(), curve) {
+            Ok(p) => p,
+            Err(e) => return set_status(&c.status_lbl, &format!("[FAIL] {}", e), "text-red"),
+        };
+        let text = format!("Apply the fan curve {}?", format_curve(&points));
+        let key = format!("{}:fan_curve", amd.device_path);
+        let original = gpu_control::fan_curve_setting(&amd, &curve.points);
+        apply(btn, &c, &key, &text, gpu_control::fan_curve_setting(&amd, &points), Some(original));
+    }));
+
+    persist_btn.connect_clicked(clone!(@strong c => move |btn| {
+        let (index, nv) = match &*c.selected.borrow() {
+            Some(Selected::Nvidia(index, nv)) => (*index, nv.clone()),
+            _ => return,
+        };
+        let enabled = c.persist_check.is_active();
+        let text = format!("{} persistence mode?", if enabled { "Enable" } else { "Disable" });
+        let original = nv.persistence.map(|p| GpuSetting::NvidiaPersistence { index, enabled: p });
+        let key = format!("nvidia{}:persistence", index);
+        apply(btn, &c, &key, &text, GpuSetting::NvidiaPersistence { index, enabled }, original);
+    }));
+
+    restore_btn.connect_clicked(clone!(@strong c => move |btn| {
+        if !gpu_control::has_changes() {
+            return set_status(&c.status_lbl, "Nothing to restore", "text-cyan");
+        }
+        if !confirm(btn, "Restore all GPU settings changed in this session?") {
+            return;
+        }
+        let errors = gpu_control::restore_all();
+        if errors.is_empty() {
+            set_status(&c.status_lbl, "[OK] Restored original GPU settings", "text-green");
+        } else {
+            set_status(&c.status_lbl, &format!("[FAIL] {}", errors.join("; ")), "text-red");
+        }
+        refresh(&c);
+    }));
+
+    frame
+}
+
+// Cloned out so no RefCell borrow is held while the confirmation dialog runs
+fn selected_amd(c: &Controls) -> Option<AmdGpuControls> {
+    match &*c.selected.borrow() {
+        Some(Selected::Amd(amd)) => Some(amd.clone()),
+        _ => None,
+    }
+}
+
+fn format_curve(points: &[(u32, u32)]) -> String {
+    points.iter().map(|(t, s)| format!("{}:{}", t, s)).collect::<Vec<_>>().join(", ")
+}
+
+fn set_status(lbl: &Label, text: &str, class: &str) {
+    let ctx = lbl.style_context();
+    for c in ["text-green", "text-red", "text-cyan"] {
+        ctx.remove_class(c);
+    }
+    ctx.add_class(class);
+    lbl.set_text(text);
+}
+
+fn confirm(widget: &impl IsA<gtk::Widget>, text: &str) -> bool {
+    super::confirm(widget, text, Some("The previous value is restored when puls-g3 exits or with Restore Defaults."))
+}
+
+fn apply(btn: &Button, c: &Controls, key: &str, text: &str, setting: GpuSetting, original: Option<GpuSetting>) {
+    if !confirm(btn, text) {
+        return;
+    }
+    match gpu_control::apply(key, &setting, original) {
+        Ok(()) => set_status(&c.status_lbl, &format!("[OK] {}", text.split('?').next().unwrap_or(text)), "text-green"),
+        Err(e) => set_status(&c.status_lbl, &format!("[FAIL] {}", e), "text-red"),
+    }
+    // Re-read what the hardware actually accepted
+    glib::idle_add_local_once(clone!(@strong c => move || refresh(&c)));
+}
+
+/// Reads the selected GPU's current settings and shows the rows it supports.
+fn refresh(c: &Controls) {
+    let id = c.combo.active_id().map(|s| s.to_string()).unwrap_or_default();
+    let selected = if let Some(path) = id.strip_prefix("amd:") {
+        Some(Selected::Amd(gpu_control::read_amd_controls(path)))
+    } else if let Some(index) = id.strip_prefix("nvidia:").and_then(|i| i.parse::<u32>().ok()) {
+        match gpu_control::read_nvidia_controls(index) {
+            Ok(nv) => Some(Selected::Nvidia(index, nv)),
+            Err(e) => {
+                set_status(&c.status_lbl, &format!("[FAIL] NVML unavailable: {}", e), "text-red");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let (mut perf, mut power, mut fan, mut curve, mut persist) = (false, false, false, false, false);
+    let set_power = |value: Option<u32>, min: Option<u32>, max: Option<u32>, default: Option<u32>| {
+        if let (Some(min), Some(max)) = (min, max) {
+            c.power_spin.set_range((min / 1000) as f64, (max / 1000) as f64);
+        }
+        if let Some(v) = value {
+            c.power_spin.set_value((v / 1000) as f64);
+        }
+        let range = match (min, max) {
+            (Some(min), Some(max)) => format!("{}-{} W", min / 1000, max / 1000),
+            _ => String::new(),
+        };
+        let default = default.map(|d| format!(", default {} W", d / 1000)).unwrap_or_default();
+        c.power_range_lbl.set_text(&format!("{}{}", range, default));
+    };
+
+    match &selected {
+        Some(Selected::Amd(amd)) => {
+            if let Some(level) = &amd.performance_level {
+                perf = true;
+                c.perf_combo.set_active_id(Some(level));
+            }
+            if amd.power_cap.is_some() {
+                power = true;
+                set_power(amd.power_cap, amd.power_cap_min, amd.power_cap_max, amd.power_cap_default);
+            }
+            if let Some(enable) = amd.pwm_enable {
+                fan = true;
+                c.fan_mode.set_active_id(Some(if enable == 1 { "fixed" } else { "auto" }));
+                if let Some(pwm) = amd.pwm {
+                    c.fan_spin.set_value((pwm * 100 / 255) as f64);
+                }
+            }
+            if let Some(fc) = &amd.fan_curve {
+                curve = true;
+                c.curve_entry.set_text(&format_curve(&fc.points));
+                c.curve_entry.set_tooltip_text(Some(&format!(
+                    "{} points, {}-{}°C, {}-{}%", fc.points.len(), fc.temp_range.0, fc.temp_range.1, fc.speed_range.0, fc.speed_range.1
+                )));
+            }
+        }
+        Some(Selected::Nvidia(_, nv)) => {
+            if nv.power_limit.is_some() {
+                power = true;
+                set_power(nv.power_limit, nv.power_limit_min, nv.power_limit_max, nv.power_limit_default);
+            }
+            if let Some(p) = nv.persistence {
+                persist = true;
+                c.persist_check.set_active(p);
+            }
+        }
+        None => {}
+    }
+
+    for (r, visible) in [(&c.perf_row, perf), (&c.power_row, power), (&c.fan_row, fan), (&c.curve_row, curve), (&c.persist_row, persist)] {
+        r.set_visible(visible);
+    }
+    *c.selected.borrow_mut() = selected;
+}
+
+/// Fills the GPU selector with controllable cards; hides the frame if there are none.
+pub fn update_controls(container: &gtk::Container, gpus: &[GpuInfo]) {
+    let entries: Vec<(String, String)> = gpus.iter().enumerate().filter_map(|(i, gpu)| {
+        let id = match (&gpu.nvidia_index, &gpu.sysfs_path) {
+            (Some(index), _) => format!("nvidia:{}", index),
+            (None, Some(path)) if gpu.brand == "AMD" => format!("amd:{}", path),
+            _ => return None,
+        };
+        Some((id, format!("GPU {} - {}", i, gpu.name)))
+    }).collect();
+
+    let changed = SHOWN_CONTROL_GPUS.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown == entries {
+            return false;
+        }
+        *shown = entries.clone();
+        true
+    });
+    if !changed {
+        return;
+    }
+
+    if let Some(frame) = crate::gui::dashboard::find_widget_by_name(container, "gpu_controls_frame") {
+        frame.set_visible(!entries.is_empty());
+    }
+    let Some(combo) = crate::gui::dashboard::find_widget_by_name(container, "gpu_control_combo")
+        .and_then(|w| w.downcast::<ComboBoxText>().ok())
+    else {
+        return;
+    };
+    let active = combo.active_id();
+    combo.remove_all();
+    for (id, label) in &entries {
+        combo.append(Some(id), label);
+    }
+    if active.as_ref().is_none_or(|a| !combo.set_active_id(Some(a))) {
+        combo.set_active(Some(0));
+    }
+}
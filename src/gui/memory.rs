@@ -1,11 +1,39 @@
 use gtk::prelude::*;
-use gtk::{Box, Orientation, Label, ProgressBar, Frame, Widget};
+use gtk::{Box, Orientation, Label, ProgressBar, Frame, Widget,
+          TreeView, TreeViewColumn, CellRendererText, ListStore, ScrolledWindow};
 use std::sync::Arc;
 use parking_lot::Mutex;
-use crate::types::AppState;
+use crate::types::{AppState, MemInfo, ZramDevice};
+use crate::gui::charts::{self, Rgb};
 use crate::utils::format_size;
 
-pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
+const SEGMENTS: [(&str, Rgb); 7] = [
+    ("Apps", charts::GREEN),
+    ("Shmem", charts::MAGENTA),
+    ("Kernel", charts::RED),
+    ("Page Cache", charts::CYAN),
+    ("Buffers", charts::BLUE),
+    ("Slab (reclaimable)", charts::YELLOW),
+    ("Free", charts::GREY),
+];
+
+fn segment_values(m: &MemInfo) -> [u64; 7] {
+    [
+        m.anon,
+        m.shmem,
+        m.slab_unreclaimable + m.kernel_stack + m.page_tables,
+        m.cached.saturating_sub(m.shmem),
+        m.buffers,
+        m.slab_reclaimable,
+        m.free,
+    ]
+}
+
+fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", (rgb.0 * 255.0) as u8, (rgb.1 * 255.0) as u8, (rgb.2 * 255.0) as u8)
+}
+
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 10);
     container.set_border_width(10);
 
@@ -39,6 +67,136 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
 
     container.pack_start(&gauges_box, false, false, 0);
 
+    let breakdown_frame = Frame::new(Some(" Memory Breakdown "));
+    let breakdown_box = Box::new(Orientation::Vertical, 5);
+    breakdown_box.set_border_width(8);
+    let bar = charts::build_stacked_bar(state, "mem_breakdown_bar", 22, |s| {
+        segment_values(&s.dynamic_data.memory.meminfo)
+            .iter()
+            .zip(SEGMENTS.iter())
+            .map(|(v, (_, color))| (*v as f64, *color))
+            .collect()
+    });
+    breakdown_box.pack_start(&bar, false, false, 0);
+    let legend_lbl = Label::new(None);
+    legend_lbl.set_widget_name("mem_breakdown_legend");
+    legend_lbl.set_halign(gtk::Align::Start);
+    legend_lbl.set_line_wrap(true);
+    breakdown_box.pack_start(&legend_lbl, false, false, 0);
+    breakdown_frame.add(&breakdown_box);
+    container.pack_start(&breakdown_frame, false, false, 0);
+
+    let swap_dev_frame = Frame::new(Some(" Swap Devices / zram / zswap "));
+    let swap_store = ListStore::new(&[
+        glib::Type::STRING, // Device
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Size
+        glib::Type::STRING, // Used
+        glib::Type::STRING, // Priority
+        glib::Type::STRING, // Compression
+    ]);
+    let swap_tree = TreeView::with_model(&swap_store);
+    swap_tree.set_widget_name("swap_devices_tree");
+    for (title, id) in &[("Device", 0), ("Type", 1), ("Size", 2), ("Used", 3), ("Priority", 4), ("Compression", 5)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        swap_tree.append_column(&col);
+    }
+    swap_dev_frame.add(&swap_tree);
+    container.pack_start(&swap_dev_frame, false, false, 0);
+
+    let oom_frame = Frame::new(Some(" OOM & Leak Watch "));
+    let oom_box = Box::new(Orientation::Vertical, 5);
+    oom_box.set_border_width(8);
+    let oom_lbl = Label::new(Some("OOM kills since boot: N/A"));
+    oom_lbl.set_widget_name("oom_status_lbl");
+    oom_lbl.set_halign(gtk::Align::Start);
+    oom_box.pack_start(&oom_lbl, false, false, 0);
+
+    let oom_store = ListStore::new(&[
+        glib::Type::STRING, // Time
+        glib::Type::STRING, // Process
+        glib::Type::STRING, // PID
+        glib::Type::STRING, // Cgroup
+        glib::Type::STRING, // RSS
+        glib::Type::STRING, // Scope
+    ]);
+    let oom_tree = TreeView::with_model(&oom_store);
+    oom_tree.set_widget_name("oom_kills_tree");
+    for (title, id) in &[("Time", 0), ("Process", 1), ("PID", 2), ("Cgroup", 3), ("RSS", 4), ("Scope", 5)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        oom_tree.append_column(&col);
+    }
+    oom_box.pack_start(&oom_tree, false, false, 0);
+
+    let leak_lbl = Label::new(Some("Possible leaks: none"));
+    leak_lbl.set_widget_name("leak_status_lbl");
+    leak_lbl.set_halign(gtk::Align::Start);
+    oom_box.pack_start(&leak_lbl, false, false, 0);
+
+    let leak_store = ListStore::new(&[
+        glib::Type::STRING, // PID
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // RSS
+        glib::Type::STRING, // Growth
+        glib::Type::STRING, // Rate
+    ]);
+    let leak_tree = TreeView::with_model(&leak_store);
+    leak_tree.set_widget_name("leak_suspects_tree");
+    for (title, id) in &[("PID", 0), ("Name", 1), ("RSS", 2), ("Growth", 3), ("Rate", 4)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        leak_tree.append_column(&col);
+    }
+    oom_box.pack_start(&leak_tree, false, false, 0);
+    oom_frame.add(&oom_box);
+    container.pack_start(&oom_frame, false, false, 0);
+
+    let dimm_frame = Frame::new(Some(" Memory Modules "));
+    let dimm_box = Box::new(Orientation::Vertical, 5);
+    let dimm_store = ListStore::new(&[
+        glib::Type::STRING, // Slot
+        glib::Type::STRING, // Size
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Speed
+        glib::Type::STRING, // Configured
+        glib::Type::STRING, // Manufacturer
+        glib::Type::STRING, // Part Number
+        glib::Type::STRING, // Rank
+    ]);
+    let dimm_tree = TreeView::with_model(&dimm_store);
+    dimm_tree.set_widget_name("dimm_tree");
+    for (title, id) in &[("Slot", 0), ("Size", 1), ("Type", 2), ("Speed", 3), ("Configured", 4),
+                         ("Manufacturer", 5), ("Part Number", 6), ("Rank", 7)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        dimm_tree.append_column(&col);
+    }
+    dimm_box.pack_start(&dimm_tree, false, false, 0);
+    let dimm_status_lbl = Label::new(None);
+    dimm_status_lbl.set_widget_name("dimm_status_lbl");
+    dimm_status_lbl.set_halign(gtk::Align::Start);
+    dimm_box.pack_start(&dimm_status_lbl, false, false, 0);
+    dimm_frame.add(&dimm_box);
+    container.pack_start(&dimm_frame, false, false, 0);
+
     let details_frame = Frame::new(Some(" Details "));
     let details_box = Box::new(Orientation::Vertical, 5);
     details_box.set_border_width(8);
@@ -50,7 +208,11 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     
     container.pack_start(&details_frame, true, true, 0);
 
-    container.upcast::<Widget>()
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_vexpand(true);
+    scrolled.set_hexpand(true);
+    scrolled.add(&container);
+    scrolled.upcast::<Widget>()
 }
 
 pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
@@ -64,6 +226,15 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
     let swap_lbl = crate::gui::dashboard::find_widget_by_name(&container, "swap_percent_lbl").and_then(|w| w.downcast::<Label>().ok());
     let swap_bar = crate::gui::dashboard::find_widget_by_name(&container, "swap_percent_bar").and_then(|w| w.downcast::<ProgressBar>().ok());
     let details_lbl = crate::gui::dashboard::find_widget_by_name(&container, "details_lbl").and_then(|w| w.downcast::<Label>().ok());
+    let legend_lbl = crate::gui::dashboard::find_widget_by_name(&container, "mem_breakdown_legend").and_then(|w| w.downcast::<Label>().ok());
+    let swap_tree = crate::gui::dashboard::find_widget_by_name(&container, "swap_devices_tree").and_then(|w| w.downcast::<TreeView>().ok());
+    let oom_lbl = crate::gui::dashboard::find_widget_by_name(&container, "oom_status_lbl").and_then(|w| w.downcast::<Label>().ok());
+    let oom_tree = crate::gui::dashboard::find_widget_by_name(&container, "oom_kills_tree").and_then(|w| w.downcast::<TreeView>().ok());
+    let leak_lbl = crate::gui::dashboard::find_widget_by_name(&container, "leak_status_lbl").and_then(|w| w.downcast::<Label>().ok());
+    let leak_tree = crate::gui::dashboard::find_widget_by_name(&container, "leak_suspects_tree").and_then(|w| w.downcast::<TreeView>().ok());
+    let dimm_tree = crate::gui::dashboard::find_widget_by_name(&container, "dimm_tree").and_then(|w| w.downcast::<TreeView>().ok());
+    let dimm_status_lbl = crate::gui::dashboard::find_widget_by_name(&container, "dimm_status_lbl").and_then(|w| w.downcast::<Label>().ok());
+    charts::refresh_chart(&container, "mem_breakdown_bar");
 
     let s = state.lock();
     let usage = &s.dynamic_data.global_usage;
@@ -87,22 +258,204 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
 
     if let Some(lbl) = details_lbl {
         let mem_temp_str = s.dynamic_data.sensors.iter()
+            .filter(|sensor| !sensor.hidden)
             .find(|sensor| {
                 let lower = sensor.label.to_lowercase();
                 lower.contains("dimm") || lower.contains("dram") || lower.contains("memory") || lower.contains("sodimm")
+                    // SPD hub / JC-42.4 thermal sensors sit on the DIMMs themselves
+                    || (sensor.sensor_type == "temp" && (sensor.chip.starts_with("spd5118") || sensor.chip.starts_with("jc42")))
             })
             .map(|sensor| format!("{:.1}°C", sensor.temp))
             .unwrap_or_else(|| "N/A".to_string());
 
+        let m = &s.dynamic_data.memory.meminfo;
         let total_mem = format_size(usage.mem_total);
         let used_mem = format_size(usage.mem_used);
         let cached_mem = format_size(usage.mem_cached);
         let free_mem = format_size(usage.mem_total.saturating_sub(usage.mem_used));
+        let commit_pct = if m.commit_limit > 0 { m.committed as f64 / m.commit_limit as f64 * 100.0 } else { 0.0 };
 
         lbl.set_text(&format!(
-            "Total Memory: {}\nUsed Memory: {}\nCached / Buffers: {}\nFree / Available: {}\nType: {}\nGeneration: {}\nSpeed: {}\nTemperature: {}",
-            total_mem, used_mem, cached_mem, free_mem, 
+            "Total Memory: {}\nUsed Memory: {}\nCached / Buffers: {}\nFree / Available: {}\n\
+             Buffers: {} | Page Cache: {} | Shmem: {}\n\
+             Slab: {} reclaimable / {} unreclaimable\n\
+             Anon: {} | Mapped: {} | Kernel Stack: {} | Page Tables: {}\n\
+             Dirty: {} | Writeback: {}\n\
+             HugePages: {} / {} free ({} each)\n\
+             Committed: {} / {} limit ({:.1}%)\n\
+             Type: {}\nGeneration: {}\nSpeed: {}\nTemperature: {}",
+            total_mem, used_mem, cached_mem, free_mem,
+            format_size(m.buffers), format_size(m.cached), format_size(m.shmem),
+            format_size(m.slab_reclaimable), format_size(m.slab_unreclaimable),
+            format_size(m.anon), format_size(m.mapped), format_size(m.kernel_stack), format_size(m.page_tables),
+            format_size(m.dirty), format_size(m.writeback),
+            m.hugepages_total, m.hugepages_free, format_size(m.hugepage_size),
+            format_size(m.committed), format_size(m.commit_limit), commit_pct,
             usage.memory_type, usage.memory_generation, usage.memory_speed, mem_temp_str
         ));
     }
+
+    if let Some(lbl) = legend_lbl {
+        let values = segment_values(&s.dynamic_data.memory.meminfo);
+        let parts: Vec<String> = SEGMENTS.iter().zip(values.iter())
+            .map(|((name, color), v)| format!("<span foreground=\"{}\">■</span> {} {}", hex(*color), name, format_size(*v)))
+            .collect();
+        lbl.set_markup(&parts.join("   "));
+    }
+
+    if let Some(store) = swap_tree.and_then(|t| t.model()).and_then(|m| m.downcast::<ListStore>().ok()) {
+        let memory = &s.dynamic_data.memory;
+        store.clear();
+        for swap in &memory.swaps {
+            let dev_name = swap.name.rsplit('/').next().unwrap_or(&swap.name);
+            let compression = memory.zram.iter()
+                .find(|z| z.name == dev_name)
+                .map(zram_ratio)
+                .unwrap_or_else(|| "-".to_string());
+            store.insert_with_values(None, &[
+                (0, &swap.name),
+                (1, &swap.swap_type),
+                (2, &format_size(swap.size)),
+                (3, &format_size(swap.used)),
+                (4, &swap.priority.to_string()),
+                (5, &compression),
+            ]);
+        }
+        for zram in memory.zram.iter().filter(|z| !memory.swaps.iter().any(|sw| sw.name.ends_with(&z.name))) {
+            store.insert_with_values(None, &[
+                (0, &format!("/dev/{}", zram.name)),
+                (1, &"zram".to_string()),
+                (2, &format_size(zram.disk_size)),
+                (3, &format_size(zram.orig_data_size)),
+                (4, &"-".to_string()),
+                (5, &zram_ratio(zram)),
+            ]);
+        }
+        if let Some(zswap) = &memory.zswap {
+            let ratio = if zswap.pool_size > 0 {
+                format!("{:.2}x ({})", zswap.stored as f64 / zswap.pool_size as f64, zswap.compressor)
+            } else {
+                zswap.compressor.clone()
+            };
+            store.insert_with_values(None, &[
+                (0, &"zswap".to_string()),
+                (1, &(if zswap.enabled { "enabled" } else { "disabled" }).to_string()),
+                (2, &format!("max {}% RAM", zswap.max_pool_percent)),
+                (3, &format!("{} in {}", format_size(zswap.stored), format_size(zswap.pool_size))),
+                (4, &"-".to_string()),
+                (5, &ratio),
+            ]);
+        }
+    }
+
+    let oom = &s.dynamic_data.oom;
+    if let Some(lbl) = oom_lbl {
+        let ctx = lbl.style_context();
+        ctx.remove_class("text-red");
+        ctx.remove_class("text-green");
+        match oom.kill_count {
+            Some(0) => {
+                lbl.set_text("[OK] OOM kills since boot: 0");
+                ctx.add_class("text-green");
+            }
+            Some(n) => {
+                lbl.set_text(&format!("[FAIL] OOM kills since boot: {}", n));
+                ctx.add_class("text-red");
+            }
+            None => lbl.set_text("OOM kills since boot: N/A"),
+        }
+    }
+
+    if let Some(store) = oom_tree.and_then(|t| t.model()).and_then(|m| m.downcast::<ListStore>().ok()) {
+        store.clear();
+        for kill in &oom.kills {
+            let cgroup = if kill.cgroup.is_empty() { "-".to_string() } else { kill.cgroup.clone() };
+            store.insert_with_values(None, &[
+                (0, &kill.timestamp),
+                (1, &kill.process),
+                (2, &kill.pid.to_string()),
+                (3, &cgroup),
+                (4, &format_size(kill.anon_rss + kill.file_rss + kill.shmem_rss)),
+                (5, &(if kill.global { "system" } else { "cgroup limit" }).to_string()),
+            ]);
+        }
+    }
+
+    if let Some(lbl) = leak_lbl {
+        let ctx = lbl.style_context();
+        if oom.leak_suspects.is_empty() {
+            lbl.set_text("Possible leaks: none");
+            ctx.remove_class("text-orange");
+        } else {
+            lbl.set_text(&format!("Possible leaks: {} process(es) with steadily growing RSS", oom.leak_suspects.len()));
+            ctx.add_class("text-orange");
+        }
+    }
+
+    if let Some(store) = leak_tree.and_then(|t| t.model()).and_then(|m| m.downcast::<ListStore>().ok()) {
+        store.clear();
+        for leak in &oom.leak_suspects {
+            store.insert_with_values(None, &[
+                (0, &leak.pid.to_string()),
+                (1, &leak.name),
+                (2, &format_size(leak.current_rss)),
+                (3, &format!("+{} in {}s", format_size(leak.current_rss - leak.start_rss), leak.window_secs)),
+                (4, &format!("{}/min", format_size(leak.growth_rate as u64))),
+            ]);
+        }
+    }
+
+    // Module inventory is read once at startup, so only fill the table the first time
+    if let Some(store) = dimm_tree.and_then(|t| t.model()).and_then(|m| m.downcast::<ListStore>().ok()) {
+        if store.iter_n_children(None) == 0 {
+            for dimm in &s.dimms {
+                let slot = if dimm.bank_locator.is_empty() {
+                    dimm.locator.clone()
+                } else {
+                    format!("{} ({})", dimm.locator, dimm.bank_locator)
+                };
+                if dimm.size == 0 {
+                    store.insert_with_values(None, &[
+                        (0, &slot), (1, &"Empty".to_string()), (2, &"-".to_string()), (3, &"-".to_string()),
+                        (4, &"-".to_string()), (5, &"-".to_string()), (6, &"-".to_string()), (7, &"-".to_string()),
+                    ]);
+                    continue;
+                }
+                let speed = |v: Option<u32>| v.map(|mts| format!("{} MT/s", mts)).unwrap_or_else(|| "N/A".to_string());
+                store.insert_with_values(None, &[
+                    (0, &slot),
+                    (1, &format_size(dimm.size)),
+                    (2, &format!("{} {}", dimm.memory_type, dimm.form_factor)),
+                    (3, &speed(dimm.speed_mts)),
+                    (4, &speed(dimm.configured_speed_mts)),
+                    (5, &dimm.manufacturer),
+                    (6, &dimm.part_number),
+                    (7, &dimm.rank.map(|r| r.to_string()).unwrap_or_else(|| "N/A".to_string())),
+                ]);
+            }
+        }
+    }
+
+    if let Some(lbl) = dimm_status_lbl {
+        if s.dimms.is_empty() {
+            lbl.set_text("SMBIOS table not readable. Run once as root to cache it in /var/cache/puls-g3.");
+        } else {
+            let populated = s.dimms.iter().filter(|d| d.size > 0).count();
+            let installed: u64 = s.dimms.iter().map(|d| d.size).sum();
+            lbl.set_text(&format!("{} of {} slots populated, {} installed", populated, s.dimms.len(), format_size(installed)));
+        }
+    }
+}
+
+fn zram_ratio(z: &ZramDevice) -> String {
+    if z.compr_data_size > 0 {
+        format!(
+            "{:.2}x ({}, {} in RAM)",
+            z.orig_data_size as f64 / z.compr_data_size as f64,
+            z.algorithm,
+            format_size(z.mem_used_total)
+        )
+    } else {
+        z.algorithm.clone()
+    }
 }
@@ -24,8 +24,11 @@ pub mod global_stats;
 pub mod cpu;
 pub mod memory;
 pub mod gpu;
+pub mod gpu_controls;
 pub mod sensors;
+pub mod firewall;
 pub mod process_detail;
+pub mod charts;
 
 fn read_proc_details(pid: &str, proc_info: &crate::types::ProcessInfo) -> crate::types::DetailedProcessInfo {
     let proc_dir = format!("/proc/{}", pid);
@@ -116,6 +119,33 @@ fn read_proc_details(pid: &str, proc_info: &crate::types::ProcessInfo) -> crate:
     }
 }
 
+/// Modal OK/Cancel warning shown before a privileged change is made.
+pub fn confirm(widget: &impl IsA<gtk::Widget>, text: &str, detail: Option<&str>) -> bool {
+    let parent = widget.toplevel().and_then(|w| w.downcast::<gtk::Window>().ok());
+    let dialog = gtk::MessageDialog::new(
+        parent.as_ref(),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        gtk::MessageType::Warning,
+        gtk::ButtonsType::OkCancel,
+        text,
+    );
+    dialog.set_secondary_text(detail);
+    let response = dialog.run();
+    dialog.close();
+    response == gtk::ResponseType::Ok
+}
+
+/// `--safe` promises no writes, so buttons that change the system are greyed out.
+pub fn disable_in_safe_mode(state: &Arc<Mutex<AppState>>, buttons: &[&gtk::Button]) {
+    if !state.lock().safe_mode {
+        return;
+    }
+    for button in buttons {
+        button.set_sensitive(false);
+        button.set_tooltip_text(Some("Disabled in safe mode"));
+    }
+}
+
 pub fn build_ui(app: &Application, state: Arc<Mutex<AppState>>, config: AppConfig) {
     style::apply_styles();
 
@@ -200,14 +230,21 @@ pub fn build_ui(app: &Application, state: Arc<Mutex<AppState>>, config: AppConfi
     stack.add_titled(&containers_tab, "containers", "=:Docker");
     let sensors_tab = sensors::build_tab(state.clone());
     stack.add_titled(&sensors_tab, "sensors", "+:Sensors");
+    let firewall_tab = firewall::build_tab(state.clone());
+    stack.add_titled(&firewall_tab, "firewall", "F:Firewall");
     let process_detail_tab = process_detail::build_tab(state.clone());
     stack.add_titled(&process_detail_tab, "process_detail", "P:Details");
     switcher.set_stack(Some(&stack));
 
     let stack_clone = stack.clone();
-    window.connect_key_press_event(move |_, key| {
+    window.connect_key_press_event(move |win, key| {
         use gdk::keys::constants as keys;
         let pval = key.keyval();
+
+        // Let text fields receive digits and letters instead of switching tabs
+        if win.focused_widget().is_some_and(|w| w.is::<gtk::Entry>() || w.is::<gtk::TextView>()) {
+            return glib::Propagation::Proceed;
+        }
         
         if pval == keys::Tab {
             let children = stack_clone.children();
@@ -234,6 +271,7 @@ pub fn build_ui(app: &Application, state: Arc<Mutex<AppState>>, config: AppConfi
             keys::minus => Some("config"),
             keys::equal => Some("containers"),
             keys::plus => Some("sensors"),
+            keys::f | keys::F => Some("firewall"),
             keys::p | keys::P => Some("process_detail"),
             _ => None,
         };
@@ -323,6 +361,7 @@ pub fn build_ui(app: &Application, state: Arc<Mutex<AppState>>, config: AppConfi
             "config" => config::update_tab(&config_tab, &state),
             "containers" => containers::update_tab(&containers_tab, &state),
             "sensors" => sensors::update_tab(&sensors_tab, &state),
+            "firewall" => firewall::update_tab(&firewall_tab, &state),
             "process_detail" => process_detail::update_tab(&process_detail_tab, &state),
             _ => {},
         }
@@ -1,12 +1,38 @@
 use gtk::prelude::*;
-use gtk::{Box, Orientation, Widget, ScrolledWindow, Frame,
-          TreeView, TreeViewColumn, CellRendererText, ListStore};
+use gtk::{Box, Orientation, Widget, ScrolledWindow, Frame, Label, Button, Entry, TextView,
+          TreeView, TreeViewColumn, CellRendererText, TreeStore, ListStore};
+use glib::{clone, ControlFlow};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 use parking_lot::Mutex;
-use crate::types::AppState;
+use crate::types::{AppState, DetailedNetInfo, TrafficPeriod, TrafficUsage, WifiInfo};
 use crate::utils::{format_rate, format_size};
+use crate::gui::charts;
+use crate::monitors::netdiag;
 
-pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
+const VIRTUAL_GROUP: &str = "Virtual interfaces";
+
+thread_local! {
+    // (name, is_virtual) of the rows in the store; the tree is only rebuilt when this
+    // changes so the virtual group keeps its expanded/collapsed state
+    static SHOWN_INTERFACES: RefCell<Vec<(String, bool)>> = const { RefCell::new(Vec::new()) };
+    static SHOWN_TRAFFIC: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static SHOWN_TRAFFIC_DETAIL: RefCell<Option<TrafficUsage>> = const { RefCell::new(None) };
+    // Last traffic snapshot, read by the selection handler. It fires synchronously
+    // from update_tab while AppState is locked, so it must not lock it again
+    static TRAFFIC_USAGE: RefCell<Vec<TrafficUsage>> = const { RefCell::new(Vec::new()) };
+}
+
+// Share of a monthly data cap at which the Network tab starts warning
+const CAP_WARN_PERCENT: f64 = 80.0;
+// Ping samples shown in the latency and loss charts
+const PING_HISTORY: usize = 60;
+const TRACE_MAX_HOPS: u8 = 30;
+
+pub fn build_tab(state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 5);
     container.set_border_width(10);
 
@@ -15,10 +41,17 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
     scrolled.set_vexpand(true);
     scrolled.set_hexpand(true);
+    scrolled.set_min_content_height(220);
 
-    let store = ListStore::new(&[
+    let store = TreeStore::new(&[
         glib::Type::STRING, // Interface
+        glib::Type::STRING, // Type
         glib::Type::STRING, // Status
+        glib::Type::STRING, // Speed
+        glib::Type::STRING, // MTU
+        glib::Type::STRING, // MAC
+        glib::Type::STRING, // Addresses
+        glib::Type::STRING, // Driver
         glib::Type::STRING, // Download/s
         glib::Type::STRING, // Upload/s
         glib::Type::STRING, // Total Down
@@ -30,8 +63,9 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     tree.set_widget_name("network_tree");
 
     for (title, id) in &[
-        ("Interface", 0), ("Status", 1), ("Download/s", 2), ("Upload/s", 3),
-        ("Total Down", 4), ("Total Up", 5), ("Packets Rx/Tx", 6),
+        ("Interface", 0), ("Type", 1), ("Status", 2), ("Speed", 3), ("MTU", 4),
+        ("MAC", 5), ("Addresses", 6), ("Driver", 7), ("Download/s", 8), ("Upload/s", 9),
+        ("Total Down", 10), ("Total Up", 11), ("Packets Rx/Tx", 12),
     ] {
         let col = TreeViewColumn::new();
         col.set_title(title);
@@ -42,11 +76,100 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
         tree.append_column(&col);
     }
 
+    let iface_box = Box::new(Orientation::Vertical, 5);
+    iface_box.set_border_width(5);
+    let action_box = Box::new(Orientation::Horizontal, 5);
+    let up_btn = Button::with_label("Bring Up");
+    let down_btn = Button::with_label("Bring Down");
+    let renew_btn = Button::with_label("Renew DHCP");
+    up_btn.style_context().add_class("suggested-action");
+    down_btn.style_context().add_class("destructive-action");
+    action_box.pack_start(&up_btn, false, false, 0);
+    action_box.pack_start(&down_btn, false, false, 0);
+    action_box.pack_start(&renew_btn, false, false, 0);
+    iface_box.pack_start(&action_box, false, false, 0);
+    super::disable_in_safe_mode(&state, &[&up_btn, &down_btn, &renew_btn]);
+
+    let iface_status_lbl = Label::new(Some("Select an interface and use the buttons above."));
+    iface_status_lbl.set_halign(gtk::Align::Start);
+    iface_status_lbl.style_context().add_class("text-cyan");
+    iface_box.pack_start(&iface_status_lbl, false, false, 0);
+
     scrolled.add(&tree);
-    frame.add(&scrolled);
+    iface_box.pack_start(&scrolled, true, true, 0);
+    frame.add(&iface_box);
     container.pack_start(&frame, true, true, 0);
 
-    container.upcast::<Widget>()
+    fn get_selected_interface(tree: &TreeView) -> Option<String> {
+        tree.selection().selected().and_then(|(model, iter)| {
+            model.value(&iter, 0).get::<String>().ok()
+        }).filter(|name| !name.starts_with(VIRTUAL_GROUP))
+    }
+
+    for (btn, up) in [(&up_btn, true), (&down_btn, false)] {
+        btn.connect_clicked(clone!(@strong tree, @strong iface_status_lbl => move |btn| {
+            if let Some(name) = get_selected_interface(&tree) {
+                if !up && !super::confirm(btn, &format!("Bring {} down?", name), Some("Connections over it drop, including remote sessions.")) {
+                    return;
+                }
+                let mgr = crate::system_service::SystemManager::new();
+                let action = if up { "up" } else { "down" };
+                match mgr.set_interface_state(&name, up) {
+                    Ok(()) => iface_status_lbl.set_text(&format!("[OK] Brought {} {}", name, action)),
+                    Err(e) => iface_status_lbl.set_text(&format!("[FAIL] Failed to bring {} {}: {}", name, action, e)),
+                }
+            } else {
+                iface_status_lbl.set_text("No interface selected");
+            }
+        }));
+    }
+
+    renew_btn.connect_clicked(clone!(@strong tree, @strong iface_status_lbl => move |_| {
+        if let Some(name) = get_selected_interface(&tree) {
+            let mgr = crate::system_service::SystemManager::new();
+            match mgr.renew_dhcp(&name) {
+                Ok(client) => iface_status_lbl.set_text(&format!("[OK] Renewed DHCP lease on {} via {}", name, client)),
+                Err(e) => iface_status_lbl.set_text(&format!("[FAIL] Failed to renew DHCP on {}: {}", name, e)),
+            }
+        } else {
+            iface_status_lbl.set_text("No interface selected");
+        }
+    }));
+
+    container.pack_start(&build_profiles_frame(&state), false, false, 0);
+
+    let wifi_frame = Frame::new(Some(" Wi-Fi "));
+    wifi_frame.set_widget_name("network_wifi_frame");
+    wifi_frame.set_no_show_all(true);
+    let wifi_box = Box::new(Orientation::Vertical, 4);
+    wifi_box.set_border_width(5);
+    let wifi_lbl = Label::new(None);
+    wifi_lbl.set_widget_name("network_wifi_lbl");
+    wifi_lbl.set_xalign(0.0);
+    wifi_lbl.set_line_wrap(true);
+    wifi_box.pack_start(&wifi_lbl, false, false, 0);
+    let signal_title = Label::new(Some("Signal strength (%)"));
+    signal_title.set_xalign(0.0);
+    wifi_box.pack_start(&signal_title, false, false, 0);
+    let chart = charts::build_history_chart(state.clone(), "network_wifi_chart", 80, &[charts::CYAN], Some(100.0), |s| {
+        s.dynamic_data.wifi.iter()
+            .map(|w| w.signal_history.iter().map(|v| *v as f64).collect())
+            .take(1)
+            .collect()
+    });
+    wifi_box.pack_start(&chart, false, false, 0);
+    wifi_box.show_all();
+    wifi_frame.add(&wifi_box);
+    container.pack_start(&wifi_frame, false, false, 0);
+
+    container.pack_start(&build_traffic_frame(), false, false, 0);
+    container.pack_start(&build_diagnostics_frame(state), false, false, 0);
+
+    let outer = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    outer.set_vexpand(true);
+    outer.set_hexpand(true);
+    outer.add(&container);
+    outer.upcast::<Widget>()
 }
 
 pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
@@ -62,23 +185,740 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         None => return,
     };
 
-    let store = match tree.model().and_then(|m| m.downcast::<ListStore>().ok()) {
+    let store = match tree.model().and_then(|m| m.downcast::<TreeStore>().ok()) {
         Some(s) => s,
         None => return,
     };
 
     let s = state.lock();
+    update_wifi(&container, &s.dynamic_data.wifi);
+    update_traffic(&container, &s.dynamic_data.traffic);
+
+    let mut nets: Vec<&DetailedNetInfo> = s.dynamic_data.networks.iter().collect();
+    nets.sort_by(|a, b| a.is_virtual.cmp(&b.is_virtual).then_with(|| a.name.cmp(&b.name)));
+
+    let layout: Vec<(String, bool)> = nets.iter().map(|n| (n.name.clone(), n.is_virtual)).collect();
+    let changed = SHOWN_INTERFACES.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown != layout {
+            *shown = layout;
+            true
+        } else {
+            false
+        }
+    });
+    if changed {
+        store.clear();
+        let virtual_count = nets.iter().filter(|n| n.is_virtual).count();
+        let mut group = None;
+        for net in &nets {
+            let parent = if net.is_virtual {
+                Some(*group.get_or_insert_with(|| {
+                    store.insert_with_values(None, None, &[
+                        (0, &format!("{} ({})", VIRTUAL_GROUP, virtual_count)),
+                    ])
+                }))
+            } else {
+                None
+            };
+            store.insert_with_values(parent.as_ref(), None, &[(0, &net.name)]);
+        }
+    }
+
+    let by_name: HashMap<&str, &DetailedNetInfo> = nets.iter().map(|n| (n.name.as_str(), *n)).collect();
+    let virtual_nets: Vec<&&DetailedNetInfo> = nets.iter().filter(|n| n.is_virtual).collect();
+    store.foreach(|model, _, iter| {
+        let name = model.value(iter, 0).get::<String>().unwrap_or_default();
+        if let Some(net) = by_name.get(name.as_str()) {
+            set_interface_row(&store, iter, net);
+        } else if name.starts_with(VIRTUAL_GROUP) {
+            let down: u64 = virtual_nets.iter().map(|n| n.down_rate).sum();
+            let up: u64 = virtual_nets.iter().map(|n| n.up_rate).sum();
+            let up_count = virtual_nets.iter().filter(|n| n.is_up).count();
+            store.set(iter, &[
+                (2, &format!("{} of {} up", up_count, virtual_nets.len())),
+                (8, &format_rate(down)),
+                (9, &format_rate(up)),
+            ]);
+        }
+        false
+    });
+}
+
+/// NetworkManager connection profiles. Loaded on demand rather than every tick
+/// since each refresh is a handful of D-Bus round trips.
+fn build_profiles_frame(state: &Arc<Mutex<AppState>>) -> Frame {
+    let frame = Frame::new(Some(" NetworkManager Profiles "));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let action_box = Box::new(Orientation::Horizontal, 5);
+    let refresh_btn = Button::with_label("Refresh");
+    let activate_btn = Button::with_label("Activate");
+    let deactivate_btn = Button::with_label("Deactivate");
+    activate_btn.style_context().add_class("suggested-action");
+    deactivate_btn.style_context().add_class("destructive-action");
+    action_box.pack_start(&refresh_btn, false, false, 0);
+    action_box.pack_start(&activate_btn, false, false, 0);
+    action_box.pack_start(&deactivate_btn, false, false, 0);
+    vbox.pack_start(&action_box, false, false, 0);
+    super::disable_in_safe_mode(state, &[&activate_btn, &deactivate_btn]);
+
+    let status_lbl = Label::new(None);
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.style_context().add_class("text-cyan");
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    let store = ListStore::new(&[
+        glib::Type::STRING, // Name
+        glib::Type::STRING, // Type
+        glib::Type::STRING, // Device
+        glib::Type::STRING, // State
+        glib::Type::STRING, // UUID
+        glib::Type::STRING, // Settings path
+        glib::Type::STRING, // Active connection path
+    ]);
+    let tree = TreeView::with_model(&store);
+    for (title, id) in &[("Name", 0), ("Type", 1), ("Device", 2), ("State", 3), ("UUID", 4)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        tree.append_column(&col);
+    }
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_min_content_height(120);
+    scrolled.add(&tree);
+    vbox.pack_start(&scrolled, true, true, 0);
+    frame.add(&vbox);
+
+    let load = clone!(@strong store, @strong status_lbl => move || {
+        store.clear();
+        let mgr = crate::system_service::SystemManager::new();
+        match mgr.list_network_profiles() {
+            Ok(profiles) => {
+                for p in &profiles {
+                    store.insert_with_values(None, &[
+                        (0, &p.id),
+                        (1, &p.kind),
+                        (2, &p.interface),
+                        (3, &(if p.active { "active" } else { "-" }).to_string()),
+                        (4, &p.uuid),
+                        (5, &p.path),
+                        (6, &p.active_path),
+                    ]);
+                }
+                status_lbl.set_text(&format!("{} profile(s)", profiles.len()));
+            }
+            Err(e) => status_lbl.set_text(&e),
+        }
+    });
+    load();
+
+    refresh_btn.connect_clicked(clone!(@strong load => move |_| load()));
+
+    // (settings path, active connection path) of the selected profile
+    fn get_selected_profile(tree: &TreeView) -> Option<(String, String, String)> {
+        tree.selection().selected().and_then(|(model, iter)| {
+            Some((
+                model.value(&iter, 0).get::<String>().ok()?,
+                model.value(&iter, 5).get::<String>().ok()?,
+                model.value(&iter, 6).get::<String>().ok()?,
+            ))
+        })
+    }
+
+    activate_btn.connect_clicked(clone!(@strong tree, @strong status_lbl, @strong load => move |_| {
+        if let Some((name, path, _)) = get_selected_profile(&tree) {
+            let mgr = crate::system_service::SystemManager::new();
+            match mgr.activate_network_profile(&path) {
+                Ok(()) => {
+                    load();
+                    status_lbl.set_text(&format!("[OK] Activating {}", name));
+                }
+                Err(e) => status_lbl.set_text(&format!("[FAIL] Failed to activate {}: {}", name, e)),
+            }
+        } else {
+            status_lbl.set_text("No profile selected");
+        }
+    }));
+
+    deactivate_btn.connect_clicked(clone!(@strong tree, @strong status_lbl, @strong load => move |btn| {
+        match get_selected_profile(&tree) {
+            Some((name, _, active)) if active.is_empty() => status_lbl.set_text(&format!("{} is not active", name)),
+            Some((name, _, active)) => {
+                if !super::confirm(btn, &format!("Deactivate {}?", name), Some("Connections over it drop, including remote sessions.")) {
+                    return;
+                }
+                let mgr = crate::system_service::SystemManager::new();
+                match mgr.deactivate_network_profile(&active) {
+                    Ok(()) => {
+                        load();
+                        status_lbl.set_text(&format!("[OK] Deactivated {}", name));
+                    }
+                    Err(e) => status_lbl.set_text(&format!("[FAIL] Failed to deactivate {}: {}", name, e)),
+                }
+            }
+            None => status_lbl.set_text("No profile selected"),
+        }
+    }));
+
+    frame
+}
+
+fn update_wifi(container: &gtk::Container, wifi: &[WifiInfo]) {
+    if let Some(frame) = crate::gui::dashboard::find_widget_by_name(container, "network_wifi_frame") {
+        frame.set_visible(!wifi.is_empty());
+    }
+    let lbl = match crate::gui::dashboard::find_widget_by_name(container, "network_wifi_lbl")
+        .and_then(|w| w.downcast::<Label>().ok())
+    {
+        Some(l) => l,
+        None => return,
+    };
+
+    let lines: Vec<String> = wifi.iter().map(|w| {
+        if !w.connected {
+            return format!("{}: not connected", w.interface);
+        }
+        let opt = |v: Option<f32>, unit: &str| v.map(|v| format!("{:.0} {}", v, unit)).unwrap_or_else(|| "-".to_string());
+        let channel = match (w.channel, w.frequency_mhz) {
+            (Some(ch), Some(freq)) => format!("{} ch {} ({} MHz)", w.band, ch, freq),
+            (None, Some(freq)) => format!("{} MHz", freq),
+            _ => "-".to_string(),
+        };
+        let signal = match w.signal_dbm {
+            Some(dbm) => format!("{:.0} dBm ({:.0}%)", dbm, crate::monitors::wifi::signal_percent(dbm)),
+            None => "-".to_string(),
+        };
+        let bitrate = |v: Option<f32>| v.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "-".to_string());
+        format!(
+            "{}: \"{}\" ({}) | {} | Signal {} | Quality {} | Noise {} | Bitrate RX {} / TX {} Mbit/s",
+            w.interface, w.ssid, w.bssid, channel, signal,
+            w.link_quality.map(|q| format!("{:.0}%", q)).unwrap_or_else(|| "-".to_string()),
+            opt(w.noise_dbm, "dBm"), bitrate(w.rx_bitrate_mbps), bitrate(w.tx_bitrate_mbps)
+        )
+    }).collect();
+    lbl.set_text(&lines.join("\n"));
+
+    // Rule-of-thumb thresholds: -67 dBm is enough for voice/video, below -75 is unreliable
+    let weakest = wifi.iter().filter(|w| w.connected).filter_map(|w| w.signal_dbm).fold(f32::MAX, f32::min);
+    let ctx = lbl.style_context();
+    for class in ["text-green", "text-orange", "text-red"] {
+        ctx.remove_class(class);
+    }
+    ctx.add_class(if weakest >= -67.0 {
+        "text-green"
+    } else if weakest >= -75.0 {
+        "text-orange"
+    } else {
+        "text-red"
+    });
+
+    charts::refresh_chart(container, "network_wifi_chart");
+}
+
+fn build_traffic_frame() -> Frame {
+    let frame = Frame::new(Some(" Traffic Usage "));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let cap_lbl = Label::new(None);
+    cap_lbl.set_widget_name("network_cap_lbl");
+    cap_lbl.set_xalign(0.0);
+    cap_lbl.set_no_show_all(true);
+    vbox.pack_start(&cap_lbl, false, false, 0);
+
+    let usage_store = ListStore::new(&[
+        glib::Type::STRING, // Interface
+        glib::Type::STRING, // Today down
+        glib::Type::STRING, // Today up
+        glib::Type::STRING, // Yesterday
+        glib::Type::STRING, // This month down
+        glib::Type::STRING, // This month up
+        glib::Type::STRING, // Last month
+        glib::Type::STRING, // Cap
+    ]);
+    let usage_tree = TreeView::with_model(&usage_store);
+    usage_tree.set_widget_name("network_traffic_tree");
+    for (title, id) in &[("Interface", 0), ("Today ↓", 1), ("Today ↑", 2), ("Yesterday", 3),
+                         ("Month ↓", 4), ("Month ↑", 5), ("Last Month", 6), ("Monthly Cap", 7)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        usage_tree.append_column(&col);
+    }
+
+    let detail_store = TreeStore::new(&[
+        glib::Type::STRING, // Period
+        glib::Type::STRING, // Download
+        glib::Type::STRING, // Upload
+        glib::Type::STRING, // Total
+    ]);
+    let detail_tree = TreeView::with_model(&detail_store);
+    detail_tree.set_widget_name("network_traffic_detail");
+    for (title, id) in &[("Period", 0), ("Download", 1), ("Upload", 2), ("Total", 3)] {
+        let col = TreeViewColumn::new();
+        col.set_title(title);
+        col.set_resizable(true);
+        let r = CellRendererText::new();
+        TreeViewColumnExt::pack_start(&col, &r, true);
+        TreeViewColumnExt::add_attribute(&col, &r, "text", *id);
+        detail_tree.append_column(&col);
+    }
+
+    let hbox = Box::new(Orientation::Horizontal, 5);
+    for tree in [&usage_tree, &detail_tree] {
+        let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+        scrolled.set_min_content_height(160);
+        scrolled.set_hexpand(true);
+        scrolled.add(tree);
+        hbox.pack_start(&scrolled, true, true, 0);
+    }
+    vbox.pack_start(&hbox, true, true, 0);
+    frame.add(&vbox);
+
+    usage_tree.selection().connect_changed(clone!(@strong detail_tree => move |selection| {
+        let name = selection.selected().and_then(|(model, iter)| model.value(&iter, 0).get::<String>().ok());
+        let usage = TRAFFIC_USAGE.with(|t| t.borrow().iter().find(|u| Some(&u.interface) == name.as_ref()).cloned());
+        if let Some(usage) = usage {
+            update_traffic_detail(&detail_tree, &usage);
+        }
+    }));
+
+    frame
+}
+
+fn build_diagnostics_frame(state: Arc<Mutex<AppState>>) -> Frame {
+    let frame = Frame::new(Some(" Diagnostics "));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let action_box = Box::new(Orientation::Horizontal, 5);
+    let host_entry = Entry::new();
+    host_entry.set_placeholder_text(Some("Host or IP"));
+    host_entry.set_text("1.1.1.1");
+    let port_entry = Entry::new();
+    port_entry.set_width_chars(6);
+    port_entry.set_placeholder_text(Some("Port"));
+    port_entry.set_text("443");
+    let ping_btn = Button::with_label("Ping");
+    let trace_btn = Button::with_label("Traceroute");
+    let dns_btn = Button::with_label("DNS Lookup");
+    let tcp_btn = Button::with_label("TCP Check");
+    let stop_btn = Button::with_label("Stop");
+    ping_btn.style_context().add_class("suggested-action");
+    stop_btn.style_context().add_class("destructive-action");
+    stop_btn.set_sensitive(false);
+    action_box.pack_start(&Label::new(Some("Host:")), false, false, 0);
+    action_box.pack_start(&host_entry, false, false, 0);
+    action_box.pack_start(&Label::new(Some("Port:")), false, false, 0);
+    action_box.pack_start(&port_entry, false, false, 0);
+    for btn in [&ping_btn, &trace_btn, &dns_btn, &tcp_btn, &stop_btn] {
+        action_box.pack_start(btn, false, false, 0);
+    }
+    vbox.pack_start(&action_box, false, false, 0);
+
+    let status_lbl = Label::new(Some("Ping, trace, resolve or connect to a host to find where connectivity breaks."));
+    status_lbl.set_xalign(0.0);
+    status_lbl.set_line_wrap(true);
+    status_lbl.style_context().add_class("text-cyan");
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    let ping: Rc<RefCell<Option<Arc<netdiag::PingSession>>>> = Rc::new(RefCell::new(None));
+    let trace: Rc<RefCell<Option<Arc<netdiag::TraceSession>>>> = Rc::new(RefCell::new(None));
+
+    let charts_box = Box::new(Orientation::Horizontal, 10);
+    let latency_box = Box::new(Orientation::Vertical, 2);
+    let latency_title = Label::new(Some("Ping latency (ms)"));
+    latency_title.set_xalign(0.0);
+    latency_box.pack_start(&latency_title, false, false, 0);
+    let latency_chart = charts::build_history_chart(state.clone(), "network_ping_latency_chart", 80, &[charts::CYAN], None,
+        clone!(@strong ping => move |_| {
+            ping.borrow().as_ref().map(|p| vec![p.rtt_history(PING_HISTORY)]).unwrap_or_default()
+        }));
+    latency_box.pack_start(&latency_chart, false, false, 0);
+    let loss_box = Box::new(Orientation::Vertical, 2);
+    let loss_title = Label::new(Some("Packet loss (%)"));
+    loss_title.set_xalign(0.0);
+    loss_box.pack_start(&loss_title, false, false, 0);
+    let loss_chart = charts::build_history_chart(state, "network_ping_loss_chart", 80, &[charts::RED], Some(100.0),
+        clone!(@strong ping => move |_| {
+            ping.borrow().as_ref().map(|p| vec![p.loss_history(PING_HISTORY)]).unwrap_or_default()
+        }));
+    loss_box.pack_start(&loss_chart, false, false, 0);
+    charts_box.pack_start(&latency_box, true, true, 0);
+    charts_box.pack_start(&loss_box, true, true, 0);
+    vbox.pack_start(&charts_box, false, false, 0);
+
+    let output = TextView::new();
+    output.set_monospace(true);
+    output.set_editable(false);
+    let scrolled = ScrolledWindow::new(gtk::Adjustment::NONE, gtk::Adjustment::NONE);
+    scrolled.set_min_content_height(140);
+    scrolled.add(&output);
+    vbox.pack_start(&scrolled, true, true, 0);
+    frame.add(&vbox);
+
+    fn set_output(view: &TextView, text: &str) {
+        if let Some(buffer) = view.buffer() {
+            buffer.set_text(text);
+        }
+    }
+
+    fn host(entry: &Entry) -> Option<String> {
+        Some(entry.text().trim().to_string()).filter(|h| !h.is_empty())
+    }
+
+    let update_stop = clone!(@strong ping, @strong trace, @strong stop_btn => move || {
+        let running = ping.borrow().as_ref().is_some_and(|p| !p.is_finished())
+            || trace.borrow().as_ref().is_some_and(|t| !t.is_finished());
+        stop_btn.set_sensitive(running);
+    });
+
+    ping_btn.connect_clicked(clone!(@strong ping, @strong host_entry, @strong status_lbl,
+                                    @strong latency_chart, @strong loss_chart, @strong update_stop => move |_| {
+        let target = match host(&host_entry) {
+            Some(h) => h,
+            None => return status_lbl.set_text("Enter a host to ping"),
+        };
+        if let Some(old) = ping.borrow().as_ref() {
+            old.cancel();
+        }
+        let session = netdiag::start_ping(&target, None, Duration::from_secs(1));
+        *ping.borrow_mut() = Some(session.clone());
+        update_stop();
+
+        glib::timeout_add_local(Duration::from_millis(500), clone!(@strong ping, @strong status_lbl,
+                                @strong latency_chart, @strong loss_chart, @strong update_stop => move || {
+            // A newer run replaced this one
+            if !ping.borrow().as_ref().is_some_and(|p| Arc::ptr_eq(p, &session)) {
+                return ControlFlow::Break;
+            }
+            let stats = session.stats();
+            let mut text = format!(
+                "PING {} ({}): {} sent, {} received, {:.0}% loss",
+                session.target, session.address(), stats.sent, stats.received, stats.loss_percent()
+            );
+            if stats.received > 0 {
+                text.push_str(&format!(" | rtt min/avg/max/jitter {:.1}/{:.1}/{:.1}/{:.1} ms",
+                    stats.min_ms, stats.avg_ms, stats.max_ms, stats.jitter_ms));
+            }
+            if let Some(e) = session.error() {
+                text = format!("[FAIL] {}", e);
+            }
+            status_lbl.set_text(&text);
+            latency_chart.queue_draw();
+            loss_chart.queue_draw();
+            if session.is_finished() {
+                update_stop();
+                ControlFlow::Break
+            } else {
+                ControlFlow::Continue
+            }
+        }));
+    }));
+
+    trace_btn.connect_clicked(clone!(@strong trace, @strong host_entry, @strong status_lbl,
+                                     @strong output, @strong update_stop => move |_| {
+        let target = match host(&host_entry) {
+            Some(h) => h,
+            None => return status_lbl.set_text("Enter a host to trace"),
+        };
+        if let Some(old) = trace.borrow().as_ref() {
+            old.cancel();
+        }
+        let session = netdiag::start_traceroute(&target, TRACE_MAX_HOPS);
+        *trace.borrow_mut() = Some(session.clone());
+        status_lbl.set_text(&format!("Tracing route to {}...", target));
+        update_stop();
+
+        glib::timeout_add_local(Duration::from_millis(300), clone!(@strong trace, @strong status_lbl,
+                                @strong output, @strong update_stop => move || {
+            if !trace.borrow().as_ref().is_some_and(|t| Arc::ptr_eq(t, &session)) {
+                return ControlFlow::Break;
+            }
+            let mut text = format!("traceroute to {} ({}), {} hops max\n", session.target, session.address(), TRACE_MAX_HOPS);
+            for hop in session.hops() {
+                let rtts: Vec<String> = hop.rtts_ms.iter()
+                    .map(|r| r.map(|ms| format!("{:.1} ms", ms)).unwrap_or_else(|| "*".to_string()))
+                    .collect();
+                text.push_str(&format!("{:>2}  {:<40} {} {}\n",
+                    hop.ttl, hop.address.as_deref().unwrap_or("*"), rtts.join("  "), hop.note));
+            }
+            set_output(&output, &text);
+
+            if !session.is_finished() {
+                return ControlFlow::Continue;
+            }
+            status_lbl.set_text(&match session.error() {
+                Some(e) => format!("[FAIL] {}", e),
+                None if session.reached() => format!("[OK] Reached {} in {} hops", session.target, session.hops().len()),
+                None => format!("[FAIL] {} not reached", session.target),
+            });
+            update_stop();
+            ControlFlow::Break
+        }));
+    }));
+
+    dns_btn.connect_clicked(clone!(@strong host_entry, @strong status_lbl, @strong output => move |_| {
+        let name = match host(&host_entry) {
+            Some(h) => h,
+            None => return status_lbl.set_text("Enter a host name to resolve"),
+        };
+        status_lbl.set_text(&format!("Resolving {}...", name));
+        let job = netdiag::spawn_job(async move { netdiag::lookup_all(&name).await });
+        glib::timeout_add_local(Duration::from_millis(200), clone!(@strong status_lbl, @strong output => move || {
+            let lookups = match job.result() {
+                Some(l) => l,
+                None => return ControlFlow::Continue,
+            };
+            let lines: Vec<String> = lookups.iter().map(|l| match (&l.error, l.latency_ms) {
+                (Some(e), _) => format!("{:<24} {:<5} error: {}", l.server, l.record_type, e),
+                (None, ms) => format!("{:<24} {:<5} {:>8.1} ms  {:<8} {}", l.server, l.record_type,
+                    ms.unwrap_or(0.0), l.rcode, if l.answers.is_empty() { "-".to_string() } else { l.answers.join(", ") }),
+            }).collect();
+            set_output(&output, &lines.join("\n"));
+            let answered = lookups.iter().filter(|l| l.error.is_none()).count();
+            status_lbl.set_text(&format!("{} {} of {} resolver queries answered",
+                if answered == lookups.len() { "[OK]" } else { "[FAIL]" }, answered, lookups.len()));
+            ControlFlow::Break
+        }));
+    }));
+
+    tcp_btn.connect_clicked(clone!(@strong host_entry, @strong port_entry, @strong status_lbl, @strong output => move |_| {
+        let target = match host(&host_entry) {
+            Some(h) => h,
+            None => return status_lbl.set_text("Enter a host to connect to"),
+        };
+        let port = match port_entry.text().trim().parse::<u16>() {
+            Ok(p) if p > 0 => p,
+            _ => return status_lbl.set_text("[FAIL] Enter a port between 1 and 65535"),
+        };
+        status_lbl.set_text(&format!("Connecting to {} port {}...", target, port));
+        let job = netdiag::spawn_job(async move { netdiag::tcp_check(&target, port).await });
+        glib::timeout_add_local(Duration::from_millis(200), clone!(@strong status_lbl, @strong output => move || {
+            let checks = match job.result() {
+                Some(c) => c,
+                None => return ControlFlow::Continue,
+            };
+            let lines: Vec<String> = checks.iter().map(|c| match (&c.error, c.latency_ms) {
+                (Some(e), _) => format!("{:<48} failed: {}", c.address, e),
+                (None, ms) => format!("{:<48} open  {:.1} ms", c.address, ms.unwrap_or(0.0)),
+            }).collect();
+            set_output(&output, &lines.join("\n"));
+            let open = checks.iter().filter(|c| c.error.is_none()).count();
+            status_lbl.set_text(&format!("{} {} of {} addresses accepted the connection",
+                if open > 0 { "[OK]" } else { "[FAIL]" }, open, checks.len()));
+            ControlFlow::Break
+        }));
+    }));
+
+    stop_btn.connect_clicked(clone!(@strong ping, @strong trace => move |btn| {
+        if let Some(p) = ping.borrow().as_ref() {
+            p.cancel();
+        }
+        if let Some(t) = trace.borrow().as_ref() {
+            t.cancel();
+        }
+        btn.set_sensitive(false);
+    }));
+
+    frame
+}
+
+fn cap_percent(usage: &TrafficUsage) -> Option<f64> {
+    usage.monthly_cap.map(|cap| usage.this_month.total() as f64 / cap.max(1) as f64 * 100.0)
+}
+
+fn update_traffic(container: &gtk::Container, traffic: &[TrafficUsage]) {
+    TRAFFIC_USAGE.with(|t| *t.borrow_mut() = traffic.to_vec());
+
+    if let Some(lbl) = crate::gui::dashboard::find_widget_by_name(container, "network_cap_lbl")
+        .and_then(|w| w.downcast::<Label>().ok())
+    {
+        let capped: Vec<(&TrafficUsage, f64)> = traffic.iter()
+            .filter_map(|u| cap_percent(u).map(|p| (u, p)))
+            .collect();
+        let lines: Vec<String> = capped.iter().map(|(u, pct)| {
+            let prefix = if *pct >= 100.0 { "[FAIL] Cap exceeded: " } else if *pct >= CAP_WARN_PERCENT { "[WARN] " } else { "" };
+            format!("{}{} used {} of its {} monthly cap ({:.0}%)",
+                prefix, u.interface, format_size(u.this_month.total()), format_size(u.monthly_cap.unwrap_or(0)), pct)
+        }).collect();
+        lbl.set_text(&lines.join("\n"));
+        lbl.set_visible(!lines.is_empty());
+
+        let worst = capped.iter().map(|(_, p)| *p).fold(0.0, f64::max);
+        let ctx = lbl.style_context();
+        for class in ["text-green", "text-orange", "text-red"] {
+            ctx.remove_class(class);
+        }
+        ctx.add_class(if worst >= 100.0 {
+            "text-red"
+        } else if worst >= CAP_WARN_PERCENT {
+            "text-orange"
+        } else {
+            "text-green"
+        });
+    }
+
+    let tree = match crate::gui::dashboard::find_widget_by_name(container, "network_traffic_tree")
+        .and_then(|w| w.downcast::<TreeView>().ok())
+    {
+        Some(t) => t,
+        None => return,
+    };
+    let store = match tree.model().and_then(|m| m.downcast::<ListStore>().ok()) {
+        Some(s) => s,
+        None => return,
+    };
+
+    // Rows are updated in place so the selection survives refreshes
+    let names: Vec<String> = traffic.iter().map(|u| u.interface.clone()).collect();
+    let changed = SHOWN_TRAFFIC.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown != names {
+            *shown = names;
+            true
+        } else {
+            false
+        }
+    });
+    if changed {
+        store.clear();
+        for usage in traffic {
+            store.insert_with_values(None, &[(0, &usage.interface)]);
+        }
+    }
+
+    let mut idx = 0;
+    store.foreach(|_, _, iter| {
+        if let Some(usage) = traffic.get(idx) {
+            let cap = match (usage.monthly_cap, cap_percent(usage)) {
+                (Some(cap), Some(pct)) => format!("{} ({:.0}%)", format_size(cap), pct),
+                _ => "-".to_string(),
+            };
+            store.set(iter, &[
+                (1, &format_size(usage.today.rx)),
+                (2, &format_size(usage.today.tx)),
+                (3, &format_size(usage.yesterday.total())),
+                (4, &format_size(usage.this_month.rx)),
+                (5, &format_size(usage.this_month.tx)),
+                (6, &format_size(usage.last_month.total())),
+                (7, &cap),
+            ]);
+        }
+        idx += 1;
+        false
+    });
+
+    if tree.selection().count_selected_rows() == 0 {
+        if let Some(first) = store.iter_first() {
+            tree.selection().select_iter(&first);
+        }
+    }
+    let selected = tree.selection().selected()
+        .and_then(|(model, iter)| model.value(&iter, 0).get::<String>().ok());
+    if let (Some(detail), Some(usage)) = (
+        crate::gui::dashboard::find_widget_by_name(container, "network_traffic_detail")
+            .and_then(|w| w.downcast::<TreeView>().ok()),
+        traffic.iter().find(|u| Some(&u.interface) == selected.as_ref()),
+    ) {
+        update_traffic_detail(&detail, usage);
+    }
+}
+
+/// Months, with the retained days nested under them and hours under each day.
+fn update_traffic_detail(tree: &TreeView, usage: &TrafficUsage) {
+    let changed = SHOWN_TRAFFIC_DETAIL.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if shown.as_ref() == Some(usage) {
+            return None;
+        }
+        let same_interface = shown.as_ref().is_some_and(|s| s.interface == usage.interface);
+        *shown = Some(usage.clone());
+        // Keep the user's expanded rows while the same interface keeps updating
+        Some(same_interface)
+    });
+    let same_interface = match changed {
+        Some(same) => same,
+        None => return,
+    };
+    let store = match tree.model().and_then(|m| m.downcast::<TreeStore>().ok()) {
+        Some(s) => s,
+        None => return,
+    };
+
+    let mut expanded = Vec::new();
+    if same_interface {
+        tree.map_expanded_rows(|_, path| expanded.push(path.clone()));
+    }
     store.clear();
 
-    for net in &s.dynamic_data.networks {
-        store.insert_with_values(None, &[
-            (0, &net.name),
-            (1, &(if net.is_up { "UP" } else { "DOWN" }).to_string()),
-            (2, &format_rate(net.down_rate)),
-            (3, &format_rate(net.up_rate)),
-            (4, &format_size(net.total_down)),
-            (5, &format_size(net.total_up)),
-            (6, &format!("{}/{}", net.packets_rx, net.packets_tx)),
-        ]);
+    let row = |parent: Option<&gtk::TreeIter>, p: &TrafficPeriod| {
+        store.insert_with_values(parent, None, &[
+            (0, &p.period),
+            (1, &format_size(p.rx)),
+            (2, &format_size(p.tx)),
+            (3, &format_size(p.total())),
+        ])
+    };
+    for month in usage.months.iter().rev() {
+        let month_iter = row(None, month);
+        for day in usage.days.iter().rev().filter(|d| d.period.starts_with(&month.period)) {
+            let day_iter = row(Some(&month_iter), day);
+            for hour in usage.hours.iter().rev().filter(|h| h.period.starts_with(&day.period)) {
+                row(Some(&day_iter), hour);
+            }
+        }
+    }
+
+    if same_interface {
+        for path in &expanded {
+            tree.expand_row(path, false);
+        }
+    } else {
+        tree.expand_row(&gtk::TreePath::from_indicesv(&[0]), false);
     }
 }
+
+fn set_interface_row(store: &TreeStore, iter: &gtk::TreeIter, net: &DetailedNetInfo) {
+    let status = if net.is_up {
+        "UP".to_string()
+    } else if net.carrier == Some(false) {
+        "NO CARRIER".to_string()
+    } else {
+        net.operstate.to_uppercase()
+    };
+    let speed = match net.speed_mbps {
+        Some(mbps) if mbps >= 1000 && mbps % 1000 == 0 => format!("{} Gb/s", mbps / 1000),
+        Some(mbps) => format!("{} Mb/s", mbps),
+        None => "-".to_string(),
+    };
+    let speed = if net.duplex.is_empty() || net.speed_mbps.is_none() {
+        speed
+    } else {
+        format!("{} {}", speed, net.duplex)
+    };
+    let mtu = if net.mtu > 0 { net.mtu.to_string() } else { "-".to_string() };
+
+    store.set(iter, &[
+        (1, &net.interface_type),
+        (2, &status),
+        (3, &speed),
+        (4, &mtu),
+        (5, &net.mac),
+        (6, &net.addresses.join(", ")),
+        (7, &net.driver),
+        (8, &format_rate(net.down_rate)),
+        (9, &format_rate(net.up_rate)),
+        (10, &format_size(net.total_down)),
+        (11, &format_size(net.total_up)),
+        (12, &format!("{}/{}", net.packets_rx, net.packets_tx)),
+    ]);
+}
@@ -1,8 +1,22 @@
 use gtk::prelude::*;
-use gtk::{Box, Orientation, TreeView, TreeViewColumn, CellRendererText, ListStore, Widget, ScrolledWindow};
+use gtk::{Box, Orientation, TreeView, TreeViewColumn, CellRendererText, TreeStore, Widget, ScrolledWindow,
+          Frame, Grid, Label, Entry, CheckButton, Button};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 use parking_lot::Mutex;
-use crate::types::AppState;
+use glib::clone;
+use crate::monitors::sensors_conf::{self, SensorSettings};
+use crate::types::{AppState, SensorInfo};
+
+thread_local! {
+    // Row keys ("chip" and "chip/id") in the order the tree was built
+    static SHOWN_SENSORS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// Hidden column holding the row key
+const KEY_COLUMN: u32 = 9;
 
 pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 10);
@@ -12,38 +26,233 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     scrolled_window.set_vexpand(true);
     scrolled_window.set_hexpand(true);
 
-    let store = ListStore::new(&[
+    let store = TreeStore::new(&[
+        glib::Type::STRING, // Sensor
         glib::Type::STRING, // Type
-        glib::Type::STRING, // Label
         glib::Type::STRING, // Value
+        glib::Type::STRING, // Min
+        glib::Type::STRING, // Max
+        glib::Type::STRING, // Crit
+        glib::Type::STRING, // Session Min
+        glib::Type::STRING, // Session Max
         glib::Type::STRING, // State
+        glib::Type::STRING, // Key
     ]);
 
     let tree = TreeView::with_model(&store);
     tree.set_widget_name("sensors_tree");
 
     let cols = [
-        ("Type", 0),
-        ("Label", 1),
+        ("Sensor", 0),
+        ("Type", 1),
         ("Value", 2),
-        ("State / Details", 3),
+        ("Min", 3),
+        ("Max", 4),
+        ("Crit", 5),
+        ("Session Min", 6),
+        ("Session Max", 7),
+        ("State", 8),
     ];
     
     for (title, col_id) in cols.iter() {
         let col = TreeViewColumn::new();
         col.set_title(title);
+        col.set_resizable(true);
         let renderer = CellRendererText::new();
         gtk::prelude::TreeViewColumnExt::pack_start(&col, &renderer, true);
         gtk::prelude::TreeViewColumnExt::add_attribute(&col, &renderer, "text", *col_id);
         tree.append_column(&col);
     }
     
+    let show_hidden = CheckButton::with_label("Show hidden sensors");
+    show_hidden.set_widget_name("sensors_show_hidden");
+    container.pack_start(&show_hidden, false, false, 0);
+
     scrolled_window.add(&tree);
     container.pack_start(&scrolled_window, true, true, 0);
+    container.pack_start(&build_settings_frame(&tree), false, false, 0);
     
     container.upcast::<Widget>()
 }
 
+#[derive(Clone)]
+struct SettingsForm {
+    target_lbl: Label,
+    label_entry: Entry,
+    compute_entry: Entry,
+    min_entry: Entry,
+    max_entry: Entry,
+    crit_entry: Entry,
+    hide_check: CheckButton,
+    save_btn: Button,
+    status_lbl: Label,
+    selected: Rc<RefCell<Option<(String, String)>>>,
+}
+
+/// Editor for the selected sensor's entry in sensors.conf. The monitor picks
+/// up the saved file on its next refresh.
+fn build_settings_frame(tree: &TreeView) -> Frame {
+    let frame = Frame::new(Some(" Sensor Settings "));
+    let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.set_border_width(5);
+
+    let target_lbl = Label::new(Some("Select a sensor to relabel, rescale or hide it."));
+    target_lbl.set_halign(gtk::Align::Start);
+    vbox.pack_start(&target_lbl, false, false, 0);
+
+    let grid = Grid::new();
+    grid.set_row_spacing(4);
+    grid.set_column_spacing(8);
+    let entry = |row: i32, col: i32, title: &str, placeholder: &str| {
+        let lbl = Label::new(Some(title));
+        lbl.set_xalign(0.0);
+        let e = Entry::new();
+        e.set_placeholder_text(Some(placeholder));
+        grid.attach(&lbl, col * 2, row, 1, 1);
+        grid.attach(&e, col * 2 + 1, row, 1, 1);
+        e
+    };
+    let label_entry = entry(0, 0, "Label", "e.g. +3.3V");
+    let compute_entry = entry(0, 1, "Compute", "e.g. @*2, @/2");
+    let min_entry = entry(1, 0, "Warn Below", "hardware limit");
+    let max_entry = entry(1, 1, "Warn Above", "hardware limit");
+    let crit_entry = entry(2, 0, "Critical", "hardware limit");
+    let hide_check = CheckButton::with_label("Hide");
+    grid.attach(&hide_check, 2, 2, 1, 1);
+    let save_btn = Button::with_label("Save");
+    save_btn.style_context().add_class("suggested-action");
+    grid.attach(&save_btn, 3, 2, 1, 1);
+    vbox.pack_start(&grid, false, false, 0);
+
+    let status_lbl = Label::new(None);
+    status_lbl.set_widget_name("sensors_settings_status");
+    status_lbl.set_halign(gtk::Align::Start);
+    status_lbl.set_line_wrap(true);
+    status_lbl.set_text(&storage_hint());
+    vbox.pack_start(&status_lbl, false, false, 0);
+
+    let form = SettingsForm {
+        target_lbl,
+        label_entry,
+        compute_entry,
+        min_entry,
+        max_entry,
+        crit_entry,
+        hide_check,
+        save_btn: save_btn.clone(),
+        status_lbl,
+        selected: Rc::new(RefCell::new(None)),
+    };
+    form.save_btn.set_sensitive(false);
+
+    tree.selection().connect_changed(clone!(@strong form => move |selection| {
+        let key = selection.selected()
+            .and_then(|(model, iter)| model.value(&iter, KEY_COLUMN as i32).get::<String>().ok());
+        // Chip rows have no sensor id
+        let Some((chip, id)) = key.as_deref().and_then(|k| k.rsplit_once('/')) else {
+            *form.selected.borrow_mut() = None;
+            form.save_btn.set_sensitive(false);
+            return;
+        };
+        let settings = match sensors_conf::load() {
+            Ok(config) => {
+                // Values from a wildcard section show up in the form but can't be cleared from it
+                let sources = config.other_sources(chip, id);
+                if sources.is_empty() {
+                    set_status(&form.status_lbl, &storage_hint(), "");
+                } else {
+                    set_status(&form.status_lbl, &format!(
+                        "[WARN] {} is also configured under chip {}; a label or Hide set there can only be removed by editing that section",
+                        id, sources.join(", ")), "text-orange");
+                }
+                config.settings_for(chip, id)
+            }
+            Err(e) => {
+                set_status(&form.status_lbl, &format!("[FAIL] {}", e), "text-red");
+                SensorSettings::default()
+            }
+        };
+        let number = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+        form.target_lbl.set_text(&format!("{} / {}", chip, id));
+        form.label_entry.set_text(settings.label.as_deref().unwrap_or(""));
+        form.compute_entry.set_text(&match &settings.compute {
+            Some((forward, Some(reverse))) => format!("{}, {}", forward, reverse),
+            Some((forward, None)) => forward.clone(),
+            None => String::new(),
+        });
+        form.min_entry.set_text(&number(settings.min));
+        form.max_entry.set_text(&number(settings.max));
+        form.crit_entry.set_text(&number(settings.crit));
+        form.hide_check.set_active(settings.hidden);
+        *form.selected.borrow_mut() = Some((chip.to_string(), id.to_string()));
+        form.save_btn.set_sensitive(true);
+    }));
+
+    save_btn.connect_clicked(clone!(@strong form => move |_| {
+        let Some((chip, id)) = form.selected.borrow().clone() else { return };
+        match save_settings(&form, &chip, &id) {
+            Ok(sources) if sources.is_empty() => set_status(&form.status_lbl, &format!("[OK] Saved settings for {} / {}", chip, id), "text-green"),
+            Ok(sources) => set_status(&form.status_lbl, &format!(
+                "[WARN] Saved, but chip {} still overrides some settings of {} / {}", sources.join(", "), chip, id), "text-orange"),
+            Err(e) => set_status(&form.status_lbl, &format!("[FAIL] {}", e), "text-red"),
+        }
+    }));
+
+    frame.add(&vbox);
+    frame
+}
+
+/// Returns the other sections that still win over what was saved, if any.
+fn save_settings(form: &SettingsForm, chip: &str, id: &str) -> Result<Vec<String>, String> {
+    let number = |entry: &Entry, what: &str| -> Result<Option<f64>, String> {
+        let text = entry.text();
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse::<f64>().map(Some).map_err(|_| format!("{} '{}' is not a number", what, text))
+    };
+    let label = form.label_entry.text().trim().to_string();
+    let compute = form.compute_entry.text().trim().to_string();
+    let settings = SensorSettings {
+        label: (!label.is_empty()).then_some(label),
+        hidden: form.hide_check.is_active(),
+        compute: (!compute.is_empty()).then(|| match compute.split_once(',') {
+            Some((forward, reverse)) => (forward.trim().to_string(), Some(reverse.trim().to_string())),
+            None => (compute.clone(), None),
+        }),
+        min: number(&form.min_entry, "Warn Below")?,
+        max: number(&form.max_entry, "Warn Above")?,
+        crit: number(&form.crit_entry, "Critical")?,
+    };
+
+    // A file that no longer parses is left alone rather than overwritten
+    let mut config = sensors_conf::load()?;
+    config.set_sensor(chip, id, &settings)?;
+    sensors_conf::save(&config)?;
+    if config.settings_for(chip, id) == settings {
+        return Ok(Vec::new());
+    }
+    Ok(config.other_sources(chip, id))
+}
+
+fn storage_hint() -> String {
+    sensors_conf::config_path()
+        .map(|path| format!("Settings are stored in {}", path.display()))
+        .unwrap_or_default()
+}
+
+fn set_status(lbl: &Label, text: &str, class: &str) {
+    let ctx = lbl.style_context();
+    for c in ["text-green", "text-red", "text-orange"] {
+        ctx.remove_class(c);
+    }
+    if !class.is_empty() {
+        ctx.add_class(class);
+    }
+    lbl.set_text(text);
+}
+
 pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
     let container = match tab.clone().downcast::<gtk::Container>() {
         Ok(c) => c,
@@ -55,38 +264,134 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
         None => return,
     };
     
-    let store = match tree.model().and_then(|m| m.downcast::<ListStore>().ok()) {
+    let store = match tree.model().and_then(|m| m.downcast::<TreeStore>().ok()) {
         Some(s) => s,
         None => return,
     };
     
+    let show_hidden = crate::gui::dashboard::find_widget_by_name(&container, "sensors_show_hidden")
+        .and_then(|w| w.downcast::<CheckButton>().ok())
+        .is_some_and(|c| c.is_active());
+
     let s = state.lock();
-    store.clear();
-    
-    for sensor in &s.dynamic_data.sensors {
-        let value_str = match sensor.sensor_type.as_str() {
-            "temp" => format!("{:.1}°C", sensor.value),
-            "fan" => format!("{:.0} RPM", sensor.value),
-            "in" => format!("{:.3} V", sensor.value),
-            "power" => format!("{:.2} W", sensor.value),
-            "curr" => format!("{:.2} A", sensor.value),
-            _ => format!("{:.2}", sensor.value),
-        };
-        
-        let type_display = match sensor.sensor_type.as_str() {
-            "temp" => "Temperature",
-            "fan" => "Fan Speed",
-            "in" => "Voltage",
-            "power" => "Power",
-            "curr" => "Current",
-            _ => "Other",
-        };
+    if let Some(err) = &s.dynamic_data.sensor_config_error {
+        if let Some(lbl) = crate::gui::dashboard::find_widget_by_name(&container, "sensors_settings_status")
+            .and_then(|w| w.downcast::<Label>().ok())
+        {
+            set_status(&lbl, &format!("[WARN] {}", err), "text-orange");
+        }
+    }
+    let sensors: Vec<&SensorInfo> = s.dynamic_data.sensors.iter().filter(|s| show_hidden || !s.hidden).collect();
+
+    let mut chips: Vec<(&str, Vec<&SensorInfo>)> = Vec::new();
+    for sensor in sensors.iter().copied() {
+        match chips.iter_mut().find(|(chip, _)| *chip == sensor.chip) {
+            Some((_, list)) => list.push(sensor),
+            None => chips.push((&sensor.chip, vec![sensor])),
+        }
+    }
+
+    let layout: Vec<String> = chips.iter()
+        .flat_map(|(chip, list)| std::iter::once(chip.to_string()).chain(list.iter().map(|s| sensor_key(s))))
+        .collect();
+    let changed = SHOWN_SENSORS.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        if *shown != layout {
+            *shown = layout;
+            true
+        } else {
+            false
+        }
+    });
+    // Only rebuild when sensors come or go so collapsed chips stay collapsed
+    if changed {
+        store.clear();
+        for (chip, list) in &chips {
+            let parent = store.insert_with_values(None, None, &[(0, chip), (KEY_COLUMN, chip)]);
+            for sensor in list {
+                store.insert_with_values(Some(&parent), None, &[(0, &sensor.label), (KEY_COLUMN, &sensor_key(sensor))]);
+            }
+        }
+        tree.expand_all();
+    }
+
+    let by_key: HashMap<String, &SensorInfo> = sensors.iter().map(|s| (sensor_key(s), *s)).collect();
+    let chip_states: HashMap<&str, &str> = chips.iter()
+        .map(|(chip, list)| (*chip, list.iter().filter(|s| !s.hidden).map(|s| sensor_state(s)).max_by_key(|st| state_rank(st)).unwrap_or("OK")))
+        .collect();
+    store.foreach(|model, _, iter| {
+        let key = model.value(iter, KEY_COLUMN as i32).get::<String>().unwrap_or_default();
+        if let Some(sensor) = by_key.get(&key) {
+            let threshold = |v: Option<f32>| v.map(|v| format_reading(&sensor.sensor_type, v as f64)).unwrap_or_else(|| "-".to_string());
+            store.set(iter, &[
+                (0, &sensor.label),
+                (1, &type_display(&sensor.sensor_type)),
+                (2, &format_reading(&sensor.sensor_type, sensor.value)),
+                (3, &threshold(sensor.min)),
+                (4, &threshold(sensor.max)),
+                (5, &threshold(sensor.critical)),
+                (6, &format_reading(&sensor.sensor_type, sensor.session_min)),
+                (7, &format_reading(&sensor.sensor_type, sensor.session_max)),
+                (8, &sensor_state(sensor)),
+            ]);
+        } else if let Some(state) = chip_states.get(key.as_str()) {
+            store.set(iter, &[(8, state)]);
+        }
+        false
+    });
+}
+
+fn sensor_key(sensor: &SensorInfo) -> String {
+    format!("{}/{}", sensor.chip, sensor.id)
+}
+
+fn sensor_state(sensor: &SensorInfo) -> &'static str {
+    let value = sensor.value as f32;
+    if sensor.hidden {
+        "HIDDEN"
+    } else if sensor.alarm {
+        "ALARM"
+    } else if sensor.critical.is_some_and(|c| value >= c) {
+        "CRIT"
+    } else if sensor.max.is_some_and(|m| value > m) {
+        "HIGH"
+    } else if sensor.min.is_some_and(|m| value < m) {
+        "LOW"
+    } else {
+        "OK"
+    }
+}
+
+fn state_rank(state: &str) -> u8 {
+    match state {
+        "ALARM" => 4,
+        "CRIT" => 3,
+        "HIGH" => 2,
+        "LOW" => 1,
+        _ => 0,
+    }
+}
+
+fn type_display(sensor_type: &str) -> &'static str {
+    match sensor_type {
+        "temp" => "Temperature",
+        "fan" => "Fan Speed",
+        "in" => "Voltage",
+        "power" => "Power",
+        "curr" => "Current",
+        "humidity" => "Humidity",
+        _ => "Other",
+    }
+}
 
-        store.insert_with_values(None, &[
-            (0, &type_display), 
-            (1, &sensor.label), 
-            (2, &value_str), 
-            (3, &"ACTIVE"),
-        ]);
+fn format_reading(sensor_type: &str, value: f64) -> String {
+    match sensor_type {
+        "temp" => format!("{:.1}°C", value),
+        "fan" => format!("{:.0} RPM", value),
+        "in" => format!("{:.3} V", value),
+        "power" => format!("{:.2} W", value),
+        "curr" => format!("{:.2} A", value),
+        "humidity" => format!("{:.1} %RH", value),
+        _ => format!("{:.2}", value),
     }
 }
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use parking_lot::Mutex;
 use glib::clone;
 use crate::types::AppState;
+use crate::utils::format_pressure;
 
 pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
     let container = Box::new(Orientation::Vertical, 5);
@@ -47,12 +48,15 @@ pub fn build_tab(_state: Arc<Mutex<AppState>>) -> Widget {
         glib::Type::STRING, // Status
         glib::Type::STRING, // Enabled
         glib::Type::STRING, // Description
+        glib::Type::STRING, // CPU PSI
+        glib::Type::STRING, // Mem PSI
+        glib::Type::STRING, // IO PSI
     ]);
 
     let tree = TreeView::with_model(&store);
     tree.set_widget_name("services_tree");
 
-    for (title, id) in &[("Name", 0), ("Status", 1), ("Enabled", 2), ("Description", 3)] {
+    for (title, id) in &[("Name", 0), ("Status", 1), ("Enabled", 2), ("Description", 3), ("CPU PSI", 4), ("Mem PSI", 5), ("IO PSI", 6)] {
         let col = TreeViewColumn::new();
         col.set_title(title);
         col.set_resizable(true);
@@ -173,6 +177,9 @@ pub fn update_tab(tab: &Widget, state: &Arc<Mutex<AppState>>) {
             (1, &srv.status),
             (2, &(if srv.enabled { "Yes" } else { "No" }).to_string()),
             (3, &srv.description),
+            (4, &format_pressure(srv.pressure.and_then(|p| p.cpu))),
+            (5, &format_pressure(srv.pressure.and_then(|p| p.memory))),
+            (6, &format_pressure(srv.pressure.and_then(|p| p.io))),
         ]);
     }
 }
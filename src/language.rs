@@ -64,6 +64,7 @@ impl Translator {
         map.insert("tab.config", "-:Config");
         map.insert("tab.containers", "=:Docker");
         map.insert("tab.sensors", "+:Sensors");
+        map.insert("tab.firewall", "F:Firewall");
         map.insert("title.config", "Configuration");
         map.insert("title.puls", "PULS - System Monitor & Admin Tool");
         map.insert("title.cpu", "CPU");
@@ -208,6 +209,7 @@ impl Translator {
         map.insert("tab.config", "-:Ayarlar");
         map.insert("tab.containers", "=:Konteynerler");
         map.insert("tab.sensors", "+:Sensörler");
+        map.insert("tab.firewall", "F:Güvenlik Duvarı");
         map.insert("title.config", "Ayarlar");
         map.insert("title.puls", "PULS - Sistem İzleyici & Yönetim Aracı");
         map.insert("title.cpu", "CPU");
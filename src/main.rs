@@ -5,6 +5,10 @@ mod monitors;
 pub mod gui;
 mod language;
 mod system_service;
+mod fstab;
+mod firewall;
+mod gpu_control;
+mod network_manager;
 mod error_logger;
 
 use crate::types::AppState;
@@ -31,15 +35,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let app_state = Arc::new(Mutex::new(AppState::default()));
     let data_collector = Arc::new(tokio::sync::Mutex::new(DataCollector::new(config.clone())));
     
-    let system_info = {
+    let (system_info, cpu_topology, dimms) = {
         let collector = data_collector.try_lock().unwrap();
-        collector.get_system_info()
+        (collector.get_system_info(), collector.get_cpu_topology(), collector.get_dimms())
     };
     
     {
         let mut state = app_state.lock();
         state.system_info = system_info;
+        state.cpu_topology = cpu_topology;
+        state.dimms = dimms;
         
+        state.safe_mode = config.safe_mode;
         if config.safe_mode {
             state.system_info.push(("Mode".to_string(), "Safe Mode".to_string()));
         }
@@ -50,6 +57,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         state.services = sys_mgr.get_services();
         state.logs = sys_mgr.get_logs(50, None, None);
         state.config_items = sys_mgr.get_grub_config();
+        state.firewall = sys_mgr.get_firewall_ruleset();
+        state.firewalld_zones = sys_mgr.get_firewalld_zones();
         
         state.boots = sys_mgr.get_boots();
         if !state.boots.is_empty() {
@@ -69,6 +78,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         data_collection_loop(app_state_clone, data_collector_clone, config_clone).await;
     });
 
+    // Lets GTK callbacks spawn background work (e.g. directory scans) with tokio::spawn
+    let _rt_guard = rt.enter();
+
     gtk::init()?;
 
     let app = Application::builder()
@@ -82,8 +94,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         crate::gui::build_ui(&a, app_state_for_gui.clone(), config_for_gui.clone());
     });
 
+    // Ctrl+C or a plain kill should still put the GPU settings back below
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        let app = app.clone();
+        glib::unix_signal_add_local(signal, move || {
+            app.quit();
+            glib::ControlFlow::Continue
+        });
+    }
+
     app.run();
 
+    for e in gpu_control::restore_all() {
+        eprintln!("Failed to restore GPU setting {}", e);
+    }
+
     Ok(())
 }
 
@@ -151,11 +176,15 @@ async fn data_collection_loop(
             let logs = sys_mgr.get_logs(100, None, None);
             let services = sys_mgr.get_services();
             let config_items = sys_mgr.get_grub_config();
+            let firewall = sys_mgr.get_firewall_ruleset();
+            let firewalld_zones = sys_mgr.get_firewalld_zones();
             {
                 let mut state = app_state.lock();
                 state.logs = logs;
                 state.services = services;
                 state.config_items = config_items;
+                state.firewall = firewall;
+                state.firewalld_zones = firewalld_zones;
             }
         }
         
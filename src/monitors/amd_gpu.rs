@@ -0,0 +1,277 @@
+// Decoder for the amdgpu `gpu_metrics` sysfs blob. Layouts follow
+// kgd_pp_interface.h: format 1 is discrete GPUs, 2 and 3 are APUs.
+
+// Fields the SMU does not populate read back as all ones
+const UNAVAILABLE_U16: u16 = u16::MAX;
+const UNAVAILABLE_U32: u32 = u32::MAX;
+
+// SMU_THROTTLER_*_BIT values used by indep_throttle_status
+const THROTTLERS: &[(u32, &str)] = &[
+    (0, "PPT0"), (1, "PPT1"), (2, "PPT2"), (3, "PPT3"),
+    (4, "SPL"), (5, "FPPT"), (6, "SPPT"), (7, "SPPT APU"),
+    (16, "TDC GFX"), (17, "TDC SoC"), (18, "TDC Mem"), (19, "TDC VDD"),
+    (20, "TDC CVIP"), (21, "EDC CPU"), (22, "EDC GFX"), (23, "APCC"),
+    (32, "Temp GPU"), (33, "Temp Core"), (34, "Temp Mem"), (35, "Temp Edge"),
+    (36, "Temp Hotspot"), (37, "Temp SoC"), (38, "Temp VR GFX"), (39, "Temp VR SoC"),
+    (40, "Temp VR Mem0"), (41, "Temp VR Mem1"), (42, "Temp Liquid0"), (43, "Temp Liquid1"),
+    (44, "VR Hot0"), (45, "VR Hot1"), (46, "PROCHOT CPU"), (47, "PROCHOT GFX"),
+    (56, "PPM"), (57, "FIT"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpuMetrics {
+    pub format: (u8, u8),
+    pub temperature_edge: Option<u32>,
+    pub temperature_hotspot: Option<u32>,
+    pub temperature_mem: Option<u32>,
+    pub gfx_activity: Option<u32>,
+    pub umc_activity: Option<u32>,
+    pub mm_activity: Option<u32>,
+    // Milliwatts, socket first
+    pub power_rails: Vec<(String, u32)>,
+    pub gfxclk: Option<u32>,
+    pub uclk: Option<u32>,
+    pub throttle_reasons: Vec<String>,
+    pub fan_rpm: Option<u32>,
+    pub pcie_link_width: Option<u32>,
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn u16(&self, off: usize) -> Option<u16> {
+        let b = self.0.get(off..off + 2)?;
+        Some(u16::from_le_bytes([b[0], b[1]])).filter(|&v| v != UNAVAILABLE_U16)
+    }
+
+    fn u32(&self, off: usize) -> Option<u32> {
+        let b = self.0.get(off..off + 4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]])).filter(|&v| v != UNAVAILABLE_U32)
+    }
+
+    fn u64(&self, off: usize) -> Option<u64> {
+        let b = self.0.get(off..off + 8)?;
+        Some(u64::from_le_bytes(b.try_into().ok()?))
+    }
+
+    fn val(&self, off: usize) -> Option<u32> {
+        self.u16(off).map(u32::from)
+    }
+
+    // APU tables report temperatures in centi-degrees
+    fn centi(&self, off: usize) -> Option<u32> {
+        self.u16(off).map(|v| u32::from(v) / 100)
+    }
+}
+
+pub fn parse_gpu_metrics(data: &[u8]) -> Option<GpuMetrics> {
+    let r = Reader(data);
+    let size = r.u16(0)? as usize;
+    let (format, content) = (*data.get(2)?, *data.get(3)?);
+    if data.len() < size {
+        return None;
+    }
+    let mut m = GpuMetrics { format: (format, content), ..Default::default() };
+
+    match (format, content) {
+        (1, 0) => {
+            // v1_0 still had the u64 timestamp first (offset 8 after padding)
+            m.temperature_edge = r.val(16);
+            m.temperature_hotspot = r.val(18);
+            m.temperature_mem = r.val(20);
+            m.gfx_activity = r.val(28);
+            m.umc_activity = r.val(30);
+            m.mm_activity = r.val(32);
+            m.power_rails = watts_rail("Socket", r.val(34));
+            m.gfxclk = r.val(54);
+            m.uclk = r.val(58);
+            m.throttle_reasons = asic_throttle(r.u32(68));
+            m.fan_rpm = r.val(72);
+            m.pcie_link_width = data.get(74).map(|&w| u32::from(w));
+        }
+        (1, 1..=3) => {
+            m.temperature_edge = r.val(4);
+            m.temperature_hotspot = r.val(6);
+            m.temperature_mem = r.val(8);
+            m.gfx_activity = r.val(16);
+            m.umc_activity = r.val(18);
+            m.mm_activity = r.val(20);
+            m.power_rails = watts_rail("Socket", r.val(22));
+            m.gfxclk = r.val(54);
+            m.uclk = r.val(58);
+            m.fan_rpm = r.val(72);
+            m.pcie_link_width = r.val(74);
+            m.throttle_reasons = if content >= 3 {
+                indep_throttle(r.u64(112))
+            } else {
+                asic_throttle(r.u32(68))
+            };
+        }
+        (2, 0) => {
+            // v2_0 still had the u64 timestamp first
+            m.temperature_edge = r.centi(16);
+            m.gfx_activity = r.val(40);
+            m.mm_activity = r.val(42);
+            m.power_rails = mw_rails(&[
+                ("Socket", r.val(44)),
+                ("CPU", r.val(46)),
+                ("SoC", r.val(48)),
+                ("GFX", r.val(50)),
+            ]);
+            m.gfxclk = r.val(80);
+            m.uclk = r.val(84);
+            m.throttle_reasons = asic_throttle(r.u32(112));
+        }
+        (2, 1..=4) => {
+            // From v2_1 the timestamp sits between the activity and power fields
+            m.temperature_edge = r.centi(4);
+            m.gfx_activity = r.val(28);
+            m.mm_activity = r.val(30);
+            m.power_rails = mw_rails(&[
+                ("Socket", r.val(40)),
+                ("CPU", r.val(42)),
+                ("SoC", r.val(44)),
+                ("GFX", r.val(46)),
+            ]);
+            m.gfxclk = r.val(76);
+            m.uclk = r.val(80);
+            m.throttle_reasons = if content >= 2 {
+                indep_throttle(r.u64(120))
+            } else {
+                asic_throttle(r.u32(108))
+            };
+        }
+        (3, 0) => {
+            m.temperature_edge = r.centi(4);
+            m.gfx_activity = r.val(42);
+            m.mm_activity = r.val(44);
+            m.power_rails = mw_rails(&[
+                ("Socket", r.u32(112)),
+                ("APU", r.u32(120)),
+                ("GFX", r.u32(124)),
+                ("dGPU", r.u32(128)),
+                ("Cores", r.u32(132)),
+                ("IPU", r.val(116)),
+            ]);
+            m.gfxclk = r.val(172);
+            m.uclk = r.val(184);
+        }
+        // MI300-class (v1_4+) tables use a different layout altogether
+        _ => return None,
+    }
+    Some(m)
+}
+
+fn watts_rail(name: &str, watts: Option<u32>) -> Vec<(String, u32)> {
+    watts.map(|w| vec![(name.to_string(), w * 1000)]).unwrap_or_default()
+}
+
+fn mw_rails(rails: &[(&str, Option<u32>)]) -> Vec<(String, u32)> {
+    rails.iter()
+        .filter_map(|(name, mw)| mw.filter(|&v| v > 0).map(|v| (name.to_string(), v)))
+        .collect()
+}
+
+fn indep_throttle(status: Option<u64>) -> Vec<String> {
+    let status = status.unwrap_or(0);
+    THROTTLERS.iter()
+        .filter(|(bit, _)| status & (1u64 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+// Older tables only carry the ASIC-specific bitmask, which has no portable decoding
+fn asic_throttle(status: Option<u32>) -> Vec<String> {
+    match status {
+        Some(s) if s != 0 => vec![format!("ASIC status 0x{:08x}", s)],
+        _ => Vec::new(),
+    }
+}
+
+/// Parses a `pp_dpm_sclk`/`pp_dpm_mclk` table into (MHz, active) levels.
+pub fn parse_dpm_table(content: &str) -> Vec<(u32, bool)> {
+    content.lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(':')?;
+            let freq = rest.split_whitespace().next()?;
+            let mhz = freq.to_ascii_lowercase().strip_suffix("mhz")?.parse::<u32>().ok()?;
+            Some((mhz, rest.trim_end().ends_with('*')))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(size: usize, format: u8, content: u8, fields: &[(usize, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0xffu8; size];
+        data[0..2].copy_from_slice(&(size as u16).to_le_bytes());
+        data[2] = format;
+        data[3] = content;
+        for (off, bytes) in fields {
+            data[*off..*off + bytes.len()].copy_from_slice(bytes);
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_gpu_metrics_versions() {
+        // Navi 21 style v1_3 table with indep throttle status (SPL + Temp Hotspot)
+        let data = blob(120, 1, 3, &[
+            (4, &52u16.to_le_bytes()), (6, &68u16.to_le_bytes()), (8, &74u16.to_le_bytes()),
+            (16, &97u16.to_le_bytes()), (18, &41u16.to_le_bytes()), (22, &231u16.to_le_bytes()),
+            (54, &2450u16.to_le_bytes()), (58, &1000u16.to_le_bytes()), (72, &1650u16.to_le_bytes()),
+            (112, &((1u64 << 4) | (1u64 << 36)).to_le_bytes()),
+        ]);
+        let m = parse_gpu_metrics(&data).unwrap();
+        assert_eq!((m.temperature_edge, m.temperature_hotspot, m.temperature_mem), (Some(52), Some(68), Some(74)));
+        assert_eq!((m.gfx_activity, m.umc_activity, m.mm_activity), (Some(97), Some(41), None));
+        assert_eq!(m.power_rails, vec![("Socket".to_string(), 231_000)]);
+        assert_eq!((m.gfxclk, m.uclk, m.fan_rpm), (Some(2450), Some(1000), Some(1650)));
+        assert_eq!(m.throttle_reasons, vec!["SPL", "Temp Hotspot"]);
+
+        // Renoir APU v2_1: centi-degree temperatures and per-rail power in mW
+        let data = blob(120, 2, 1, &[
+            (4, &4550u16.to_le_bytes()), (28, &12u16.to_le_bytes()), (30, &3u16.to_le_bytes()),
+            (40, &15000u16.to_le_bytes()), (42, &9000u16.to_le_bytes()), (44, &0u16.to_le_bytes()), (46, &3000u16.to_le_bytes()),
+            (76, &1600u16.to_le_bytes()), (80, &1200u16.to_le_bytes()), (108, &0u32.to_le_bytes()),
+        ]);
+        let m = parse_gpu_metrics(&data).unwrap();
+        assert_eq!(m.temperature_edge, Some(45));
+        assert_eq!((m.gfx_activity, m.mm_activity), (Some(12), Some(3)));
+        assert_eq!(m.power_rails, vec![("Socket".to_string(), 15000), ("CPU".to_string(), 9000), ("GFX".to_string(), 3000)]);
+        assert_eq!((m.gfxclk, m.uclk), (Some(1600), Some(1200)));
+        assert!(m.throttle_reasons.is_empty());
+
+        // v2_0 keeps the timestamp ahead of the temperatures
+        let data = blob(120, 2, 0, &[
+            (16, &5125u16.to_le_bytes()), (40, &64u16.to_le_bytes()), (44, &11000u16.to_le_bytes()),
+            (80, &1800u16.to_le_bytes()), (112, &0u32.to_le_bytes()),
+        ]);
+        let m = parse_gpu_metrics(&data).unwrap();
+        assert_eq!((m.temperature_edge, m.gfx_activity, m.gfxclk), (Some(51), Some(64), Some(1800)));
+        assert_eq!(m.power_rails, vec![("Socket".to_string(), 11000)]);
+
+        // Phoenix v3_0
+        let data = blob(256, 3, 0, &[
+            (4, &6100u16.to_le_bytes()), (42, &33u16.to_le_bytes()),
+            (112, &24000u32.to_le_bytes()), (124, &7000u32.to_le_bytes()), (172, &2700u16.to_le_bytes()),
+        ]);
+        let m = parse_gpu_metrics(&data).unwrap();
+        assert_eq!(m.temperature_edge, Some(61));
+        assert_eq!(m.power_rails, vec![("Socket".to_string(), 24000), ("GFX".to_string(), 7000)]);
+        assert_eq!(m.gfxclk, Some(2700));
+
+        assert_eq!(parse_gpu_metrics(&blob(64, 1, 5, &[])), None);
+        assert_eq!(parse_gpu_metrics(&[0x78, 0x00, 0x01]), None);
+    }
+
+    #[test]
+    fn test_parse_dpm_table() {
+        let sclk = "0: 500Mhz \n1: 1850Mhz *\n2: 2615Mhz \n";
+        assert_eq!(parse_dpm_table(sclk), vec![(500, false), (1850, true), (2615, false)]);
+        let mclk = "S: 19Mhz *\n0: 96Mhz \n1: 1000MHz\n";
+        assert_eq!(parse_dpm_table(mclk), vec![(19, true), (96, false), (1000, false)]);
+    }
+}
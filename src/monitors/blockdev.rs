@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::monitors::{list_dir, read_string, read_u64};
+use crate::types::BlockDevice;
+
+const SYS_CLASS_BLOCK: &str = "/sys/class/block";
+const UDEV_DATA: &str = "/run/udev/data";
+
+pub fn read_block_tree() -> Vec<BlockDevice> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    let swaps = fs::read_to_string("/proc/swaps").unwrap_or_default();
+    read_block_tree_from(Path::new(SYS_CLASS_BLOCK), Path::new(UDEV_DATA), &mountinfo, &swaps)
+}
+
+pub fn read_block_tree_from(class_block: &Path, udev_data: &Path, mountinfo: &str, swaps: &str) -> Vec<BlockDevice> {
+    let mounts = parse_mountinfo(mountinfo);
+    let swap_devices: HashSet<String> = swaps
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split_whitespace().next())
+        .map(|dev| dev.rsplit('/').next().unwrap_or(dev).to_string())
+        .collect();
+
+    let mut nodes: HashMap<String, (BlockDevice, Vec<String>)> = HashMap::new();
+    let entries = match fs::read_dir(class_block) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = fs::canonicalize(entry.path()).unwrap_or_else(|_| entry.path());
+        let size = read_u64(&path.join("size")).unwrap_or(0) * 512;
+        if (name.starts_with("loop") || name.starts_with("ram")) && size == 0 {
+            continue;
+        }
+
+        let is_partition = path.join("partition").exists();
+        let parents: Vec<String> = if is_partition {
+            path.parent()
+                .and_then(|p| p.file_name())
+                .map(|n| vec![n.to_string_lossy().to_string()])
+                .unwrap_or_default()
+        } else {
+            list_dir(&path.join("slaves"))
+        };
+
+        let devnum = read_string(&path.join("dev")).unwrap_or_default();
+        let udev = read_udev(&udev_data.join(format!("b{}", devnum)));
+        let mut mountpoints = mounts.get(&devnum).map(|(_, m)| m.clone()).unwrap_or_default();
+        if swap_devices.contains(&name) {
+            mountpoints.push("[SWAP]".to_string());
+        }
+        let fs_type = udev
+            .get("ID_FS_TYPE")
+            .cloned()
+            .or_else(|| mounts.get(&devnum).map(|(fs, _)| fs.clone()))
+            .unwrap_or_default();
+
+        let (kind, display_name) = classify(&name, &path, is_partition);
+
+        let node = BlockDevice {
+            name: name.clone(),
+            display_name,
+            kind,
+            size,
+            model: read_string(&path.join("device/model"))
+                .or_else(|| udev.get("ID_MODEL").map(|m| m.replace('_', " ")))
+                .unwrap_or_default(),
+            serial: read_string(&path.join("device/serial"))
+                .or_else(|| udev.get("ID_SERIAL_SHORT").cloned())
+                .unwrap_or_default(),
+            transport: if is_partition || !parents.is_empty() { String::new() } else { transport(&name, &path) },
+            scheduler: read_string(&path.join("queue/scheduler"))
+                .and_then(|s| s.split_whitespace().find(|t| t.starts_with('[')).map(|t| t.trim_matches(|c| c == '[' || c == ']').to_string()))
+                .unwrap_or_default(),
+            rotational: read_u64(&path.join("queue/rotational")).map(|v| v == 1),
+            removable: read_u64(&path.join("removable")) == Some(1),
+            read_only: read_u64(&path.join("ro")) == Some(1),
+            fs_type,
+            label: udev.get("ID_FS_LABEL").cloned().unwrap_or_default(),
+            uuid: udev.get("ID_FS_UUID").cloned().unwrap_or_default(),
+            mountpoints,
+            children: Vec::new(),
+        };
+        nodes.insert(name, (node, parents));
+    }
+
+    let mut children_of: HashMap<String, Vec<String>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (name, (_, parents)) in &nodes {
+        let known: Vec<&String> = parents.iter().filter(|p| nodes.contains_key(*p)).collect();
+        if known.is_empty() {
+            roots.push(name.clone());
+        }
+        for parent in known {
+            children_of.entry(parent.clone()).or_default().push(name.clone());
+        }
+    }
+    roots.sort_by_key(|name| natural_key(name));
+
+    roots
+        .iter()
+        .map(|name| build_node(name, &nodes, &children_of, 0))
+        .collect()
+}
+
+fn build_node(
+    name: &str,
+    nodes: &HashMap<String, (BlockDevice, Vec<String>)>,
+    children_of: &HashMap<String, Vec<String>>,
+    depth: usize,
+) -> BlockDevice {
+    let mut node = nodes[name].0.clone();
+    if depth < 8 {
+        let mut kids = children_of.get(name).cloned().unwrap_or_default();
+        kids.sort_by_key(|name| natural_key(name));
+        node.children = kids
+            .iter()
+            .map(|k| build_node(k, nodes, children_of, depth + 1))
+            .collect();
+    }
+    node
+}
+
+/// Physical disks underneath a block device, following partitions and dm/md slaves.
+pub fn backing_disks(name: &str) -> Vec<String> {
+    if crate::monitors::diskstats::is_physical_disk(name) {
+        return vec![name.to_string()];
+    }
+    let class_path = Path::new(SYS_CLASS_BLOCK).join(name);
+    if class_path.join("partition").exists() {
+        return fs::canonicalize(&class_path)
+            .ok()
+            .and_then(|p| p.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()))
+            .map(|parent| backing_disks(&parent))
+            .unwrap_or_default();
+    }
+    let mut disks: Vec<String> = list_dir(&class_path.join("slaves"))
+        .iter()
+        .flat_map(|slave| backing_disks(slave))
+        .collect();
+    disks.sort();
+    disks.dedup();
+    disks
+}
+
+fn classify(name: &str, path: &Path, is_partition: bool) -> (String, String) {
+    if is_partition {
+        return ("part".to_string(), name.to_string());
+    }
+    if name.starts_with("dm-") {
+        let dm_name = read_string(&path.join("dm/name")).unwrap_or_else(|| name.to_string());
+        let uuid = read_string(&path.join("dm/uuid")).unwrap_or_default();
+        let kind = if uuid.starts_with("CRYPT-") {
+            "crypt"
+        } else if uuid.starts_with("LVM-") {
+            "lvm"
+        } else {
+            "dm"
+        };
+        return (kind.to_string(), format!("mapper/{}", dm_name));
+    }
+    if name.starts_with("md") {
+        let level = read_string(&path.join("md/level")).unwrap_or_else(|| "md".to_string());
+        return (level, name.to_string());
+    }
+    if name.starts_with("loop") {
+        return ("loop".to_string(), name.to_string());
+    }
+    if name.starts_with("zram") {
+        return ("zram".to_string(), name.to_string());
+    }
+    if name.starts_with("sr") {
+        return ("rom".to_string(), name.to_string());
+    }
+    ("disk".to_string(), name.to_string())
+}
+
+fn transport(name: &str, path: &Path) -> String {
+    let sys_path = path.to_string_lossy();
+    if name.starts_with("nvme") {
+        "NVMe"
+    } else if sys_path.contains("/usb") {
+        "USB"
+    } else if sys_path.contains("/ata") {
+        "SATA"
+    } else if name.starts_with("mmcblk") {
+        "MMC"
+    } else if name.starts_with("vd") || sys_path.contains("/virtio") {
+        "virtio"
+    } else if sys_path.contains("/host") {
+        "SCSI"
+    } else {
+        ""
+    }
+    .to_string()
+}
+
+/// Maps `major:minor` to (fstype, mountpoints) from /proc/self/mountinfo.
+pub fn parse_mountinfo(content: &str) -> HashMap<String, (String, Vec<String>)> {
+    let mut mounts: HashMap<String, (String, Vec<String>)> = HashMap::new();
+    for line in content.lines() {
+        let (pre, post) = match line.split_once(" - ") {
+            Some(p) => p,
+            None => continue,
+        };
+        let fields: Vec<&str> = pre.split_whitespace().collect();
+        if fields.len() < 5 {
+            continue;
+        }
+        let fs_type = post.split_whitespace().next().unwrap_or("").to_string();
+        let entry = mounts.entry(fields[2].to_string()).or_insert_with(|| (fs_type, Vec::new()));
+        entry.1.push(unescape_mount(fields[4]));
+    }
+    mounts
+}
+
+pub fn unescape_mount(path: &str) -> String {
+    path.replace("\\040", " ").replace("\\011", "\t").replace("\\012", "\n").replace("\\134", "\\")
+}
+
+fn read_udev(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .map(|c| {
+            c.lines()
+                .filter_map(|l| l.strip_prefix("E:"))
+                .filter_map(|kv| kv.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// sda2 < sda10, nvme0n1p2 < nvme0n1p10
+fn natural_key(name: &str) -> (String, u64) {
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (prefix, num) = name.split_at(name.len() - digits);
+    (prefix.to_string(), num.parse().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let content = "29 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw\n\
+                       30 29 259:1 / /boot\\040efi rw shared:2 - vfat /dev/nvme0n1p1 rw\n\
+                       31 29 0:25 / /proc rw - proc proc rw\n";
+        let mounts = parse_mountinfo(content);
+        assert_eq!(mounts["259:2"], ("ext4".to_string(), vec!["/".to_string()]));
+        assert_eq!(mounts["259:1"].1, vec!["/boot efi".to_string()]);
+    }
+
+    #[test]
+    fn test_block_tree_with_luks_and_lvm() {
+        let root = std::env::temp_dir().join(format!("puls-blk-{}", std::process::id()));
+        let devices = root.join("devices");
+        let class = root.join("class");
+        fs::create_dir_all(&class).unwrap();
+
+        let disk = devices.join("pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda");
+        write_fixture(disk.join("size"), "1000000");
+        write_fixture(disk.join("dev"), "8:0");
+        write_fixture(disk.join("device/model"), "Samsung SSD 870");
+        write_fixture(disk.join("queue/scheduler"), "none [mq-deadline] kyber");
+        write_fixture(disk.join("queue/rotational"), "0");
+        for (i, size) in [(1, "2048"), (2, "900000"), (10, "4096")] {
+            write_fixture(disk.join(format!("sda{}/size", i)), size);
+            write_fixture(disk.join(format!("sda{}/partition", i)), &i.to_string());
+            write_fixture(disk.join(format!("sda{}/dev", i)), &format!("8:{}", i));
+            symlink(disk.join(format!("sda{}", i)), class.join(format!("sda{}", i))).unwrap();
+        }
+        symlink(&disk, class.join("sda")).unwrap();
+
+        let crypt = devices.join("virtual/block/dm-0");
+        write_fixture(crypt.join("size"), "899000");
+        write_fixture(crypt.join("dev"), "253:0");
+        write_fixture(crypt.join("dm/name"), "luks-root");
+        write_fixture(crypt.join("dm/uuid"), "CRYPT-LUKS2-abcd-luks-root");
+        write_fixture(crypt.join("slaves/sda2"), "");
+        symlink(&crypt, class.join("dm-0")).unwrap();
+
+        let lv = devices.join("virtual/block/dm-1");
+        write_fixture(lv.join("size"), "800000");
+        write_fixture(lv.join("dev"), "253:1");
+        write_fixture(lv.join("dm/name"), "vg-root");
+        write_fixture(lv.join("dm/uuid"), "LVM-xyz");
+        write_fixture(lv.join("slaves/dm-0"), "");
+        symlink(&lv, class.join("dm-1")).unwrap();
+
+        write_fixture(root.join("udev/b253:1"), "S:disk/by-uuid/1234\nE:ID_FS_TYPE=btrfs\nE:ID_FS_UUID=1234\n");
+        let mountinfo = "29 1 253:1 / / rw shared:1 - btrfs /dev/mapper/vg-root rw\n";
+
+        let tree = read_block_tree_from(&class, &root.join("udev"), mountinfo, "");
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(tree.len(), 1);
+        let sda = &tree[0];
+        assert_eq!(sda.kind, "disk");
+        assert_eq!(sda.size, 1000000 * 512);
+        assert_eq!(sda.transport, "SATA");
+        assert_eq!(sda.scheduler, "mq-deadline");
+        assert_eq!(sda.model, "Samsung SSD 870");
+        let parts: Vec<&str> = sda.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(parts, vec!["sda1", "sda2", "sda10"]);
+
+        let luks = &sda.children[1].children[0];
+        assert_eq!(luks.kind, "crypt");
+        assert_eq!(luks.display_name, "mapper/luks-root");
+        let lvm = &luks.children[0];
+        assert_eq!(lvm.kind, "lvm");
+        assert_eq!(lvm.fs_type, "btrfs");
+        assert_eq!(lvm.mountpoints, vec!["/".to_string()]);
+    }
+}
@@ -213,6 +213,7 @@ impl ContainerMonitor {
                 disk_w,
                 image,
                 ports,
+                pressure: crate::monitors::pressure::container_pressure(&id_full),
             });
         }
         
@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::monitors::read_string;
+use crate::types::{CacheInfo, CoreKind, CpuDomain, CpuTopology, LogicalCpu, PhysicalCore};
+
+const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+const DEVICES_SYSFS: &str = "/sys/devices";
+
+pub fn read_cpu_topology() -> CpuTopology {
+    read_cpu_topology_from(Path::new(CPU_SYSFS), Path::new(DEVICES_SYSFS))
+}
+
+pub fn read_cpu_topology_from(cpu_root: &Path, devices_root: &Path) -> CpuTopology {
+    let mut ids: Vec<usize> = match fs::read_dir(cpu_root) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_prefix("cpu").and_then(|n| n.parse::<usize>().ok())
+            })
+            .collect(),
+        Err(_) => return CpuTopology::default(),
+    };
+    ids.sort_unstable();
+
+    // Intel hybrid parts expose one PMU per core type
+    let p_cores = read_string(&devices_root.join("cpu_core/cpus")).map(|s| parse_cpu_list(&s));
+    let e_cores = read_string(&devices_root.join("cpu_atom/cpus")).map(|s| parse_cpu_list(&s));
+
+    let mut caches: Vec<CacheInfo> = Vec::new();
+    let mut cpus = Vec::new();
+    let mut capacities = Vec::new();
+
+    for id in ids {
+        let cpu_dir = cpu_root.join(format!("cpu{}", id));
+        let topo = cpu_dir.join("topology");
+        if !topo.exists() {
+            continue;
+        }
+
+        let package_id = read_u32(&topo.join("physical_package_id")).unwrap_or(0);
+        let die_id = read_u32(&topo.join("die_id")).unwrap_or(0);
+        let core_id = read_u32(&topo.join("core_id")).unwrap_or(id as u32);
+        let thread_siblings = read_string(&topo.join("thread_siblings_list"))
+            .map(|s| parse_cpu_list(&s))
+            .unwrap_or_else(|| vec![id]);
+
+        let mut l3_group = None;
+        if let Ok(entries) = fs::read_dir(cpu_dir.join("cache")) {
+            let mut indexes: Vec<_> = entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("index"))
+                .map(|e| e.path())
+                .collect();
+            indexes.sort();
+
+            for index in indexes {
+                let level = read_u32(&index.join("level")).unwrap_or(0) as u8;
+                let cache_type = read_string(&index.join("type")).unwrap_or_default();
+                let size = read_string(&index.join("size")).map(|s| parse_cache_size(&s)).unwrap_or(0);
+                let shared_cpus = read_string(&index.join("shared_cpu_list"))
+                    .map(|s| parse_cpu_list(&s))
+                    .unwrap_or_else(|| vec![id]);
+
+                let pos = match caches.iter().position(|c| {
+                    c.level == level && c.cache_type == cache_type && c.shared_cpus == shared_cpus
+                }) {
+                    Some(pos) => pos,
+                    None => {
+                        caches.push(CacheInfo { level, cache_type, size, shared_cpus });
+                        caches.len() - 1
+                    }
+                };
+
+                if level == 3 {
+                    l3_group = Some(pos);
+                }
+            }
+        }
+
+        let kind = match (&p_cores, &e_cores) {
+            (Some(p), _) if p.contains(&id) => CoreKind::Performance,
+            (_, Some(e)) if e.contains(&id) => CoreKind::Efficiency,
+            _ => CoreKind::Standard,
+        };
+
+        capacities.push(read_u32(&cpu_dir.join("cpu_capacity")));
+
+        cpus.push(LogicalCpu {
+            id,
+            package_id,
+            die_id,
+            core_id,
+            thread_siblings,
+            l3_group,
+            kind,
+        });
+    }
+
+    // big.LITTLE systems report relative capacity instead of a hybrid PMU
+    if p_cores.is_none() && e_cores.is_none() {
+        let known: Vec<u32> = capacities.iter().flatten().copied().collect();
+        if let (Some(&max), Some(&min)) = (known.iter().max(), known.iter().min()) {
+            if max != min {
+                for (cpu, cap) in cpus.iter_mut().zip(&capacities) {
+                    cpu.kind = match cap {
+                        Some(c) if *c == max => CoreKind::Performance,
+                        Some(_) => CoreKind::Efficiency,
+                        None => CoreKind::Standard,
+                    };
+                }
+            }
+        }
+    }
+
+    // Renumber L3 groups so they index only the L3 entries
+    let l3_positions: Vec<usize> = caches.iter().enumerate()
+        .filter(|(_, c)| c.level == 3)
+        .map(|(i, _)| i)
+        .collect();
+    for cpu in cpus.iter_mut() {
+        cpu.l3_group = cpu.l3_group.and_then(|g| l3_positions.iter().position(|&p| p == g));
+    }
+
+    CpuTopology { cpus, caches }
+}
+
+impl CpuTopology {
+    pub fn package_count(&self) -> usize {
+        let mut packages: Vec<u32> = self.cpus.iter().map(|c| c.package_id).collect();
+        packages.sort_unstable();
+        packages.dedup();
+        packages.len()
+    }
+
+    pub fn physical_cores(&self) -> Vec<PhysicalCore> {
+        let mut cores: BTreeMap<(u32, u32, u32), PhysicalCore> = BTreeMap::new();
+        for cpu in &self.cpus {
+            let core = cores.entry((cpu.package_id, cpu.die_id, cpu.core_id)).or_insert_with(|| PhysicalCore {
+                package_id: cpu.package_id,
+                core_id: cpu.core_id,
+                kind: cpu.kind,
+                threads: Vec::new(),
+            });
+            core.threads.push(cpu.id);
+        }
+        cores.into_values().collect()
+    }
+
+    pub fn is_hybrid(&self) -> bool {
+        self.cpus.iter().any(|c| c.kind == CoreKind::Efficiency)
+    }
+
+    /// Groups physical cores by package and then by die or shared L3 slice,
+    /// which is how CCDs/CCXs show up on AMD parts.
+    pub fn domains(&self) -> Vec<CpuDomain> {
+        let l3_caches: Vec<&CacheInfo> = self.caches.iter().filter(|c| c.level == 3).collect();
+        let mut grouped: BTreeMap<(u32, u32, Option<usize>), Vec<PhysicalCore>> = BTreeMap::new();
+
+        for core in self.physical_cores() {
+            let first = match core.threads.first().and_then(|t| self.cpus.iter().find(|c| c.id == *t)) {
+                Some(c) => c,
+                None => continue,
+            };
+            grouped.entry((first.package_id, first.die_id, first.l3_group)).or_default().push(core);
+        }
+
+        let multi_package = self.package_count() > 1;
+        let mut per_package: BTreeMap<u32, usize> = BTreeMap::new();
+        for (package_id, _, _) in grouped.keys() {
+            *per_package.entry(*package_id).or_default() += 1;
+        }
+
+        let mut index_in_package: BTreeMap<u32, usize> = BTreeMap::new();
+        grouped.into_iter().map(|((package_id, die_id, l3_group), cores)| {
+            let idx = index_in_package.entry(package_id).or_default();
+            let split = per_package.get(&package_id).copied().unwrap_or(1) > 1;
+            let same_die = self.cpus.iter()
+                .filter(|c| c.package_id == package_id)
+                .all(|c| c.die_id == die_id);
+
+            let mut parts = Vec::new();
+            if multi_package {
+                parts.push(format!("Package {}", package_id));
+            }
+            if split {
+                if same_die {
+                    parts.push(format!("CCD {}", idx));
+                } else {
+                    parts.push(format!("Die {}", die_id));
+                }
+            }
+            if parts.is_empty() {
+                parts.push("All Cores".to_string());
+            }
+            *idx += 1;
+
+            CpuDomain {
+                label: parts.join(" / "),
+                package_id,
+                l3_size: l3_group.and_then(|g| l3_caches.get(g)).map(|c| c.size),
+                cores,
+            }
+        }).collect()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.cpus.is_empty() {
+            return "Unknown".to_string();
+        }
+        let cores = self.physical_cores();
+        let domains = self.domains();
+        let mut parts = vec![format!(
+            "{} Package{}",
+            self.package_count(),
+            if self.package_count() == 1 { "" } else { "s" }
+        )];
+        if domains.len() > self.package_count() {
+            parts.push(format!("{} CCDs/Dies", domains.len()));
+        }
+        parts.push(format!("{} Cores / {} Threads", cores.len(), self.cpus.len()));
+        if self.is_hybrid() {
+            let p = cores.iter().filter(|c| c.kind == CoreKind::Performance).count();
+            let e = cores.iter().filter(|c| c.kind == CoreKind::Efficiency).count();
+            parts.push(format!("{}P + {}E", p, e));
+        }
+        parts.join(" | ")
+    }
+
+    pub fn cache_summary(&self) -> String {
+        let mut levels: BTreeMap<(u8, String), (u64, usize)> = BTreeMap::new();
+        for cache in &self.caches {
+            let name = match cache.cache_type.as_str() {
+                "Data" => format!("L{}d", cache.level),
+                "Instruction" => format!("L{}i", cache.level),
+                _ => format!("L{}", cache.level),
+            };
+            let entry = levels.entry((cache.level, name)).or_insert((cache.size, 0));
+            entry.1 += 1;
+        }
+        levels.into_iter()
+            .map(|((_, name), (size, count))| format!("{} {} x{}", name, crate::utils::format_size(size), count))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+/// Parses kernel cpu lists such as `0-3,8,10-11`.
+pub fn parse_cpu_list(s: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+        if let Some((start, end)) = part.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                cpus.extend(start..=end);
+            }
+        } else if let Ok(cpu) = part.parse::<usize>() {
+            cpus.push(cpu);
+        }
+    }
+    cpus
+}
+
+fn parse_cache_size(s: &str) -> u64 {
+    let s = s.trim();
+    let (num, mult) = match s.chars().last() {
+        Some('K') => (&s[..s.len() - 1], 1024),
+        Some('M') => (&s[..s.len() - 1], 1024 * 1024),
+        Some('G') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+    num.parse::<u64>().unwrap_or(0) * mult
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    read_string(path).and_then(|s| s.parse::<u32>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_cache_size() {
+        assert_eq!(parse_cache_size("32K"), 32 * 1024);
+        assert_eq!(parse_cache_size("16M"), 16 * 1024 * 1024);
+        assert_eq!(parse_cache_size("512"), 512);
+    }
+
+    #[test]
+    fn test_two_ccd_smt_topology() {
+        let root = std::env::temp_dir().join(format!("puls-topo-{}", std::process::id()));
+        let cpu_root = root.join("cpu");
+        // 2 CCDs x 2 cores x 2 threads, siblings are n and n+4
+        for id in 0..8usize {
+            let core = id % 4;
+            let dir = cpu_root.join(format!("cpu{}", id));
+            write_fixture(dir.join("topology/physical_package_id"), "0");
+            write_fixture(dir.join("topology/die_id"), "0");
+            write_fixture(dir.join("topology/core_id"), &core.to_string());
+            write_fixture(dir.join("topology/thread_siblings_list"), &format!("{},{}", core, core + 4));
+            write_fixture(dir.join("cache/index0/level"), "1");
+            write_fixture(dir.join("cache/index0/type"), "Data");
+            write_fixture(dir.join("cache/index0/size"), "32K");
+            write_fixture(dir.join("cache/index0/shared_cpu_list"), &format!("{},{}", core, core + 4));
+            let ccd = if core < 2 { "0-1,4-5" } else { "2-3,6-7" };
+            write_fixture(dir.join("cache/index3/level"), "3");
+            write_fixture(dir.join("cache/index3/type"), "Unified");
+            write_fixture(dir.join("cache/index3/size"), "32768K");
+            write_fixture(dir.join("cache/index3/shared_cpu_list"), ccd);
+        }
+
+        let topo = read_cpu_topology_from(&cpu_root, &root.join("devices"));
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(topo.cpus.len(), 8);
+        assert_eq!(topo.physical_cores().len(), 4);
+        assert!(!topo.is_hybrid());
+
+        let domains = topo.domains();
+        assert_eq!(domains.len(), 2);
+        assert_eq!(domains[0].label, "CCD 0");
+        assert_eq!(domains[0].l3_size, Some(32 * 1024 * 1024));
+        assert_eq!(domains[1].cores[0].threads, vec![2, 6]);
+    }
+
+    #[test]
+    fn test_hybrid_classification() {
+        let root = std::env::temp_dir().join(format!("puls-hybrid-{}", std::process::id()));
+        let cpu_root = root.join("cpu");
+        for id in 0..4usize {
+            let dir = cpu_root.join(format!("cpu{}", id));
+            write_fixture(dir.join("topology/core_id"), &id.to_string());
+            write_fixture(dir.join("topology/thread_siblings_list"), &id.to_string());
+        }
+        write_fixture(root.join("devices/cpu_core/cpus"), "0-1");
+        write_fixture(root.join("devices/cpu_atom/cpus"), "2-3");
+
+        let topo = read_cpu_topology_from(&cpu_root, &root.join("devices"));
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(topo.is_hybrid());
+        assert_eq!(topo.cpus[0].kind, CoreKind::Performance);
+        assert_eq!(topo.cpus[3].kind, CoreKind::Efficiency);
+        assert!(topo.summary().contains("2P + 2E"));
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::types::DirUsage;
+
+// Files remembered per directory; smaller ones still count towards the totals
+const MAX_FILES_PER_DIR: usize = 20;
+const MAX_CHILDREN: usize = 100;
+
+struct ScanNode {
+    name: String,
+    parent: Option<usize>,
+    size: u64,
+    files: u64,
+    children: Vec<usize>,
+    largest_files: Vec<(String, u64)>,
+}
+
+/// Directory tree with sizes aggregated upwards as entries are added, so a
+/// snapshot taken mid-scan already shows correct partial totals.
+pub struct ScanTree {
+    nodes: Vec<ScanNode>,
+    pub dirs: u64,
+    pub errors: u64,
+}
+
+impl ScanTree {
+    pub fn new(root: &str) -> Self {
+        Self {
+            nodes: vec![ScanNode {
+                name: root.to_string(),
+                parent: None,
+                size: 0,
+                files: 0,
+                children: Vec::new(),
+                largest_files: Vec::new(),
+            }],
+            dirs: 1,
+            errors: 0,
+        }
+    }
+
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    pub fn add_dir(&mut self, parent: usize, name: String, own_size: u64) -> usize {
+        let idx = self.nodes.len();
+        self.nodes.push(ScanNode {
+            name,
+            parent: Some(parent),
+            size: 0,
+            files: 0,
+            children: Vec::new(),
+            largest_files: Vec::new(),
+        });
+        self.nodes[parent].children.push(idx);
+        self.dirs += 1;
+        self.add_size(idx, own_size, 0);
+        idx
+    }
+
+    pub fn add_file(&mut self, dir: usize, name: String, size: u64) {
+        self.add_size(dir, size, 1);
+        let files = &mut self.nodes[dir].largest_files;
+        if files.len() < MAX_FILES_PER_DIR {
+            files.push((name, size));
+        } else if let Some(smallest) = files.iter_mut().min_by_key(|(_, s)| *s).filter(|(_, s)| *s < size) {
+            *smallest = (name, size);
+        }
+    }
+
+    fn add_size(&mut self, idx: usize, size: u64, files: u64) {
+        let mut cur = Some(idx);
+        while let Some(i) = cur {
+            self.nodes[i].size += size;
+            self.nodes[i].files += files;
+            cur = self.nodes[i].parent;
+        }
+    }
+
+    pub fn total(&self) -> (u64, u64) {
+        (self.nodes[0].size, self.nodes[0].files)
+    }
+
+    /// Looks up a directory by absolute path below the scan root.
+    pub fn find(&self, path: &str) -> Option<usize> {
+        let rel = Path::new(path).strip_prefix(&self.nodes[0].name).ok()?;
+        rel.components().try_fold(0, |idx, comp| {
+            let name = comp.as_os_str().to_string_lossy();
+            self.nodes[idx]
+                .children
+                .iter()
+                .copied()
+                .find(|&c| self.nodes[c].name == name)
+        })
+    }
+
+    fn path_of(&self, idx: usize) -> PathBuf {
+        let mut names = Vec::new();
+        let mut cur = Some(idx);
+        while let Some(i) = cur {
+            names.push(self.nodes[i].name.as_str());
+            cur = self.nodes[i].parent;
+        }
+        names.iter().rev().collect()
+    }
+
+    /// Copies `depth` levels below `idx`, largest entries first.
+    pub fn snapshot(&self, idx: usize, depth: usize) -> DirUsage {
+        let node = &self.nodes[idx];
+        let path = self.path_of(idx);
+        let mut children = Vec::new();
+        if depth > 0 {
+            children.extend(node.children.iter().map(|&c| self.snapshot(c, depth - 1)));
+            children.extend(node.largest_files.iter().map(|(name, size)| DirUsage {
+                name: name.clone(),
+                path: path.join(name).to_string_lossy().to_string(),
+                size: *size,
+                files: 1,
+                is_dir: false,
+                children: Vec::new(),
+            }));
+            children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+            children.truncate(MAX_CHILDREN);
+        }
+        DirUsage {
+            name: node.name.clone(),
+            path: path.to_string_lossy().to_string(),
+            size: node.size,
+            files: node.files,
+            is_dir: true,
+            children,
+        }
+    }
+}
+
+/// A running (or finished) scan shared between the tokio task and the GUI.
+pub struct DirScan {
+    pub root: PathBuf,
+    tree: Mutex<ScanTree>,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl DirScan {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    /// (bytes, files, directories, unreadable entries) seen so far.
+    pub fn progress(&self) -> (u64, u64, u64, u64) {
+        let tree = self.tree.lock();
+        let (size, files) = tree.total();
+        (size, files, tree.dirs, tree.errors)
+    }
+
+    pub fn snapshot(&self, path: &str, depth: usize) -> Option<DirUsage> {
+        let tree = self.tree.lock();
+        tree.find(path).map(|idx| tree.snapshot(idx, depth))
+    }
+}
+
+/// Starts walking `root` on the tokio runtime, staying on its filesystem.
+/// Must be called from within a runtime context.
+pub fn start_scan(root: &Path) -> Arc<DirScan> {
+    let scan = Arc::new(DirScan {
+        root: root.to_path_buf(),
+        tree: Mutex::new(ScanTree::new(&root.to_string_lossy())),
+        cancelled: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    });
+    let task_scan = scan.clone();
+    tokio::spawn(async move {
+        walk(&task_scan).await;
+        task_scan.finished.store(true, Ordering::Relaxed);
+    });
+    scan
+}
+
+async fn walk(scan: &DirScan) {
+    let root_dev = match tokio::fs::symlink_metadata(&scan.root).await {
+        Ok(meta) => meta.dev(),
+        Err(_) => {
+            scan.tree.lock().errors += 1;
+            return;
+        }
+    };
+    // Hard-linked files are counted once, like du
+    let mut seen_inodes: HashSet<u64> = HashSet::new();
+    let mut stack = vec![(scan.root.clone(), scan.tree.lock().root())];
+
+    while let Some((dir, idx)) = stack.pop() {
+        if scan.is_cancelled() {
+            return;
+        }
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(_) => {
+                scan.tree.lock().errors += 1;
+                continue;
+            }
+        };
+
+        let mut subdirs = Vec::new();
+        let mut files = Vec::new();
+        let mut errors = 0;
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(_) => {
+                    errors += 1;
+                    break;
+                }
+            };
+            // DirEntry metadata does not follow symlinks
+            let meta = match entry.metadata().await {
+                Ok(meta) => meta,
+                Err(_) => {
+                    errors += 1;
+                    continue;
+                }
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let size = meta.blocks() * 512;
+            if meta.is_dir() {
+                if meta.dev() == root_dev {
+                    subdirs.push((name, size));
+                }
+            } else if meta.nlink() <= 1 || seen_inodes.insert(meta.ino()) {
+                files.push((name, size));
+            }
+        }
+
+        let mut tree = scan.tree.lock();
+        tree.errors += errors;
+        for (name, size) in files {
+            tree.add_file(idx, name, size);
+        }
+        for (name, size) in subdirs {
+            let path = dir.join(&name);
+            let child = tree.add_dir(idx, name, size);
+            stack.push((path, child));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_tree_aggregation() {
+        let mut tree = ScanTree::new("/data");
+        let root = tree.root();
+        let logs = tree.add_dir(root, "logs".to_string(), 4096);
+        let old = tree.add_dir(logs, "old".to_string(), 4096);
+        tree.add_file(root, "notes.txt".to_string(), 100);
+        tree.add_file(logs, "app.log".to_string(), 5000);
+        tree.add_file(old, "app.log.1".to_string(), 20000);
+
+        assert_eq!(tree.total(), (4096 * 2 + 25100, 3));
+        assert_eq!(tree.find("/data/logs/old"), Some(old));
+        assert_eq!(tree.find("/data"), Some(root));
+        assert_eq!(tree.find("/data/missing"), None);
+        assert_eq!(tree.find("/elsewhere"), None);
+
+        let snap = tree.snapshot(root, 1);
+        assert_eq!(snap.children.len(), 2);
+        assert_eq!(snap.children[0].name, "logs");
+        assert_eq!(snap.children[0].size, 4096 * 2 + 25000);
+        assert_eq!(snap.children[0].files, 2);
+        assert!(snap.children[0].children.is_empty());
+        assert_eq!(snap.children[1].path, "/data/notes.txt");
+        assert!(!snap.children[1].is_dir);
+
+        let deep = tree.snapshot(old, 1);
+        assert_eq!(deep.path, "/data/logs/old");
+        assert_eq!(deep.children[0].path, "/data/logs/old/app.log.1");
+    }
+
+    #[test]
+    fn test_largest_files_kept() {
+        let mut tree = ScanTree::new("/");
+        let root = tree.root();
+        for i in 0..(MAX_FILES_PER_DIR as u64 + 5) {
+            tree.add_file(root, format!("f{}", i), i * 10);
+        }
+        let snap = tree.snapshot(root, 1);
+        assert_eq!(snap.children.len(), MAX_FILES_PER_DIR);
+        assert_eq!(snap.files, MAX_FILES_PER_DIR as u64 + 5);
+        assert_eq!(snap.children[0].name, format!("f{}", MAX_FILES_PER_DIR + 4));
+        assert!(snap.children.iter().all(|c| c.size >= 50));
+    }
+}
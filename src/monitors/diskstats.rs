@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::types::BlockDeviceIo;
+
+// /proc/diskstats always counts in 512-byte sectors regardless of the device
+const SECTOR_SIZE: u64 = 512;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskStatsSample {
+    pub name: String,
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+    pub write_ms: u64,
+    pub in_flight: u64,
+    pub io_ms: u64,
+    pub weighted_io_ms: u64,
+}
+
+/// Columns after `major minor name`: reads merged sectors ms writes merged sectors ms
+/// in_flight io_ms weighted_ms (discard/flush columns on newer kernels are ignored).
+pub fn parse_diskstats(content: &str) -> Vec<DiskStatsSample> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 14 {
+                return None;
+            }
+            let num = |i: usize| parts[i].parse::<u64>().unwrap_or(0);
+            Some(DiskStatsSample {
+                name: parts[2].to_string(),
+                reads: num(3),
+                sectors_read: num(5),
+                read_ms: num(6),
+                writes: num(7),
+                sectors_written: num(9),
+                write_ms: num(10),
+                in_flight: num(11),
+                io_ms: num(12),
+                weighted_io_ms: num(13),
+            })
+        })
+        .filter(|s| !s.name.starts_with("loop") && !s.name.starts_with("ram"))
+        .collect()
+}
+
+/// Whole disks backed by real hardware; partitions, dm, md and zram have no `device` link.
+pub fn is_physical_disk(name: &str) -> bool {
+    Path::new("/sys/block").join(name).join("device").exists()
+}
+
+pub struct DiskIoTracker {
+    prev: HashMap<String, DiskStatsSample>,
+    prev_time: Option<Instant>,
+}
+
+impl DiskIoTracker {
+    pub fn new() -> Self {
+        Self {
+            prev: HashMap::new(),
+            prev_time: None,
+        }
+    }
+
+    pub fn refresh(&mut self) -> Vec<BlockDeviceIo> {
+        let samples = fs::read_to_string("/proc/diskstats")
+            .map(|c| parse_diskstats(&c))
+            .unwrap_or_default();
+        self.update(Instant::now(), samples, is_physical_disk)
+    }
+
+    pub fn update<F>(&mut self, now: Instant, samples: Vec<DiskStatsSample>, is_physical: F) -> Vec<BlockDeviceIo>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let elapsed_ms = self
+            .prev_time
+            .map(|t| now.duration_since(t).as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+
+        let devices = samples
+            .iter()
+            .map(|cur| {
+                let mut io = BlockDeviceIo {
+                    name: cur.name.clone(),
+                    physical: is_physical(&cur.name),
+                    in_flight: cur.in_flight,
+                    ..Default::default()
+                };
+                if let Some(prev) = self.prev.get(&cur.name).filter(|_| elapsed_ms > 0.0) {
+                    let secs = elapsed_ms / 1000.0;
+                    let reads = cur.reads.saturating_sub(prev.reads);
+                    let writes = cur.writes.saturating_sub(prev.writes);
+                    io.read_rate = ((cur.sectors_read.saturating_sub(prev.sectors_read) * SECTOR_SIZE) as f64 / secs) as u64;
+                    io.write_rate = ((cur.sectors_written.saturating_sub(prev.sectors_written) * SECTOR_SIZE) as f64 / secs) as u64;
+                    io.read_iops = reads as f64 / secs;
+                    io.write_iops = writes as f64 / secs;
+                    io.read_latency_ms = if reads > 0 {
+                        cur.read_ms.saturating_sub(prev.read_ms) as f64 / reads as f64
+                    } else {
+                        0.0
+                    };
+                    io.write_latency_ms = if writes > 0 {
+                        cur.write_ms.saturating_sub(prev.write_ms) as f64 / writes as f64
+                    } else {
+                        0.0
+                    };
+                    io.queue_depth = cur.weighted_io_ms.saturating_sub(prev.weighted_io_ms) as f64 / elapsed_ms;
+                    io.util_pct = (cur.io_ms.saturating_sub(prev.io_ms) as f64 / elapsed_ms * 100.0).clamp(0.0, 100.0);
+                }
+                io
+            })
+            .collect();
+
+        self.prev = samples.into_iter().map(|s| (s.name.clone(), s)).collect();
+        self.prev_time = Some(now);
+        devices
+    }
+}
+
+impl Default for DiskIoTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maps a mount source such as `/dev/mapper/root` to its diskstats name (`dm-0`).
+pub fn block_device_name(device: &str) -> String {
+    let resolved = fs::canonicalize(device)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| device.to_string());
+    resolved.rsplit('/').next().unwrap_or(&resolved).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const SNAPSHOT_A: &str = "   7       0 loop0 10 0 20 0 0 0 0 0 0 0 0 0 0 0 0\n\
+                              259       0 nvme0n1 1000 0 8000 500 2000 0 16000 4000 0 3000 4500 0 0 0 0\n\
+                              259       1 nvme0n1p1 900 0 7000 450 1900 0 15000 3900 0 2900 4300\n\
+                              253       0 dm-0 950 0 7500 480 1950 0 15500 3950 0 2950 4400 0 0 0 0\n";
+    const SNAPSHOT_B: &str = "   7       0 loop0 10 0 20 0 0 0 0 0 0 0 0 0 0 0 0\n\
+                              259       0 nvme0n1 1100 0 10048 700 2400 0 24192 5600 2 3500 6500 0 0 0 0\n\
+                              259       1 nvme0n1p1 1000 0 9048 650 2300 0 23192 5500 2 3400 6300\n\
+                              253       0 dm-0 1050 0 9548 680 2350 0 23692 5550 2 3450 6400 0 0 0 0\n";
+
+    #[test]
+    fn test_parse_diskstats() {
+        let stats = parse_diskstats(SNAPSHOT_A);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].name, "nvme0n1");
+        assert_eq!(stats[0].reads, 1000);
+        assert_eq!(stats[0].sectors_written, 16000);
+        assert_eq!(stats[0].weighted_io_ms, 4500);
+        assert_eq!(stats[1].name, "nvme0n1p1");
+    }
+
+    #[test]
+    fn test_tracker_rates() {
+        let mut tracker = DiskIoTracker::new();
+        let start = Instant::now();
+        let physical = |name: &str| name == "nvme0n1";
+
+        let first = tracker.update(start, parse_diskstats(SNAPSHOT_A), physical);
+        assert_eq!(first[0].read_rate, 0);
+
+        let second = tracker.update(start + Duration::from_secs(1), parse_diskstats(SNAPSHOT_B), physical);
+        let disk = &second[0];
+        assert!(disk.physical);
+        assert!(!second[2].physical);
+        assert_eq!(disk.read_rate, 2048 * 512);
+        assert_eq!(disk.write_rate, 8192 * 512);
+        assert_eq!(disk.read_iops, 100.0);
+        assert_eq!(disk.write_iops, 400.0);
+        assert_eq!(disk.read_latency_ms, 2.0);
+        assert_eq!(disk.write_latency_ms, 4.0);
+        assert_eq!(disk.queue_depth, 2.0);
+        assert_eq!(disk.util_pct, 50.0);
+        assert_eq!(disk.in_flight, 2);
+    }
+}
@@ -1,13 +1,29 @@
 use crate::types::GpuInfo;
-use std::collections::VecDeque;
+use super::amd_gpu;
+use super::intel_gpu::IntelGpu;
+use super::nvml::{self, Nvml};
+use std::collections::{HashMap, VecDeque};
 use std::process::Command;
 use std::path::Path;
 use std::fs;
 
+const NVIDIA_SMI_FIELDS: &[&str] = &[
+    "name", "utilization.gpu", "memory.used", "memory.total", "temperature.gpu",
+    "power.draw", "clocks.gr", "clocks.mem", "fan.speed", "driver_version",
+    "pcie.link.gen.current", "pcie.link.width.current", "power.limit",
+    "clocks_throttle_reasons.active", "ecc.errors.corrected.volatile.total",
+    "ecc.errors.uncorrected.volatile.total",
+];
+
 pub struct GpuMonitor {
     gpu_history: VecDeque<Vec<u32>>,
     gpu_memory_history: VecDeque<Vec<u32>>,
+    gpu_temperature_history: VecDeque<Vec<u32>>,
+    gpu_power_history: VecDeque<Vec<u32>>,
+    gpu_clock_history: VecDeque<Vec<u32>>,
     last_update: std::time::Instant,
+    nvml: Option<Nvml>,
+    intel: HashMap<String, IntelGpu>,
 }
 
 impl GpuMonitor {
@@ -15,7 +31,12 @@ impl GpuMonitor {
         Self {
             gpu_history: VecDeque::new(),
             gpu_memory_history: VecDeque::new(),
+            gpu_temperature_history: VecDeque::new(),
+            gpu_power_history: VecDeque::new(),
+            gpu_clock_history: VecDeque::new(),
             last_update: std::time::Instant::now(),
+            nvml: Nvml::load().ok(),
+            intel: HashMap::new(),
         }
     }
     
@@ -41,78 +62,41 @@ impl GpuMonitor {
             }
         } else {
             for (i, gpu) in gpus.iter_mut().enumerate() {
-                gpu.utilization_history = self.gpu_history
-                    .iter()
-                    .filter_map(|frame| frame.get(i).cloned())
-                    .collect();
-                    
-                gpu.memory_history = self.gpu_memory_history
-                    .iter()
-                    .filter_map(|frame| frame.get(i).cloned())
-                    .collect();
+                gpu.utilization_history = column(&self.gpu_history, i);
+                gpu.memory_history = column(&self.gpu_memory_history, i);
+                gpu.temperature_history = column(&self.gpu_temperature_history, i);
+                gpu.power_history = column(&self.gpu_power_history, i);
+                gpu.clock_history = column(&self.gpu_clock_history, i);
             }
             Ok(gpus)
         }
     }
     
-    fn get_nvidia_gpus(&self) -> Result<Vec<GpuInfo>, String> {
+    fn get_nvidia_gpus(&mut self) -> Result<Vec<GpuInfo>, String> {
+        let nvml_error = match self.nvml.as_mut().map(|n| n.gpus()) {
+            Some(Ok(gpus)) => return Ok(gpus),
+            Some(Err(e)) => Some(e),
+            None => None,
+        };
+
         let output = Command::new("nvidia-smi")
-            .arg("--query-gpu=name,utilization.gpu,memory.used,memory.total,temperature.gpu,power.draw,clocks.gr,clocks.mem,fan.speed,driver_version,pcie.link.gen.current,pcie.link.width.current")
+            .arg(format!("--query-gpu={}", NVIDIA_SMI_FIELDS.join(",")))
             .arg("--format=csv,noheader,nounits")
             .output()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| match &nvml_error {
+                Some(n) => format!("{}; nvidia-smi: {}", n, e),
+                None => e.to_string(),
+            })?;
             
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(format!("nvidia-smi failed: {}", stderr.trim()));
         }
         
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut gpus = Vec::new();
-        
-        for line in stdout.lines() {
-            let parts: Vec<&str> = line.split(", ").collect();
-            if parts.len() < 9 { 
-                continue;
-            }
-            
-            let name = parts[0].to_string();
-            let utilization = parts[1].parse::<u32>().unwrap_or(0);
-            let memory_used = parts[2].parse::<u64>().unwrap_or(0) * 1024 * 1024;
-            let memory_total = parts[3].parse::<u64>().unwrap_or(0) * 1024 * 1024;
-            let temperature = parts[4].parse::<u32>().unwrap_or(0);
-            let power_usage = (parts[5].parse::<f32>().unwrap_or(0.0) * 1000.0) as u32;
-            let graphics_clock = parts[6].parse::<u32>().unwrap_or(0);
-            let memory_clock = parts[7].parse::<u32>().unwrap_or(0);
-            let fan_speed = parts[8].parse::<u32>().ok();
-            let driver_version = parts.get(9).unwrap_or(&"Unknown").to_string();
-            let pci_link_gen = parts.get(10).and_then(|s| s.parse::<u32>().ok());
-            let pci_link_width = parts.get(11).and_then(|s| s.parse::<u32>().ok());
-            
-            gpus.push(GpuInfo {
-                name,
-                brand: "NVIDIA".to_string(),
-                utilization,
-                memory_used,
-                memory_total,
-                temperature,
-                memory_temperature: None,
-                power_usage,
-                graphics_clock,
-                memory_clock,
-                fan_speed,
-                pci_link_gen,
-                pci_link_width,
-                driver_version,
-                utilization_history: Vec::new(),
-                memory_history: Vec::new(),
-            });
-        }
-        
-        Ok(gpus)
+        Ok(parse_nvidia_smi_csv(&String::from_utf8_lossy(&output.stdout)))
     }
 
-    fn get_drm_gpus(&self) -> Result<Vec<GpuInfo>, String> {
+    fn get_drm_gpus(&mut self) -> Result<Vec<GpuInfo>, String> {
         let mut gpus = Vec::new();
         let drm_path = Path::new("/sys/class/drm");
         
@@ -175,28 +159,66 @@ impl GpuMonitor {
             })
             .unwrap_or(0);
 
+        let metrics = fs::read(device_path.join("gpu_metrics")).ok()
+            .and_then(|data| amd_gpu::parse_gpu_metrics(&data))
+            .unwrap_or_default();
+        let utilization = if utilization == 0 { metrics.gfx_activity.unwrap_or(0) } else { utilization };
+
         let (memory_used, memory_total) = self.read_amd_memory(device_path);
-        let temperature = self.find_hwmon_temp(device_path).unwrap_or(0);
-        let power_usage = self.find_hwmon_power(device_path).unwrap_or(0);
+        let gtt_used = read_sysfs_u64(&device_path.join("mem_info_gtt_used")).unwrap_or(0);
+        let gtt_total = read_sysfs_u64(&device_path.join("mem_info_gtt_total")).unwrap_or(0);
+        let memory_activity = read_sysfs_u64(&device_path.join("mem_busy_percent"))
+            .map(|v| v as u32)
+            .or(metrics.umc_activity);
 
-        let mut graphics_clock = self.read_amd_clock(device_path, "pp_dpm_sclk").unwrap_or(0);
-        if graphics_clock == 0 {
-             // Fallback to hwmon freq inputs
-             graphics_clock = self.find_hwmon_clock(device_path, "freq1_input").unwrap_or(0);
-        }
+        // amdgpu labels its hwmon sensors edge, junction and mem
+        let temperature = metrics.temperature_edge
+            .or_else(|| self.find_hwmon_labeled_temp(device_path, "edge"))
+            .or_else(|| self.find_hwmon_temp(device_path))
+            .unwrap_or(0);
+        let hotspot_temperature = metrics.temperature_hotspot
+            .or_else(|| self.find_hwmon_labeled_temp(device_path, "junction"));
+        let memory_temperature = metrics.temperature_mem
+            .or_else(|| self.find_hwmon_labeled_temp(device_path, "mem"));
 
-        let mut memory_clock = self.read_amd_clock(device_path, "pp_dpm_mclk").unwrap_or(0);
-        if memory_clock == 0 {
-             memory_clock = self.find_hwmon_clock(device_path, "freq2_input").unwrap_or(0);
-        }
+        let power_usage = self.find_hwmon_power(device_path)
+            .or_else(|| metrics.power_rails.first().map(|(_, mw)| *mw))
+            .unwrap_or(0);
+        let power_limit = self.find_hwmon_file(device_path, "power1_cap")
+            .filter(|&uw| uw > 0)
+            .map(|uw| (uw / 1000) as u32);
+        let fan_pwm = self.find_hwmon_file(device_path, "pwm1")
+            .map(|pwm| (pwm * 100 / 255) as u32);
+
+        let sclk_levels = fs::read_to_string(device_path.join("pp_dpm_sclk"))
+            .map(|c| amd_gpu::parse_dpm_table(&c))
+            .unwrap_or_default();
+        let mclk_levels = fs::read_to_string(device_path.join("pp_dpm_mclk"))
+            .map(|c| amd_gpu::parse_dpm_table(&c))
+            .unwrap_or_default();
+        let active_level = |levels: &[(u32, bool)]| levels.iter().find(|(_, active)| *active).map(|(mhz, _)| *mhz);
+
+        // Prefer the SMU's averaged clocks, then the active DPM level, then hwmon
+        let graphics_clock = metrics.gfxclk
+            .or_else(|| active_level(&sclk_levels))
+            .or_else(|| self.find_hwmon_clock(device_path, "freq1_input"))
+            .or_else(|| self.find_hwmon_clock(device_path, "freq0_input"))
+            .unwrap_or(0);
+        let memory_clock = metrics.uclk
+            .or_else(|| active_level(&mclk_levels))
+            .or_else(|| self.find_hwmon_clock(device_path, "freq2_input"))
+            .unwrap_or(0);
         
         let pci_link_gen = fs::read_to_string(device_path.join("current_link_gen"))
             .ok().and_then(|s| s.trim().parse::<u32>().ok());
         let pci_link_width = fs::read_to_string(device_path.join("current_link_width"))
-            .ok().and_then(|s| s.trim().parse::<u32>().ok());
+            .ok().and_then(|s| s.trim().parse::<u32>().ok())
+            .or(metrics.pcie_link_width);
 
-        if graphics_clock == 0 {
-             graphics_clock = self.find_hwmon_clock(device_path, "freq0_input").unwrap_or(0);
+        // The SMU reports graphics and multimedia (VCN) activity separately
+        let mut engines = vec![("GFX".to_string(), utilization)];
+        if let Some(mm) = metrics.mm_activity {
+            engines.push(("Video".to_string(), mm));
         }
 
         Ok(GpuInfo {
@@ -206,16 +228,28 @@ impl GpuMonitor {
             memory_used,
             memory_total,
             temperature,
-            memory_temperature: None,
+            memory_temperature,
+            hotspot_temperature,
             power_usage,
+            power_limit,
             graphics_clock,
             memory_clock,
-            fan_speed: self.find_hwmon_fan(device_path), 
+            fan_speed: self.find_hwmon_fan(device_path).or(metrics.fan_rpm),
+            fan_pwm,
             pci_link_gen,
             pci_link_width,
             driver_version: "amdgpu".to_string(),
-            utilization_history: Vec::new(),
-            memory_history: Vec::new(),
+            backend: if metrics.format.0 > 0 { format!("gpu_metrics v{}.{}", metrics.format.0, metrics.format.1) } else { "sysfs".to_string() },
+            throttle_reasons: metrics.throttle_reasons,
+            memory_activity,
+            gtt_used,
+            gtt_total,
+            sclk_levels,
+            mclk_levels,
+            power_rails: metrics.power_rails,
+            engines,
+            sysfs_path: Some(device_path.to_string_lossy().to_string()),
+            ..Default::default()
         })
     }
     
@@ -233,22 +267,6 @@ impl GpuMonitor {
         (used, total)
     }
 
-    fn read_amd_clock(&self, device_path: &Path, file_name: &str) -> Option<u32> {
-        if let Ok(content) = fs::read_to_string(device_path.join(file_name)) {
-            for line in content.lines() {
-                if line.contains('*') {
-                    for part in line.split_whitespace() {
-                        if part.ends_with("Mhz") {
-                             let num_str = &part[..part.len()-3];
-                             return num_str.parse::<u32>().ok();
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
     fn find_hwmon_clock(&self, device_path: &Path, filename: &str) -> Option<u32> {
         let hwmon_dir = device_path.join("hwmon");
         if let Ok(entries) = fs::read_dir(hwmon_dir) {
@@ -284,10 +302,13 @@ impl GpuMonitor {
         None
     }
 
-    fn parse_intel_gpu(&self, card_path: &Path, device_path: &Path, card_name: &str) -> Result<GpuInfo, String> {
+    fn parse_intel_gpu(&mut self, card_path: &Path, device_path: &Path, card_name: &str) -> Result<GpuInfo, String> {
         let name = fs::read_to_string(device_path.join("device"))
              .map(|id| format!("Intel Graphics ({})", id.trim()))
              .unwrap_or_else(|_| format!("Intel GPU ({})", card_name));
+        let driver_version = fs::read_link(device_path.join("driver")).ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "i915".to_string());
 
         let freq_paths = [
             card_path.join("gt/gt0/rps_act_freq_mhz"),
@@ -298,6 +319,8 @@ impl GpuMonitor {
             card_path.join("gt_cur_freq_mhz"),
             device_path.join("gt_act_freq_mhz"),
             device_path.join("gt_cur_freq_mhz"),
+            device_path.join("tile0/gt0/freq0/act_freq"),
+            device_path.join("tile0/gt0/freq0/cur_freq"),
         ];
 
         let mut graphics_clock = 0;
@@ -314,27 +337,40 @@ impl GpuMonitor {
                 }
             }
         }
-             
+
+        let sample = self.intel
+            .entry(card_name.to_string())
+            .or_insert_with(|| IntelGpu::open(card_path, device_path))
+            .sample();
+        let (memory_used, memory_total) = sample.lmem.unwrap_or((0, 0));
+
         let temperature = self.find_hwmon_temp(device_path).unwrap_or(0);
-        let power_usage = self.find_hwmon_power(device_path).unwrap_or(0);
+        let power_usage = sample.power_usage
+            .or_else(|| self.find_hwmon_power(device_path))
+            .unwrap_or(0);
         
         Ok(GpuInfo {
             name,
             brand: "Intel".to_string(),
-            utilization: 0, 
-            memory_used: 0,
-            memory_total: 0,
+            utilization: sample.engines.iter().map(|(_, busy)| *busy).max().unwrap_or(0),
+            memory_used,
+            memory_total,
             temperature,
             memory_temperature: None,
             power_usage,
+            power_limit: sample.power_limit,
             graphics_clock,
             memory_clock: 0,
             fan_speed: None,
             pci_link_gen: None,
             pci_link_width: None,
-            driver_version: "i915".to_string(),
-            utilization_history: Vec::new(),
-            memory_history: Vec::new(),
+            driver_version,
+            backend: "sysfs".to_string(),
+            engines: sample.engines,
+            rc6_residency: sample.rc6_residency,
+            telemetry_error: sample.error,
+            sysfs_path: Some(device_path.to_string_lossy().to_string()),
+            ..Default::default()
         })
     }
 
@@ -362,6 +398,26 @@ impl GpuMonitor {
         None
     }
     
+    fn find_hwmon_labeled_temp(&self, device_path: &Path, label: &str) -> Option<u32> {
+        for entry in fs::read_dir(device_path.join("hwmon")).ok()?.flatten() {
+            for i in 1..=3 {
+                let matches = fs::read_to_string(entry.path().join(format!("temp{}_label", i)))
+                    .map(|l| l.trim() == label)
+                    .unwrap_or(false);
+                if matches {
+                    return read_sysfs_u64(&entry.path().join(format!("temp{}_input", i))).map(|v| (v / 1000) as u32);
+                }
+            }
+        }
+        None
+    }
+
+    fn find_hwmon_file(&self, device_path: &Path, file_name: &str) -> Option<u64> {
+        fs::read_dir(device_path.join("hwmon")).ok()?
+            .flatten()
+            .find_map(|entry| read_sysfs_u64(&entry.path().join(file_name)))
+    }
+
     fn find_hwmon_power(&self, device_path: &Path) -> Option<u32> {
          let hwmon_dir = device_path.join("hwmon");
         if let Ok(entries) = fs::read_dir(hwmon_dir) {
@@ -404,15 +460,11 @@ impl GpuMonitor {
             }
         }).collect();
         
-        self.gpu_history.push_back(utilizations);
-        self.gpu_memory_history.push_back(memory_usage);
-        
-        while self.gpu_history.len() > max_history {
-            self.gpu_history.pop_front();
-        }
-        while self.gpu_memory_history.len() > max_history {
-            self.gpu_memory_history.pop_front();
-        }
+        push_frame(&mut self.gpu_history, utilizations, max_history);
+        push_frame(&mut self.gpu_memory_history, memory_usage, max_history);
+        push_frame(&mut self.gpu_temperature_history, gpus.iter().map(|g| g.temperature).collect(), max_history);
+        push_frame(&mut self.gpu_power_history, gpus.iter().map(|g| g.power_usage).collect(), max_history);
+        push_frame(&mut self.gpu_clock_history, gpus.iter().map(|g| g.graphics_clock).collect(), max_history);
     }
 
     fn find_hwmon_utilization(&self, device_path: &Path) -> Option<u32> {
@@ -457,4 +509,133 @@ impl GpuMonitor {
     pub fn is_available(&self) -> bool {
         true
     }
-}
\ No newline at end of file
+}
+
+// History is kept as one frame per update holding a value per GPU
+fn push_frame(history: &mut VecDeque<Vec<u32>>, frame: Vec<u32>, max_history: usize) {
+    history.push_back(frame);
+    while history.len() > max_history {
+        history.pop_front();
+    }
+}
+
+fn column(history: &VecDeque<Vec<u32>>, index: usize) -> Vec<u32> {
+    history.iter().filter_map(|frame| frame.get(index).copied()).collect()
+}
+
+fn read_sysfs_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok().and_then(|s| s.trim().parse::<u64>().ok())
+}
+
+/// Parses `nvidia-smi --query-gpu` output in [`NVIDIA_SMI_FIELDS`] order.
+/// Fields the driver cannot report come back as `[N/A]` or `[Not Supported]`.
+pub fn parse_nvidia_smi_csv(output: &str) -> Vec<GpuInfo> {
+    let mut gpus = Vec::new();
+
+    for line in output.lines() {
+        let mut fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() < 9 {
+            continue;
+        }
+        // Only the name can contain a comma
+        let extra = fields.len().saturating_sub(NVIDIA_SMI_FIELDS.len());
+        let name = fields[..=extra].join(", ");
+        fields.drain(..extra);
+
+        let num = |i: usize| fields.get(i).and_then(|s| s.parse::<f64>().ok());
+        let text = |i: usize| fields.get(i).filter(|s| !s.is_empty() && !s.starts_with('[')).map(|s| s.to_string());
+
+        let throttle_reasons = text(13)
+            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .map(nvml::throttle_reason_names)
+            .unwrap_or_default();
+
+        gpus.push(GpuInfo {
+            name,
+            brand: "NVIDIA".to_string(),
+            utilization: num(1).unwrap_or(0.0) as u32,
+            memory_used: num(2).unwrap_or(0.0) as u64 * 1024 * 1024,
+            memory_total: num(3).unwrap_or(0.0) as u64 * 1024 * 1024,
+            temperature: num(4).unwrap_or(0.0) as u32,
+            power_usage: (num(5).unwrap_or(0.0) * 1000.0) as u32,
+            graphics_clock: num(6).unwrap_or(0.0) as u32,
+            memory_clock: num(7).unwrap_or(0.0) as u32,
+            fan_speed: num(8).map(|v| v as u32),
+            driver_version: text(9).unwrap_or_else(|| "Unknown".to_string()),
+            pci_link_gen: num(10).map(|v| v as u32),
+            pci_link_width: num(11).map(|v| v as u32),
+            power_limit: num(12).map(|v| (v * 1000.0) as u32),
+            throttle_reasons,
+            ecc_corrected: num(14).map(|v| v as u64),
+            ecc_uncorrected: num(15).map(|v| v as u64),
+            backend: "nvidia-smi".to_string(),
+            nvidia_index: Some(gpus.len() as u32),
+            ..Default::default()
+        });
+    }
+
+    gpus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvidia_smi_unavailable_fields() {
+        // Recorded from a laptop GPU (no fan, ECC or power readings) and a datacenter card
+        let output = "\
+NVIDIA GeForce RTX 3050 Laptop GPU, 7, 512, 4096, 46, [N/A], 210, 405, [N/A], 550.54.14, 1, 16, [N/A], 0x0000000000000001, [N/A], [N/A]
+Tesla T4, 98, 14802, 15360, 71, 69.42, 1590, 5000, [N/A], 550.54.14, 3, 16, 70.00, 0x0000000000000024, 3, 0
+";
+        let gpus = parse_nvidia_smi_csv(output);
+        assert_eq!(gpus.len(), 2);
+
+        let laptop = &gpus[0];
+        assert_eq!(laptop.name, "NVIDIA GeForce RTX 3050 Laptop GPU");
+        assert_eq!(laptop.utilization, 7);
+        assert_eq!(laptop.memory_total, 4096 * 1024 * 1024);
+        assert_eq!(laptop.power_usage, 0);
+        assert_eq!(laptop.power_limit, None);
+        assert_eq!(laptop.fan_speed, None);
+        assert_eq!(laptop.ecc_corrected, None);
+        assert_eq!(laptop.throttle_reasons, vec!["Idle"]);
+        assert_eq!(laptop.pci_link_gen, Some(1));
+
+        let tesla = &gpus[1];
+        assert_eq!(tesla.power_usage, 69420);
+        assert_eq!(tesla.power_limit, Some(70000));
+        assert_eq!(tesla.graphics_clock, 1590);
+        assert_eq!(tesla.throttle_reasons, vec!["SW Power Cap", "SW Thermal"]);
+        assert_eq!(tesla.ecc_corrected, Some(3));
+        assert_eq!(tesla.ecc_uncorrected, Some(0));
+        assert_eq!(tesla.driver_version, "550.54.14");
+    }
+
+    #[test]
+    fn test_parse_short_and_comma_names() {
+        // Rows from the original nine-column query still parse
+        let gpus = parse_nvidia_smi_csv("Quadro K2200, 3, 180, 4096, 38, 11.80, 135, 405, 42\n[N/A], [N/A]\n");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].fan_speed, Some(42));
+        assert_eq!(gpus[0].driver_version, "Unknown");
+        assert_eq!(gpus[0].throttle_reasons, Vec::<String>::new());
+
+        let line = "Acme, Inc. GPU, 1, 2, 3, 4, 5.00, 6, 7, 8, 535.1, 4, 8, 100.00, 0x0000000000000000, 0, 0";
+        let gpus = parse_nvidia_smi_csv(line);
+        assert_eq!(gpus[0].name, "Acme, Inc. GPU");
+        assert_eq!(gpus[0].pci_link_width, Some(8));
+        assert_eq!(gpus[0].power_limit, Some(100000));
+    }
+
+    #[test]
+    fn test_history_capped_per_gpu() {
+        let mut history = VecDeque::new();
+        for frame in [vec![10, 50], vec![20], vec![30, 70]] {
+            push_frame(&mut history, frame, 2);
+        }
+        // A GPU missing from a frame just has a shorter series
+        assert_eq!(column(&history, 0), vec![20, 30]);
+        assert_eq!(column(&history, 1), vec![70]);
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::monitors::read_string;
+use crate::types::SensorInfo;
+
+const HWMON_SYSFS: &str = "/sys/class/hwmon";
+
+// (attribute prefix, unit, divisor from the sysfs integer)
+const SENSOR_KINDS: &[(&str, &str, f64)] = &[
+    ("temp", "°C", 1000.0),
+    ("fan", "RPM", 1.0),
+    ("in", "V", 1000.0),
+    ("power", "W", 1_000_000.0),
+    ("curr", "A", 1000.0),
+    ("humidity", "%RH", 1000.0),
+];
+
+pub fn read_sensors() -> Vec<SensorInfo> {
+    read_sensors_from(Path::new(HWMON_SYSFS))
+}
+
+pub fn read_sensors_from(hwmon_root: &Path) -> Vec<SensorInfo> {
+    let mut dirs: Vec<PathBuf> = match fs::read_dir(hwmon_root) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    dirs.sort();
+
+    let mut sensors = Vec::new();
+    for dir in dirs {
+        let chip = chip_name(&dir);
+        let mut chip_sensors = read_chip(&dir, &chip);
+        chip_sensors.sort_by_key(|s| sort_key(&s.sensor_type, &s.id));
+        sensors.append(&mut chip_sensors);
+    }
+    sensors
+}
+
+/// Reads every `<kind><n>_input` of one hwmon device along with its label,
+/// thresholds and alarm flags.
+fn read_chip(dir: &Path, chip: &str) -> Vec<SensorInfo> {
+    let mut ids: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let id = name.strip_suffix("_input").or_else(|| name.strip_suffix("_average"))?;
+                split_id(id).map(|_| id.to_string())
+            })
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    // power sensors may expose both _input and _average
+    ids.sort();
+    ids.dedup();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let (kind, _) = split_id(&id)?;
+            let &(_, unit, divisor) = SENSOR_KINDS.iter().find(|(k, _, _)| *k == kind)?;
+            let attr = |suffix: &str| dir.join(format!("{}_{}", id, suffix));
+            let scaled = |suffix: &str| read_f64(&attr(suffix)).map(|v| (v / divisor) as f32);
+
+            let value = read_f64(&attr("input")).or_else(|| read_f64(&attr("average")))? / divisor;
+            let max = scaled("max").or_else(|| if kind == "power" { scaled("cap") } else { None });
+            let alarm = ["alarm", "min_alarm", "max_alarm", "crit_alarm", "lcrit_alarm", "fault"]
+                .iter()
+                .any(|suffix| read_f64(&attr(suffix)).is_some_and(|v| v != 0.0));
+
+            Some(SensorInfo {
+                label: read_string(&attr("label")).unwrap_or_else(|| id.clone()),
+                chip: chip.to_string(),
+                sensor_type: kind.to_string(),
+                value,
+                unit: unit.to_string(),
+                temp: value as f32,
+                min: scaled("min"),
+                max,
+                critical: scaled("crit"),
+                alarm,
+                hidden: false,
+                session_min: value,
+                session_max: value,
+                id,
+            })
+        })
+        .collect()
+}
+
+/// "temp12" -> ("temp", 12)
+fn split_id(id: &str) -> Option<(&str, u32)> {
+    let digits = id.find(|c: char| c.is_ascii_digit())?;
+    let (kind, index) = id.split_at(digits);
+    if !SENSOR_KINDS.iter().any(|(k, _, _)| *k == kind) {
+        return None;
+    }
+    Some((kind, index.parse().ok()?))
+}
+
+fn sort_key(kind: &str, id: &str) -> (usize, u32) {
+    let order = SENSOR_KINDS.iter().position(|(k, _, _)| *k == kind).unwrap_or(SENSOR_KINDS.len());
+    (order, split_id(id).map(|(_, n)| n).unwrap_or(0))
+}
+
+/// lm-sensors style chip name, e.g. `k10temp-pci-00c3` or `nct6775-isa-0290`.
+pub fn chip_name(dir: &Path) -> String {
+    let name = read_string(&dir.join("name"))
+        .unwrap_or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+    format!("{}-{}", name, bus_address(&dir.join("device")).unwrap_or_else(|| "virtual-0".to_string()))
+}
+
+// Walks up from the hwmon parent device until it finds a bus lm-sensors knows how to name
+fn bus_address(device: &Path) -> Option<String> {
+    let mut dev = fs::canonicalize(device).ok()?;
+    for _ in 0..3 {
+        let subsystem = fs::read_link(dev.join("subsystem")).ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+        let base = dev.file_name()?.to_string_lossy().to_string();
+        let address = match subsystem.as_deref() {
+            Some("pci") => {
+                // 0000:00:18.3 -> bus 00, devfn (0x18 << 3) | 3
+                let mut parts = base.rsplitn(3, ':');
+                let (dev_fn, bus) = (parts.next()?, parts.next()?);
+                let (slot, func) = dev_fn.split_once('.')?;
+                let devfn = (u32::from_str_radix(slot, 16).ok()? << 3) | func.parse::<u32>().ok()?;
+                Some(format!("pci-{:02x}{:02x}", u32::from_str_radix(bus, 16).ok()?, devfn))
+            }
+            Some("i2c") => {
+                let (adapter, addr) = base.split_once('-')?;
+                Some(format!("i2c-{}-{:x}", adapter, u32::from_str_radix(addr, 16).ok()?))
+            }
+            Some("platform") | Some("isa") => {
+                let n = base.rsplit_once('.').and_then(|(_, n)| n.parse::<u32>().ok()).unwrap_or(0);
+                Some(format!("isa-{:04x}", n))
+            }
+            Some("acpi") => {
+                let n = base.rsplit_once(':').and_then(|(_, n)| n.parse::<u32>().ok()).unwrap_or(0);
+                Some(format!("acpi-{}", n))
+            }
+            _ => None,
+        };
+        if address.is_some() {
+            return address;
+        }
+        dev = dev.parent()?.to_path_buf();
+    }
+    None
+}
+
+fn read_f64(path: &Path) -> Option<f64> {
+    read_string(path).and_then(|s| s.parse::<f64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_read_hwmon_chips() {
+        let root = std::env::temp_dir().join(format!("puls-hwmon-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let pci = root.join("devices/pci0000:00/0000:00:18.3");
+        fs::create_dir_all(&pci).unwrap();
+        fs::create_dir_all(root.join("bus/pci")).unwrap();
+        symlink(root.join("bus/pci"), pci.join("subsystem")).unwrap();
+
+        let k10 = root.join("class/hwmon0");
+        write_fixture(k10.join("name"), "k10temp\n");
+        symlink(&pci, k10.join("device")).unwrap();
+        write_fixture(k10.join("temp1_input"), "48250\n");
+        write_fixture(k10.join("temp1_label"), "Tctl\n");
+        write_fixture(k10.join("temp1_max"), "95000\n");
+        write_fixture(k10.join("temp1_crit"), "100000\n");
+
+        let sio = root.join("class/hwmon1");
+        write_fixture(sio.join("name"), "nct6775\n");
+        write_fixture(sio.join("in10_input"), "1840\n");
+        write_fixture(sio.join("in2_input"), "3312\n");
+        write_fixture(sio.join("in2_min"), "2960\n");
+        write_fixture(sio.join("in2_max"), "3632\n");
+        write_fixture(sio.join("in2_alarm"), "0\n");
+        write_fixture(sio.join("fan1_input"), "0\n");
+        write_fixture(sio.join("fan1_min"), "300\n");
+        write_fixture(sio.join("fan1_alarm"), "1\n");
+        write_fixture(sio.join("power1_average"), "12500000\n");
+        write_fixture(sio.join("power1_cap"), "65000000\n");
+
+        let sensors = read_sensors_from(&root.join("class"));
+        let _ = fs::remove_dir_all(&root);
+
+        let ids: Vec<(&str, &str)> = sensors.iter().map(|s| (s.chip.as_str(), s.id.as_str())).collect();
+        assert_eq!(ids, vec![
+            ("k10temp-pci-00c3", "temp1"),
+            ("nct6775-virtual-0", "fan1"),
+            ("nct6775-virtual-0", "in2"),
+            ("nct6775-virtual-0", "in10"),
+            ("nct6775-virtual-0", "power1"),
+        ]);
+
+        let tctl = &sensors[0];
+        assert_eq!(tctl.label, "Tctl");
+        assert_eq!(tctl.value, 48.25);
+        assert_eq!((tctl.max, tctl.critical), (Some(95.0), Some(100.0)));
+
+        assert!(sensors[1].alarm);
+        assert_eq!(sensors[1].min, Some(300.0));
+        assert_eq!(sensors[2].label, "in2");
+        assert!(!sensors[2].alarm);
+        assert_eq!((sensors[2].min, sensors[2].max), (Some(2.96), Some(3.632)));
+        assert_eq!(sensors[4].value, 12.5);
+        assert_eq!(sensors[4].max, Some(65.0));
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const EVENT_SOURCE_DIR: &str = "/sys/bus/event_source/devices";
+const POWERCAP_DIR: &str = "/sys/class/powercap";
+
+// i915 names its busy counters after the engine: rcs0-busy, vcs1-busy, ...
+const I915_ENGINES: &[(&str, &str)] = &[
+    ("rcs", "Render"),
+    ("bcs", "Copy"),
+    ("vcs", "Video"),
+    ("vecs", "VideoEnhance"),
+    ("ccs", "Compute"),
+];
+// xe takes the engine class as an event parameter (DRM_XE_ENGINE_CLASS_*)
+const XE_ENGINES: &[(&str, &str, u64)] = &[
+    ("rcs", "Render", 0),
+    ("bcs", "Copy", 1),
+    ("vcs", "Video", 2),
+    ("vecs", "VideoEnhance", 3),
+    ("ccs", "Compute", 4),
+];
+
+const DRM_IOCTL_I915_QUERY: libc::c_ulong = 0xC010_6479;
+const DRM_I915_QUERY_MEMORY_REGIONS: u64 = 4;
+const I915_MEMORY_CLASS_DEVICE: u16 = 1;
+const DRM_IOCTL_XE_DEVICE_QUERY: libc::c_ulong = 0xC028_6440;
+const DRM_XE_DEVICE_QUERY_MEM_REGIONS: u32 = 1;
+const DRM_XE_MEM_REGION_CLASS_VRAM: u16 = 1;
+const MEM_REGION_INFO_SIZE: usize = 88;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Driver {
+    I915,
+    Xe,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CounterKind {
+    // i915: nanoseconds the engine was busy
+    BusyNs,
+    // xe: engine active ticks over total ticks
+    ActiveTicks,
+    TotalTicks,
+    // i915 reports RC6 residency in ns, xe reports GT C6 residency in ms
+    Rc6Ns,
+    C6Ms,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct EventSpec {
+    engine: String,
+    kind: CounterKind,
+    config: u64,
+}
+
+struct Counter {
+    spec: EventSpec,
+    fd: OwnedFd,
+    last: Option<u64>,
+}
+
+/// Mirrors the leading `PERF_ATTR_SIZE_VER0` bytes of `struct perf_event_attr`.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+#[derive(Default, Debug)]
+pub struct IntelSample {
+    pub engines: Vec<(String, u32)>,
+    pub rc6_residency: Option<u32>,
+    pub power_usage: Option<u32>,
+    pub power_limit: Option<u32>,
+    pub lmem: Option<(u64, u64)>,
+    pub error: Option<String>,
+}
+
+enum EnergySource {
+    // µJ counter from the card's own hwmon (discrete cards)
+    Hwmon(PathBuf),
+    // RAPL uncore plane for integrated graphics; wraps at max_energy_range_uj
+    Rapl(PathBuf, u64),
+}
+
+/// Per-card telemetry state. PMU counters and energy readings are deltas, so
+/// the first sample after opening only has instantaneous values.
+pub struct IntelGpu {
+    driver: Driver,
+    counters: Vec<Counter>,
+    last_read: Option<Instant>,
+    pmu_error: Option<String>,
+    energy: Option<EnergySource>,
+    last_energy: Option<(Instant, u64)>,
+    power_limit: Option<u32>,
+    render_node: Option<File>,
+}
+
+impl IntelGpu {
+    pub fn open(card_path: &Path, device_path: &Path) -> Self {
+        let driver = match fs::read_link(device_path.join("driver")).ok().and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())) {
+            Some(d) if d == "xe" => Driver::Xe,
+            _ => Driver::I915,
+        };
+        let slot = fs::canonicalize(device_path).ok().and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+
+        let (counters, pmu_error) = match open_pmu(Path::new(EVENT_SOURCE_DIR), card_path, driver, slot.as_deref()) {
+            Ok(c) => (c, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+
+        let hwmon = fs::read_dir(device_path.join("hwmon")).ok()
+            .and_then(|mut d| d.next())
+            .and_then(|e| e.ok())
+            .map(|e| e.path());
+        let energy = hwmon.as_ref()
+            .map(|h| h.join("energy1_input"))
+            .filter(|p| p.exists())
+            .map(EnergySource::Hwmon)
+            .or_else(find_rapl_uncore);
+        let power_limit = hwmon
+            .and_then(|h| fs::read_to_string(h.join("power1_max")).ok())
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|&uw| uw > 0)
+            .map(|uw| (uw / 1000) as u32);
+
+        let render_node = fs::read_dir(device_path.join("drm")).ok()
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .find(|n| n.starts_with("renderD"))
+            .and_then(|n| File::open(Path::new("/dev/dri").join(n)).ok());
+
+        Self {
+            driver,
+            counters,
+            last_read: None,
+            pmu_error,
+            energy,
+            last_energy: None,
+            power_limit,
+            render_node,
+        }
+    }
+
+    pub fn sample(&mut self) -> IntelSample {
+        let now = Instant::now();
+        let mut sample = IntelSample {
+            power_limit: self.power_limit,
+            error: self.pmu_error.clone(),
+            ..Default::default()
+        };
+
+        let elapsed_ns = self.last_read.map(|t| now.duration_since(t).as_nanos() as f64);
+        self.last_read = Some(now);
+        let mut deltas: Vec<(&EventSpec, Option<u64>)> = Vec::new();
+        for counter in &mut self.counters {
+            let value = read_counter(&counter.fd);
+            let delta = match (value, counter.last) {
+                (Some(v), Some(l)) => Some(v.saturating_sub(l)),
+                _ => None,
+            };
+            counter.last = value;
+            deltas.push((&counter.spec, delta));
+        }
+
+        if let Some(elapsed) = elapsed_ns.filter(|&e| e > 0.0) {
+            let percent = |v: f64| (v * 100.0).round().clamp(0.0, 100.0) as u32;
+            for (spec, delta) in &deltas {
+                let Some(delta) = delta else { continue };
+                match spec.kind {
+                    CounterKind::BusyNs => sample.engines.push((spec.engine.clone(), percent(*delta as f64 / elapsed))),
+                    CounterKind::Rc6Ns => sample.rc6_residency = Some(percent(*delta as f64 / elapsed)),
+                    CounterKind::C6Ms => sample.rc6_residency = Some(percent(*delta as f64 * 1e6 / elapsed)),
+                    CounterKind::ActiveTicks => {
+                        let total = deltas.iter()
+                            .find(|(s, _)| s.kind == CounterKind::TotalTicks && s.engine == spec.engine)
+                            .and_then(|(_, d)| *d)
+                            .filter(|&t| t > 0);
+                        if let Some(total) = total {
+                            sample.engines.push((spec.engine.clone(), percent(*delta as f64 / total as f64)));
+                        }
+                    }
+                    CounterKind::TotalTicks => {}
+                }
+            }
+        }
+
+        if let Some(energy) = &self.energy {
+            let reading = match energy {
+                EnergySource::Hwmon(path) | EnergySource::Rapl(path, _) => fs::read_to_string(path).ok().and_then(|s| s.trim().parse::<u64>().ok()),
+            };
+            if let Some(uj) = reading {
+                if let Some((then, last)) = self.last_energy {
+                    let delta = match energy {
+                        EnergySource::Rapl(_, range) if uj < last => uj + range - last,
+                        _ => uj.saturating_sub(last),
+                    };
+                    let secs = now.duration_since(then).as_secs_f64();
+                    if secs > 0.0 {
+                        // µJ/s is µW; GpuInfo.power_usage is in mW
+                        sample.power_usage = Some((delta as f64 / secs / 1000.0) as u32);
+                    }
+                }
+                self.last_energy = Some((now, uj));
+            }
+        }
+
+        if let Some(node) = &self.render_node {
+            sample.lmem = match self.driver {
+                Driver::I915 => query_i915_lmem(node),
+                Driver::Xe => query_xe_vram(node),
+            };
+        }
+
+        sample
+    }
+}
+
+fn open_pmu(root: &Path, card_path: &Path, driver: Driver, slot: Option<&str>) -> Result<Vec<Counter>, String> {
+    let pmu_dir = find_pmu_dir(root, driver, slot).ok_or_else(|| "no i915/xe PMU registered".to_string())?;
+    let pmu_type: u32 = fs::read_to_string(pmu_dir.join("type"))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .ok_or_else(|| "unreadable PMU type".to_string())?;
+    // Uncore PMUs count system-wide and must be opened on the CPU they advertise
+    let cpu: i32 = fs::read_to_string(pmu_dir.join("cpumask"))
+        .ok()
+        .and_then(|s| s.trim().split([',', '-']).next().and_then(|c| c.parse().ok()))
+        .unwrap_or(0);
+
+    let specs = match driver {
+        Driver::I915 => i915_events(&pmu_dir),
+        Driver::Xe => xe_events(&pmu_dir, &card_path.join("device")),
+    };
+    if specs.is_empty() {
+        return Err("PMU exposes no engine counters".to_string());
+    }
+
+    let mut counters = Vec::new();
+    let mut last_err = None;
+    for spec in specs {
+        match perf_event_open(pmu_type, spec.config, cpu) {
+            Ok(fd) => counters.push(Counter { spec, fd, last: None }),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    match (counters.is_empty(), last_err) {
+        (true, Some(e)) if e.raw_os_error() == Some(libc::EACCES) || e.raw_os_error() == Some(libc::EPERM) => {
+            Err("engine busyness needs CAP_PERFMON or kernel.perf_event_paranoid <= 0".to_string())
+        }
+        (true, Some(e)) => Err(format!("perf_event_open: {}", e)),
+        _ => Ok(counters),
+    }
+}
+
+fn find_pmu_dir(root: &Path, driver: Driver, slot: Option<&str>) -> Option<PathBuf> {
+    let prefix = match driver {
+        Driver::I915 => "i915",
+        Driver::Xe => "xe",
+    };
+    // Discrete cards register i915_0000_03_00.0 (colons replaced); the integrated GPU is plain "i915"
+    if let Some(slot) = slot {
+        let named = root.join(format!("{}_{}", prefix, slot.replace(':', "_")));
+        if named.exists() {
+            return Some(named);
+        }
+    }
+    let plain = root.join(prefix);
+    plain.exists().then_some(plain)
+}
+
+fn i915_events(pmu_dir: &Path) -> Vec<EventSpec> {
+    let formats = read_formats(pmu_dir);
+    let mut specs = Vec::new();
+    for entry in fs::read_dir(pmu_dir.join("events")).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(spec) = fs::read_to_string(entry.path()) else { continue };
+        let Some(config) = encode_event(spec.trim(), &formats, &[]) else { continue };
+
+        if let Some(engine) = name.strip_suffix("-busy") {
+            let Some(label) = engine_label(engine) else { continue };
+            specs.push(EventSpec { engine: label, kind: CounterKind::BusyNs, config });
+        } else if name == "rc6-residency" || name == "rc6-residency-gt0" {
+            specs.push(EventSpec { engine: "rc6".to_string(), kind: CounterKind::Rc6Ns, config });
+        }
+    }
+    specs.sort_by(|a, b| a.engine.cmp(&b.engine));
+    // Multi-GT kernels expose both names for GT0; keep one
+    specs.dedup_by(|a, b| a.kind == CounterKind::Rc6Ns && b.kind == CounterKind::Rc6Ns);
+    specs
+}
+
+fn xe_events(pmu_dir: &Path, device_path: &Path) -> Vec<EventSpec> {
+    let formats = read_formats(pmu_dir);
+    let event = |name: &str| fs::read_to_string(pmu_dir.join("events").join(name)).ok().map(|s| s.trim().to_string());
+    let mut specs = Vec::new();
+
+    // Engine classes present on GT0 show up as directories under tile0/gt0/engines
+    let engines_dir = device_path.join("tile0/gt0/engines");
+    let (active, total) = (event("engine-active-ticks"), event("engine-total-ticks"));
+    if let (Some(active), Some(total)) = (active, total) {
+        for (dir, label, class) in XE_ENGINES {
+            if !engines_dir.join(dir).exists() {
+                continue;
+            }
+            let params = [("gt", 0), ("engine_class", *class), ("engine_instance", 0)];
+            if let (Some(a), Some(t)) = (encode_event(&active, &formats, &params), encode_event(&total, &formats, &params)) {
+                specs.push(EventSpec { engine: label.to_string(), kind: CounterKind::ActiveTicks, config: a });
+                specs.push(EventSpec { engine: label.to_string(), kind: CounterKind::TotalTicks, config: t });
+            }
+        }
+    }
+    if let Some(config) = event("gt-c6-residency").and_then(|e| encode_event(&e, &formats, &[("gt", 0)])) {
+        specs.push(EventSpec { engine: "rc6".to_string(), kind: CounterKind::C6Ms, config });
+    }
+    specs
+}
+
+fn engine_label(engine: &str) -> Option<String> {
+    let class = engine.trim_end_matches(|c: char| c.is_ascii_digit());
+    let instance = &engine[class.len()..];
+    I915_ENGINES.iter()
+        .find(|(prefix, _)| *prefix == class)
+        .map(|(_, label)| format!("{}{}", label, if instance == "0" { String::new() } else { format!(" {}", instance) }))
+}
+
+/// Reads `format/<field>` entries such as `config:12-19` into bit ranges.
+fn read_formats(pmu_dir: &Path) -> HashMap<String, (u32, u32)> {
+    let mut formats = HashMap::new();
+    for entry in fs::read_dir(pmu_dir.join("format")).into_iter().flatten().flatten() {
+        let Ok(spec) = fs::read_to_string(entry.path()) else { continue };
+        let Some(bits) = spec.trim().strip_prefix("config:") else { continue };
+        let mut range = bits.split('-').filter_map(|b| b.parse::<u32>().ok());
+        if let Some(lo) = range.next() {
+            let hi = range.next().unwrap_or(lo);
+            formats.insert(entry.file_name().to_string_lossy().to_string(), (lo, hi));
+        }
+    }
+    formats
+}
+
+/// Builds a perf `config` value from an event string (`config=0x100000` or
+/// `event=0x02,engine_class=?`) plus caller-supplied parameter values.
+fn encode_event(spec: &str, formats: &HashMap<String, (u32, u32)>, params: &[(&str, u64)]) -> Option<u64> {
+    let mut config: u64 = 0;
+    let mut place = |field: &str, value: u64| -> Option<()> {
+        if field == "config" {
+            config |= value;
+            return Some(());
+        }
+        let (lo, hi) = *formats.get(field)?;
+        let width = hi - lo + 1;
+        let mask = if width >= 64 { u64::MAX } else { (1u64 << width) - 1 };
+        config |= (value & mask) << lo;
+        Some(())
+    };
+
+    for term in spec.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (field, value) = term.split_once('=').unwrap_or((term, "1"));
+        let value = match value.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => value.parse::<u64>().ok()?,
+        };
+        place(field, value)?;
+    }
+    for (field, value) in params {
+        if formats.contains_key(*field) {
+            place(field, *value)?;
+        }
+    }
+    Some(config)
+}
+
+fn perf_event_open(pmu_type: u32, config: u64, cpu: i32) -> io::Result<OwnedFd> {
+    let attr = PerfEventAttr {
+        type_: pmu_type,
+        size: std::mem::size_of::<PerfEventAttr>() as u32,
+        config,
+        ..Default::default()
+    };
+    let fd = unsafe {
+        libc::syscall(libc::SYS_perf_event_open, &attr as *const PerfEventAttr, -1 as libc::pid_t, cpu, -1 as libc::c_int, PERF_FLAG_FD_CLOEXEC)
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as i32) })
+}
+
+fn read_counter(fd: &OwnedFd) -> Option<u64> {
+    let mut value = [0u8; 8];
+    let n = unsafe { libc::read(fd.as_raw_fd(), value.as_mut_ptr() as *mut libc::c_void, value.len()) };
+    (n == 8).then(|| u64::from_ne_bytes(value))
+}
+
+fn find_rapl_uncore() -> Option<EnergySource> {
+    // intel-rapl:0:N with name "uncore" is the graphics power plane (PP1)
+    fs::read_dir(POWERCAP_DIR).ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        let name = fs::read_to_string(path.join("name")).ok()?;
+        if name.trim() != "uncore" {
+            return None;
+        }
+        let energy = path.join("energy_uj");
+        fs::read_to_string(&energy).ok()?;
+        let range = fs::read_to_string(path.join("max_energy_range_uj")).ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(u64::MAX);
+        Some(EnergySource::Rapl(energy, range))
+    })
+}
+
+/// Two-pass DRM query: the first call sizes the buffer, the second fills it.
+fn drm_query(node: &File, request: libc::c_ulong, arg: &mut [u64]) -> Option<()> {
+    let ret = unsafe { libc::ioctl(node.as_raw_fd(), request, arg.as_mut_ptr()) };
+    (ret == 0).then_some(())
+}
+
+fn region_sizes(data: &[u8], count: usize, header: usize, class_of: impl Fn(&[u8]) -> u16, device_class: u16, used_of: impl Fn(&[u8]) -> Option<(u64, u64)>) -> Option<(u64, u64)> {
+    let mut used = 0;
+    let mut total = 0;
+    let mut found = false;
+    for i in 0..count {
+        let off = header + i * MEM_REGION_INFO_SIZE;
+        let region = data.get(off..off + MEM_REGION_INFO_SIZE)?;
+        if class_of(region) == device_class {
+            let (u, t) = used_of(region)?;
+            used += u;
+            total += t;
+            found = true;
+        }
+    }
+    found.then_some((used, total))
+}
+
+fn read_u64(buf: &[u8], off: usize) -> u64 {
+    u64::from_ne_bytes(buf[off..off + 8].try_into().unwrap_or([0; 8]))
+}
+
+/// Local memory (lmem) on discrete i915 cards. The unallocated size is only
+/// accurate with CAP_PERFMON; otherwise it equals the probed size.
+fn query_i915_lmem(node: &File) -> Option<(u64, u64)> {
+    // struct drm_i915_query_item { u64 query_id; s32 length; u32 flags; u64 data_ptr; }
+    let mut item = [DRM_I915_QUERY_MEMORY_REGIONS, 0, 0];
+    // struct drm_i915_query { u32 num_items; u32 flags; u64 items_ptr; }
+    let mut query = [1u64, item.as_mut_ptr() as u64];
+    drm_query(node, DRM_IOCTL_I915_QUERY, &mut query)?;
+    let length = (item[1] & 0xffff_ffff) as i32;
+    if length <= 0 {
+        return None;
+    }
+    let mut data = vec![0u64; (length as usize).div_ceil(8)];
+    item[1] = length as u32 as u64;
+    item[2] = data.as_mut_ptr() as u64;
+    query[1] = item.as_mut_ptr() as u64;
+    drm_query(node, DRM_IOCTL_I915_QUERY, &mut query)?;
+    let bytes: Vec<u8> = data.iter().flat_map(|w| w.to_ne_bytes()).collect();
+
+    let count = u32::from_ne_bytes(bytes.get(0..4)?.try_into().ok()?) as usize;
+    // region { u16 class; u16 instance; } rsvd0; probed_size @8; unallocated_size @16
+    region_sizes(&bytes, count, 16,
+        |r| u16::from_ne_bytes([r[0], r[1]]),
+        I915_MEMORY_CLASS_DEVICE,
+        |r| {
+            let probed = read_u64(r, 8);
+            let free = read_u64(r, 16);
+            Some((probed.saturating_sub(free), probed))
+        })
+}
+
+/// VRAM regions on xe. `used` reads as 0 without CAP_PERFMON.
+fn query_xe_vram(node: &File) -> Option<(u64, u64)> {
+    // struct drm_xe_device_query { u64 extensions; u32 query; u32 size; u64 data; u64 reserved[2]; }
+    let mut query = [0u64, DRM_XE_DEVICE_QUERY_MEM_REGIONS as u64, 0, 0, 0];
+    drm_query(node, DRM_IOCTL_XE_DEVICE_QUERY, &mut query)?;
+    let size = (query[1] >> 32) as usize;
+    if size == 0 {
+        return None;
+    }
+    let mut data = vec![0u64; size.div_ceil(8)];
+    query[2] = data.as_mut_ptr() as u64;
+    drm_query(node, DRM_IOCTL_XE_DEVICE_QUERY, &mut query)?;
+    let bytes: Vec<u8> = data.iter().flat_map(|w| w.to_ne_bytes()).collect();
+
+    let count = u32::from_ne_bytes(bytes.get(0..4)?.try_into().ok()?) as usize;
+    // u16 mem_class; u16 instance; u32 min_page_size; total_size @8; used @16
+    region_sizes(&bytes, count, 8,
+        |r| u16::from_ne_bytes([r[0], r[1]]),
+        DRM_XE_MEM_REGION_CLASS_VRAM,
+        |r| Some((read_u64(r, 16), read_u64(r, 8))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+
+    #[test]
+    fn test_pmu_event_discovery() {
+        let root = std::env::temp_dir().join(format!("puls-intelpmu-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let i915 = root.join("i915_0000_03_00.0");
+        write_fixture(i915.join("format/i915_eventid"), "config:0-20\n");
+        write_fixture(i915.join("events/rcs0-busy"), "config=0x0000000000000000\n");
+        write_fixture(i915.join("events/vcs1-busy"), "config=0x0000000000110000\n");
+        write_fixture(i915.join("events/rc6-residency"), "config=0x100002\n");
+        write_fixture(i915.join("events/rc6-residency.unit"), "ns\n");
+        write_fixture(i915.join("events/actual-frequency"), "config=0x100000\n");
+
+        assert_eq!(find_pmu_dir(&root, Driver::I915, Some("0000:03:00.0")), Some(i915.clone()));
+        assert_eq!(find_pmu_dir(&root, Driver::Xe, Some("0000:03:00.0")), None);
+
+        let events = i915_events(&i915);
+        let summary: Vec<(&str, CounterKind, u64)> = events.iter().map(|e| (e.engine.as_str(), e.kind, e.config)).collect();
+        assert_eq!(summary, vec![
+            ("Render", CounterKind::BusyNs, 0),
+            ("Video 1", CounterKind::BusyNs, 0x110000),
+            ("rc6", CounterKind::Rc6Ns, 0x100002),
+        ]);
+
+        let xe = root.join("xe_0000_03_00.0");
+        let device = root.join("device");
+        fs::create_dir_all(device.join("tile0/gt0/engines/rcs")).unwrap();
+        fs::create_dir_all(device.join("tile0/gt0/engines/vcs")).unwrap();
+        write_fixture(xe.join("format/event"), "config:0-11\n");
+        write_fixture(xe.join("format/engine_instance"), "config:12-19\n");
+        write_fixture(xe.join("format/engine_class"), "config:20-27\n");
+        write_fixture(xe.join("format/gt"), "config:60-63\n");
+        write_fixture(xe.join("events/engine-active-ticks"), "event=0x02\n");
+        write_fixture(xe.join("events/engine-total-ticks"), "event=0x03\n");
+        write_fixture(xe.join("events/gt-c6-residency"), "event=0x01\n");
+
+        let events = xe_events(&xe, &device);
+        let summary: Vec<(&str, CounterKind, u64)> = events.iter().map(|e| (e.engine.as_str(), e.kind, e.config)).collect();
+        assert_eq!(summary, vec![
+            ("Render", CounterKind::ActiveTicks, 0x02),
+            ("Render", CounterKind::TotalTicks, 0x03),
+            ("Video", CounterKind::ActiveTicks, 0x02 | (2 << 20)),
+            ("Video", CounterKind::TotalTicks, 0x03 | (2 << 20)),
+            ("rc6", CounterKind::C6Ms, 0x01),
+        ]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
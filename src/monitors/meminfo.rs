@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::Path;
+
+use crate::monitors::read_u64;
+use crate::types::{MemInfo, MemoryBreakdown, SwapDevice, ZramDevice, ZswapInfo};
+
+pub fn read_memory_breakdown() -> MemoryBreakdown {
+    let meminfo = fs::read_to_string("/proc/meminfo")
+        .map(|c| parse_meminfo(&c))
+        .unwrap_or_default();
+    let swaps = fs::read_to_string("/proc/swaps")
+        .map(|c| parse_swaps(&c))
+        .unwrap_or_default();
+    let zswap = read_zswap(&meminfo);
+
+    MemoryBreakdown {
+        meminfo,
+        zram: read_zram_devices(),
+        zswap,
+        swaps,
+    }
+}
+
+/// Values in /proc/meminfo are in kB except the HugePages_* page counts.
+pub fn parse_meminfo(content: &str) -> MemInfo {
+    let mut info = MemInfo::default();
+
+    for line in content.lines() {
+        let (key, rest) = match line.split_once(':') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        let mut parts = rest.split_whitespace();
+        let value = parts.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        let bytes = if parts.next() == Some("kB") { value * 1024 } else { value };
+
+        match key {
+            "MemTotal" => info.total = bytes,
+            "MemFree" => info.free = bytes,
+            "MemAvailable" => info.available = bytes,
+            "Buffers" => info.buffers = bytes,
+            "Cached" => info.cached = bytes,
+            "SwapCached" => info.swap_cached = bytes,
+            "Shmem" => info.shmem = bytes,
+            "SReclaimable" => info.slab_reclaimable = bytes,
+            "SUnreclaim" => info.slab_unreclaimable = bytes,
+            "AnonPages" => info.anon = bytes,
+            "Mapped" => info.mapped = bytes,
+            "Dirty" => info.dirty = bytes,
+            "Writeback" => info.writeback = bytes,
+            "KernelStack" => info.kernel_stack = bytes,
+            "PageTables" => info.page_tables = bytes,
+            "Committed_AS" => info.committed = bytes,
+            "CommitLimit" => info.commit_limit = bytes,
+            "HugePages_Total" => info.hugepages_total = bytes,
+            "HugePages_Free" => info.hugepages_free = bytes,
+            "Hugepagesize" => info.hugepage_size = bytes,
+            "SwapTotal" => info.swap_total = bytes,
+            "SwapFree" => info.swap_free = bytes,
+            "Zswap" => info.zswap_pool = Some(bytes),
+            "Zswapped" => info.zswap_stored = Some(bytes),
+            _ => {}
+        }
+    }
+
+    info
+}
+
+/// /proc/swaps sizes are in KiB.
+pub fn parse_swaps(content: &str) -> Vec<SwapDevice> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 5 {
+                return None;
+            }
+            Some(SwapDevice {
+                name: parts[0].replace("\\040", " "),
+                swap_type: parts[1].to_string(),
+                size: parts[2].parse::<u64>().unwrap_or(0) * 1024,
+                used: parts[3].parse::<u64>().unwrap_or(0) * 1024,
+                priority: parts[4].parse::<i32>().unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// mm_stat columns: orig_data_size compr_data_size mem_used_total mem_limit ...
+pub fn parse_mm_stat(content: &str) -> Option<(u64, u64, u64)> {
+    let parts: Vec<u64> = content
+        .split_whitespace()
+        .filter_map(|v| v.parse::<u64>().ok())
+        .collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    Some((parts[0], parts[1], parts[2]))
+}
+
+fn read_zram_devices() -> Vec<ZramDevice> {
+    let mut devices = Vec::new();
+    let entries = match fs::read_dir("/sys/block") {
+        Ok(e) => e,
+        Err(_) => return devices,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with("zram") {
+            continue;
+        }
+        let path = entry.path();
+        let disk_size = read_u64(&path.join("disksize")).unwrap_or(0);
+        if disk_size == 0 {
+            continue;
+        }
+        let (orig_data_size, compr_data_size, mem_used_total) = fs::read_to_string(path.join("mm_stat"))
+            .ok()
+            .and_then(|c| parse_mm_stat(&c))
+            .unwrap_or((0, 0, 0));
+        let algorithm = fs::read_to_string(path.join("comp_algorithm"))
+            .ok()
+            .and_then(|c| {
+                c.split_whitespace()
+                    .find(|a| a.starts_with('['))
+                    .map(|a| a.trim_matches(|ch| ch == '[' || ch == ']').to_string())
+            })
+            .unwrap_or_default();
+
+        devices.push(ZramDevice {
+            name,
+            disk_size,
+            orig_data_size,
+            compr_data_size,
+            mem_used_total,
+            algorithm,
+        });
+    }
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn read_zswap(meminfo: &MemInfo) -> Option<ZswapInfo> {
+    let params = Path::new("/sys/module/zswap/parameters");
+    if !params.exists() {
+        return None;
+    }
+    let read = |name: &str| fs::read_to_string(params.join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+
+    Some(ZswapInfo {
+        enabled: matches!(read("enabled").as_str(), "Y" | "1"),
+        compressor: read("compressor"),
+        max_pool_percent: read("max_pool_percent").parse().unwrap_or(0),
+        pool_size: meminfo.zswap_pool.unwrap_or(0),
+        stored: meminfo.zswap_stored.unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let content = "MemTotal:        6158152 kB\n\
+                       MemFree:         3747580 kB\n\
+                       Buffers:           73144 kB\n\
+                       SUnreclaim:        20820 kB\n\
+                       HugePages_Total:       4\n\
+                       Hugepagesize:       2048 kB\n\
+                       Zswapped:              0 kB\n";
+        let info = parse_meminfo(content);
+        assert_eq!(info.total, 6158152 * 1024);
+        assert_eq!(info.buffers, 73144 * 1024);
+        assert_eq!(info.slab_unreclaimable, 20820 * 1024);
+        assert_eq!(info.hugepages_total, 4);
+        assert_eq!(info.hugepage_size, 2 * 1024 * 1024);
+        assert_eq!(info.zswap_stored, Some(0));
+        assert_eq!(info.zswap_pool, None);
+    }
+
+    #[test]
+    fn test_parse_swaps() {
+        let content = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n\
+                       /dev/zram0                              partition\t8388604\t\t1024\t\t100\n\
+                       /swap\\040file                           file\t\t2097148\t\t0\t\t-2\n";
+        let swaps = parse_swaps(content);
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(swaps[0].size, 8388604 * 1024);
+        assert_eq!(swaps[0].used, 1024 * 1024);
+        assert_eq!(swaps[0].priority, 100);
+        assert_eq!(swaps[1].name, "/swap file");
+        assert_eq!(swaps[1].priority, -2);
+    }
+
+    #[test]
+    fn test_parse_mm_stat() {
+        let stat = "  4096000   1024000   1200000        0  1300000     12     0     0     0\n";
+        assert_eq!(parse_mm_stat(stat), Some((4096000, 1024000, 1200000)));
+        assert_eq!(parse_mm_stat(""), None);
+    }
+}
@@ -2,34 +2,73 @@
 
 pub mod system_monitor;
 pub mod gpu_monitor;
+pub mod nvml;
+pub mod intel_gpu;
+pub mod amd_gpu;
 pub mod container_monitor;
+pub mod cpu_topology;
+pub mod pressure;
+pub mod meminfo;
+pub mod smbios;
+pub mod hwmon;
+pub mod sensors_conf;
+pub mod oom;
+pub mod diskstats;
+pub mod smart;
+pub mod blockdev;
+pub mod dirsize;
+pub mod storage_health;
+pub mod quota;
+pub mod netif;
+pub mod wifi;
+pub mod traffic;
+pub mod netdiag;
 
 pub use system_monitor::SystemMonitor;
 pub use gpu_monitor::GpuMonitor;
 pub use container_monitor::ContainerMonitor;
 
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::time::{Duration, Instant};
 
-use crate::types::{DynamicData, AppConfig, GlobalUsage};
+use crate::types::{DynamicData, AppConfig, GlobalUsage, CpuTopology, DimmInfo, OomKill, OomWatch, QuotaEntry};
 use crate::utils::update_history;
 
+// repquota walks the quota files of every filesystem, so it runs once a minute
+const QUOTA_REFRESH: Duration = Duration::from_secs(60);
+
 pub struct DataCollector {
     system_monitor: SystemMonitor,
     gpu_monitor: GpuMonitor,
     container_monitor: ContainerMonitor,
     config: AppConfig,
     last_update: Instant,
+    leak_detector: oom::LeakDetector,
+    oom_kill_count: Option<u64>,
+    oom_kills: Vec<OomKill>,
+    quotas: Result<Vec<QuotaEntry>, String>,
+    quotas_checked: Option<Instant>,
+    wifi_signal_history: HashMap<String, VecDeque<f32>>,
+    traffic: traffic::TrafficAccounting,
 }
 
 impl DataCollector {
     pub fn new(config: AppConfig) -> Self {
         Self {
-            system_monitor: SystemMonitor::new(),
+            system_monitor: SystemMonitor::new(config.cache_dmi),
             gpu_monitor: GpuMonitor::new(),
             container_monitor: ContainerMonitor::new(),
+            leak_detector: oom::LeakDetector::new(Duration::from_secs(config.leak_window_secs)),
             config,
             last_update: Instant::now(),
+            oom_kill_count: None,
+            oom_kills: Vec::new(),
+            quotas: Ok(Vec::new()),
+            quotas_checked: None,
+            wifi_signal_history: HashMap::new(),
+            traffic: traffic::TrafficAccounting::load(),
         }
     }
     
@@ -61,7 +100,15 @@ impl DataCollector {
         
         let cores = self.system_monitor.get_cores();
         
-        let disks = self.system_monitor.get_disks();
+        let block_io = self.system_monitor.update_block_io();
+        let drive_health = self.system_monitor.refresh_drive_health();
+        let disks = self.system_monitor.get_disks(&block_io, &drive_health);
+        let block_devices = blockdev::read_block_tree();
+        let storage_health = storage_health::read_storage_health();
+        if self.quotas_checked.is_none_or(|t| now.duration_since(t) >= QUOTA_REFRESH) {
+            self.quotas = quota::read_quotas();
+            self.quotas_checked = Some(now);
+        }
         
         let networks = if self.config.enable_network_monitoring {
             self.system_monitor.get_networks()
@@ -69,11 +116,14 @@ impl DataCollector {
             Vec::new()
         };
         
+        let wifi = self.collect_wifi(&networks);
+        let traffic = self.traffic.update(&networks, &self.config.data_caps);
+
         let (total_net_down, total_net_up) = self.system_monitor
             .calculate_total_network_io(&networks);
         
-        let (total_disk_read, total_disk_write) = self.system_monitor
-            .calculate_total_disk_io(&processes);
+        let (total_disk_read, total_disk_write, total_disk_read_ops, total_disk_write_ops) = self.system_monitor
+            .calculate_total_disk_io(&block_io);
         
         let (containers, docker_error) = if self.config.enable_docker {
             if self.container_monitor.is_available() {
@@ -122,6 +172,19 @@ impl DataCollector {
             total_disk_write,
             gpu_util,
         );
+        global_usage.disk_read_ops = total_disk_read_ops;
+        global_usage.disk_write_ops = total_disk_write_ops;
+        global_usage.disk_util = block_io.iter()
+            .filter(|d| d.physical)
+            .map(|d| d.util_pct)
+            .fold(0.0, f64::max);
+        global_usage.pressure = pressure::read_system_pressure();
+        
+        let memory = meminfo::read_memory_breakdown();
+        let oom = self.collect_oom_watch();
+        global_usage.mem_cached = memory.meminfo.buffers
+            + memory.meminfo.cached
+            + memory.meminfo.slab_reclaimable;
         
         update_history(&mut prev_global_usage.cpu_history, global_usage.cpu, self.config.history_length);
         update_history(&mut prev_global_usage.mem_history, 
@@ -132,6 +195,13 @@ impl DataCollector {
         update_history(&mut prev_global_usage.disk_read_history, total_disk_read, self.config.history_length);
         update_history(&mut prev_global_usage.disk_write_history, total_disk_write, self.config.history_length);
         
+        if let Some(ref psi) = global_usage.pressure {
+            let some_avg10 = |r: Option<crate::types::PsiResource>| r.map(|r| r.some.avg10).unwrap_or(0.0);
+            update_history(&mut prev_global_usage.cpu_pressure_history, some_avg10(psi.cpu), self.config.history_length);
+            update_history(&mut prev_global_usage.mem_pressure_history, some_avg10(psi.memory), self.config.history_length);
+            update_history(&mut prev_global_usage.io_pressure_history, some_avg10(psi.io), self.config.history_length);
+        }
+        
         if let Some(gpu_util_val) = gpu_util {
             update_history(&mut prev_global_usage.gpu_history, gpu_util_val, self.config.history_length);
         }
@@ -143,6 +213,9 @@ impl DataCollector {
         global_usage.disk_read_history = prev_global_usage.disk_read_history;
         global_usage.disk_write_history = prev_global_usage.disk_write_history;
         global_usage.gpu_history = prev_global_usage.gpu_history;
+        global_usage.cpu_pressure_history = prev_global_usage.cpu_pressure_history;
+        global_usage.mem_pressure_history = prev_global_usage.mem_pressure_history;
+        global_usage.io_pressure_history = prev_global_usage.io_pressure_history;
         
         let collection_end = Instant::now();
         let collection_duration = collection_end.duration_since(collection_start);
@@ -162,12 +235,57 @@ impl DataCollector {
             global_usage,
             temperatures,
             sensors,
+            memory,
+            oom,
+            block_io,
+            drive_health,
+            block_devices,
+            storage_health,
+            quotas: self.quotas.clone(),
+            wifi,
+            traffic,
             last_update: std::time::Instant::now(),
             docker_error,
+            sensor_config_error: self.system_monitor.sensor_config_error(),
             generation: 0,
         }
     }
     
+    fn collect_wifi(&mut self, networks: &[crate::types::DetailedNetInfo]) -> Vec<crate::types::WifiInfo> {
+        let interfaces: Vec<String> = networks.iter()
+            .filter(|n| n.interface_type == "wifi")
+            .map(|n| n.name.clone())
+            .collect();
+        self.wifi_signal_history.retain(|name, _| interfaces.contains(name));
+
+        let mut wifi = wifi::read_wifi(&interfaces);
+        for info in &mut wifi {
+            let history = self.wifi_signal_history.entry(info.interface.clone()).or_default();
+            let signal = if info.connected { info.signal_dbm.map(wifi::signal_percent).unwrap_or(0.0) } else { 0.0 };
+            update_history(history, signal, self.config.history_length);
+            info.signal_history = history.clone();
+        }
+        wifi
+    }
+
+    // The journal is only queried when the kernel's oom_kill counter moves
+    fn collect_oom_watch(&mut self) -> OomWatch {
+        let kill_count = oom::read_oom_kill_count();
+        if kill_count != self.oom_kill_count {
+            self.oom_kills = if kill_count.unwrap_or(0) > 0 { oom::read_oom_kills() } else { Vec::new() };
+            self.oom_kill_count = kill_count;
+        }
+
+        let samples = self.system_monitor.process_memory_samples();
+        let leak_suspects = self.leak_detector.update(std::time::Instant::now(), &samples);
+
+        OomWatch {
+            kill_count,
+            kills: self.oom_kills.clone(),
+            leak_suspects,
+        }
+    }
+    
     pub fn get_system_info(&self) -> Vec<(String, String)> {
         let mut info = self.system_monitor.get_system_info();
         
@@ -193,6 +311,14 @@ impl DataCollector {
         info
     }
     
+    pub fn get_cpu_topology(&self) -> CpuTopology {
+        self.system_monitor.get_cpu_topology()
+    }
+
+    pub fn get_dimms(&self) -> Vec<DimmInfo> {
+        self.system_monitor.get_dimms()
+    }
+    
     pub async fn health_check(&self) -> Vec<(String, bool)> {
         let mut health = Vec::new();
         
@@ -224,4 +350,27 @@ impl DataCollector {
     }
 }
 
-pub type SharedDataCollector = Arc<tokio::sync::Mutex<DataCollector>>;
\ No newline at end of file
+pub type SharedDataCollector = Arc<tokio::sync::Mutex<DataCollector>>;
+
+/// Entry names in a sysfs directory; empty when it doesn't exist.
+pub(crate) fn list_dir(path: &Path) -> Vec<String> {
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Trimmed contents of a sysfs attribute, `None` when missing or blank.
+pub(crate) fn read_string(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+pub(crate) fn read_u64(path: &Path) -> Option<u64> {
+    read_string(path).and_then(|s| s.parse().ok())
+}
+
+/// Writes a fake sysfs/procfs file for tests, creating its parent directories.
+#[cfg(test)]
+pub fn write_fixture(path: std::path::PathBuf, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
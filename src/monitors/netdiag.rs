@@ -0,0 +1,831 @@
+use std::io;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::types::{DnsLookup, PingStats, TcpCheck, TraceHop};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+const TCP_TIMEOUT: Duration = Duration::from_secs(3);
+// Same defaults as traceroute(8)
+const TRACE_BASE_PORT: u16 = 33434;
+const TRACE_PROBES: usize = 3;
+const PING_PAYLOAD: &[u8] = b"puls-g3 ping....................";
+// Rolling window for the loss chart
+const LOSS_WINDOW: usize = 10;
+
+static NEXT_PING_ID: AtomicU16 = AtomicU16::new(0);
+
+async fn resolve(host: &str) -> Result<IpAddr, String> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(ip);
+    }
+    tokio::net::lookup_host((host, 0))
+        .await
+        .map_err(|e| format!("Cannot resolve {}: {}", host, e))?
+        .next()
+        .map(|a| a.ip())
+        .ok_or_else(|| format!("{} has no addresses", host))
+}
+
+fn to_raw(addr: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let len = match addr {
+        SocketAddr::V4(a) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: a.port().to_be(),
+                sin_addr: libc::in_addr { s_addr: u32::from_ne_bytes(a.ip().octets()) },
+                sin_zero: [0; 8],
+            };
+            unsafe { std::ptr::write(&mut storage as *mut _ as *mut libc::sockaddr_in, sin) };
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(a) => {
+            let sin6 = libc::sockaddr_in6 {
+                sin6_family: libc::AF_INET6 as libc::sa_family_t,
+                sin6_port: a.port().to_be(),
+                sin6_flowinfo: 0,
+                sin6_addr: libc::in6_addr { s6_addr: a.ip().octets() },
+                sin6_scope_id: a.scope_id(),
+            };
+            unsafe { std::ptr::write(&mut storage as *mut _ as *mut libc::sockaddr_in6, sin6) };
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    (storage, len as libc::socklen_t)
+}
+
+/// # Safety
+/// `sa` must point to a sockaddr at least as large as its family requires.
+unsafe fn from_raw(sa: *const libc::sockaddr) -> Option<SocketAddr> {
+    match (*sa).sa_family as libc::c_int {
+        libc::AF_INET => {
+            let sin = std::ptr::read_unaligned(sa as *const libc::sockaddr_in);
+            let ip = Ipv4Addr::from(sin.sin_addr.s_addr.to_ne_bytes());
+            Some(SocketAddr::new(IpAddr::V4(ip), u16::from_be(sin.sin_port)))
+        }
+        libc::AF_INET6 => {
+            let sin6 = std::ptr::read_unaligned(sa as *const libc::sockaddr_in6);
+            let ip = Ipv6Addr::from(sin6.sin6_addr.s6_addr);
+            Some(SocketAddr::new(IpAddr::V6(ip), u16::from_be(sin6.sin6_port)))
+        }
+        _ => None,
+    }
+}
+
+/// Waits up to `timeout` and returns the poll revents (POLLERR is always reported).
+fn wait_for(fd: RawFd, timeout: Duration) -> i16 {
+    let mut pfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+    let ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    if unsafe { libc::poll(&mut pfd, 1, ms) } <= 0 {
+        return 0;
+    }
+    pfd.revents
+}
+
+fn setsockopt_int(fd: RawFd, level: libc::c_int, name: libc::c_int, value: libc::c_int) -> io::Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(fd, level, name, &value as *const _ as *const libc::c_void, mem::size_of::<libc::c_int>() as libc::socklen_t)
+    };
+    if ret < 0 { Err(io::Error::last_os_error()) } else { Ok(()) }
+}
+
+
+fn icmp_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// ICMP/ICMPv6 echo request. The kernel fills in the ICMPv6 checksum itself
+/// since it covers the IPv6 pseudo-header.
+pub fn build_echo_request(v6: bool, id: u16, seq: u16, payload: &[u8]) -> Vec<u8> {
+    let mut pkt = vec![if v6 { 128 } else { 8 }, 0, 0, 0];
+    pkt.extend_from_slice(&id.to_be_bytes());
+    pkt.extend_from_slice(&seq.to_be_bytes());
+    pkt.extend_from_slice(payload);
+    if !v6 {
+        let sum = icmp_checksum(&pkt);
+        pkt[2..4].copy_from_slice(&sum.to_be_bytes());
+    }
+    pkt
+}
+
+/// (id, seq) of an echo reply. Raw IPv4 sockets still deliver the IP header.
+pub fn parse_echo_reply(buf: &[u8], v6: bool, with_ip_header: bool) -> Option<(u16, u16)> {
+    let icmp = if with_ip_header {
+        let ihl = (*buf.first()? & 0x0f) as usize * 4;
+        buf.get(ihl..)?
+    } else {
+        buf
+    };
+    if icmp.len() < 8 || icmp[0] != if v6 { 129 } else { 0 } {
+        return None;
+    }
+    Some((u16::from_be_bytes([icmp[4], icmp[5]]), u16::from_be_bytes([icmp[6], icmp[7]])))
+}
+
+struct IcmpSocket {
+    fd: OwnedFd,
+    v6: bool,
+    // Datagram "ping sockets" need no privileges (net.ipv4.ping_group_range) but the
+    // kernel rewrites the echo id; raw sockets need CAP_NET_RAW and see every reply
+    raw: bool,
+    id: u16,
+}
+
+impl IcmpSocket {
+    fn open(v6: bool) -> Result<Self, String> {
+        let (domain, proto) = if v6 { (libc::AF_INET6, libc::IPPROTO_ICMPV6) } else { (libc::AF_INET, libc::IPPROTO_ICMP) };
+        for (kind, raw) in [(libc::SOCK_DGRAM, false), (libc::SOCK_RAW, true)] {
+            let fd = unsafe { libc::socket(domain, kind | libc::SOCK_CLOEXEC, proto) };
+            if fd >= 0 {
+                let id = (std::process::id() as u16).wrapping_add(NEXT_PING_ID.fetch_add(1, Ordering::Relaxed));
+                return Ok(Self { fd: unsafe { OwnedFd::from_raw_fd(fd) }, v6, raw, id });
+            }
+        }
+        Err(format!(
+            "ICMP sockets are not permitted ({}); allow your group in net.ipv4.ping_group_range or run as root",
+            io::Error::last_os_error()
+        ))
+    }
+
+    fn send_echo(&self, addr: IpAddr, seq: u16) -> io::Result<()> {
+        let pkt = build_echo_request(self.v6, self.id, seq, PING_PAYLOAD);
+        let (sa, len) = to_raw(&SocketAddr::new(addr, 0));
+        let sent = unsafe {
+            libc::sendto(self.fd.as_raw_fd(), pkt.as_ptr() as *const libc::c_void, pkt.len(), 0,
+                         &sa as *const _ as *const libc::sockaddr, len)
+        };
+        if sent < 0 { Err(io::Error::last_os_error()) } else { Ok(()) }
+    }
+
+    /// Waits for the reply to `seq` until `deadline`.
+    fn wait_reply(&self, seq: u16, deadline: Instant) -> bool {
+        let mut buf = [0u8; 1500];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            if wait_for(self.fd.as_raw_fd(), remaining) & libc::POLLIN == 0 {
+                continue;
+            }
+            let n = unsafe {
+                libc::recv(self.fd.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len(), libc::MSG_DONTWAIT)
+            };
+            if n <= 0 {
+                continue;
+            }
+            if let Some((id, reply_seq)) = parse_echo_reply(&buf[..n as usize], self.v6, self.raw && !self.v6) {
+                if reply_seq == seq && (!self.raw || id == self.id) {
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+pub struct PingSession {
+    pub target: String,
+    address: Mutex<String>,
+    // RTT in ms per sequence number, `None` for a lost probe
+    samples: Mutex<Vec<Option<f64>>>,
+    error: Mutex<Option<String>>,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl PingSession {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn address(&self) -> String {
+        self.address.lock().clone()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().clone()
+    }
+
+    pub fn stats(&self) -> PingStats {
+        let samples = self.samples.lock();
+        let rtts: Vec<f64> = samples.iter().flatten().copied().collect();
+        let mut stats = PingStats {
+            sent: samples.len() as u32,
+            received: rtts.len() as u32,
+            ..Default::default()
+        };
+        if !rtts.is_empty() {
+            stats.min_ms = rtts.iter().copied().fold(f64::MAX, f64::min);
+            stats.max_ms = rtts.iter().copied().fold(0.0, f64::max);
+            stats.avg_ms = rtts.iter().sum::<f64>() / rtts.len() as f64;
+            // Mean difference between consecutive replies, as in RFC 3550
+            let diffs: Vec<f64> = rtts.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+            if !diffs.is_empty() {
+                stats.jitter_ms = diffs.iter().sum::<f64>() / diffs.len() as f64;
+            }
+        }
+        stats
+    }
+
+    /// Last `len` RTTs for the latency chart; lost probes are drawn as 0.
+    pub fn rtt_history(&self, len: usize) -> Vec<f64> {
+        let samples = self.samples.lock();
+        samples.iter().skip(samples.len().saturating_sub(len)).map(|s| s.unwrap_or(0.0)).collect()
+    }
+
+    /// Loss % over a rolling window ending at each of the last `len` probes.
+    pub fn loss_history(&self, len: usize) -> Vec<f64> {
+        let samples = self.samples.lock();
+        (samples.len().saturating_sub(len)..samples.len())
+            .map(|i| {
+                let window = &samples[(i + 1).saturating_sub(LOSS_WINDOW)..=i];
+                window.iter().filter(|s| s.is_none()).count() as f64 / window.len() as f64 * 100.0
+            })
+            .collect()
+    }
+}
+
+/// Pings `target` every `interval`, `count` times or until cancelled. The raw
+/// socket work runs on tokio's blocking pool; call from within a runtime context.
+pub fn start_ping(target: &str, count: Option<u32>, interval: Duration) -> Arc<PingSession> {
+    let session = Arc::new(PingSession {
+        target: target.to_string(),
+        address: Mutex::new(String::new()),
+        samples: Mutex::new(Vec::new()),
+        error: Mutex::new(None),
+        cancelled: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    });
+    let task_session = session.clone();
+    tokio::spawn(async move {
+        match resolve(&task_session.target).await {
+            Ok(addr) => {
+                *task_session.address.lock() = addr.to_string();
+                let blocking_session = task_session.clone();
+                let _ = tokio::task::spawn_blocking(move || ping_loop(&blocking_session, addr, count, interval)).await;
+            }
+            Err(e) => *task_session.error.lock() = Some(e),
+        }
+        task_session.finished.store(true, Ordering::Relaxed);
+    });
+    session
+}
+
+fn ping_loop(session: &PingSession, addr: IpAddr, count: Option<u32>, interval: Duration) {
+    let socket = match IcmpSocket::open(addr.is_ipv6()) {
+        Ok(s) => s,
+        Err(e) => {
+            *session.error.lock() = Some(e);
+            return;
+        }
+    };
+
+    let mut seq: u16 = 0;
+    while !session.cancelled.load(Ordering::Relaxed) && count.is_none_or(|c| (seq as u32) < c) {
+        let start = Instant::now();
+        let rtt = match socket.send_echo(addr, seq) {
+            Ok(()) => socket
+                .wait_reply(seq, start + PROBE_TIMEOUT)
+                .then(|| start.elapsed().as_secs_f64() * 1000.0),
+            Err(e) => {
+                *session.error.lock() = Some(e.to_string());
+                None
+            }
+        };
+        session.samples.lock().push(rtt);
+        seq = seq.wrapping_add(1);
+
+        // Sleep in short steps so Stop takes effect quickly
+        while start.elapsed() < interval && !session.cancelled.load(Ordering::Relaxed) {
+            std::thread::sleep(interval.saturating_sub(start.elapsed()).min(Duration::from_millis(100)));
+        }
+    }
+}
+
+
+struct ProbeError {
+    offender: Option<IpAddr>,
+    dest_port: u16,
+    // Hop reached the destination (port unreachable) or gave up (other unreachable)
+    done: bool,
+    note: &'static str,
+}
+
+/// Reads one ICMP error from the socket's error queue (IP_RECVERR).
+fn read_error_queue(fd: RawFd) -> Option<ProbeError> {
+    let mut buf = [0u8; 512];
+    let mut control = [0u8; 512];
+    let mut name: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut iov = libc::iovec { iov_base: buf.as_mut_ptr() as *mut libc::c_void, iov_len: buf.len() };
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_name = &mut name as *mut _ as *mut libc::c_void;
+    msg.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = control.len() as _;
+
+    if unsafe { libc::recvmsg(fd, &mut msg, libc::MSG_ERRQUEUE | libc::MSG_DONTWAIT) } < 0 {
+        return None;
+    }
+    let dest_port = unsafe { from_raw(&name as *const _ as *const libc::sockaddr) }.map(|a| a.port()).unwrap_or(0);
+
+    let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    while !cmsg.is_null() {
+        let hdr = unsafe { &*cmsg };
+        let is_v4 = hdr.cmsg_level == libc::SOL_IP && hdr.cmsg_type == libc::IP_RECVERR;
+        let is_v6 = hdr.cmsg_level == libc::SOL_IPV6 && hdr.cmsg_type == libc::IPV6_RECVERR;
+        if is_v4 || is_v6 {
+            let ee_ptr = unsafe { libc::CMSG_DATA(cmsg) } as *const libc::sock_extended_err;
+            let ee = unsafe { std::ptr::read_unaligned(ee_ptr) };
+            if ee.ee_origin != libc::SO_EE_ORIGIN_ICMP && ee.ee_origin != libc::SO_EE_ORIGIN_ICMP6 {
+                return None;
+            }
+            let offender = unsafe { from_raw(libc::SO_EE_OFFENDER(ee_ptr)) }.map(|a| a.ip());
+            let (done, note) = match (is_v6, ee.ee_type, ee.ee_code) {
+                (false, 11, _) | (true, 3, _) => (false, ""),
+                (false, 3, 3) | (true, 1, 4) => (true, ""),
+                (false, 3, 0) | (true, 1, 0) => (true, "!N"),
+                (false, 3, 1) | (true, 1, 3) => (true, "!H"),
+                (false, 3, 2) => (true, "!P"),
+                (false, 3, 13) | (true, 1, 1) => (true, "!X"),
+                _ => (true, "!"),
+            };
+            return Some(ProbeError { offender, dest_port, done, note });
+        }
+        cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
+    }
+    None
+}
+
+pub struct TraceSession {
+    pub target: String,
+    address: Mutex<String>,
+    hops: Mutex<Vec<TraceHop>>,
+    error: Mutex<Option<String>>,
+    reached: AtomicBool,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl TraceSession {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn reached(&self) -> bool {
+        self.reached.load(Ordering::Relaxed)
+    }
+
+    pub fn address(&self) -> String {
+        self.address.lock().clone()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().clone()
+    }
+
+    pub fn hops(&self) -> Vec<TraceHop> {
+        self.hops.lock().clone()
+    }
+}
+
+/// UDP traceroute. ICMP time-exceeded replies are read from the socket error
+/// queue, which works without privileges, the same way tracepath(8) does.
+pub fn start_traceroute(target: &str, max_hops: u8) -> Arc<TraceSession> {
+    let session = Arc::new(TraceSession {
+        target: target.to_string(),
+        address: Mutex::new(String::new()),
+        hops: Mutex::new(Vec::new()),
+        error: Mutex::new(None),
+        reached: AtomicBool::new(false),
+        cancelled: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    });
+    let task_session = session.clone();
+    tokio::spawn(async move {
+        match resolve(&task_session.target).await {
+            Ok(addr) => {
+                *task_session.address.lock() = addr.to_string();
+                let blocking_session = task_session.clone();
+                let result = tokio::task::spawn_blocking(move || trace_loop(&blocking_session, addr, max_hops)).await;
+                if let Ok(Err(e)) = result {
+                    *task_session.error.lock() = Some(e.to_string());
+                }
+            }
+            Err(e) => *task_session.error.lock() = Some(e),
+        }
+        task_session.finished.store(true, Ordering::Relaxed);
+    });
+    session
+}
+
+fn trace_loop(session: &TraceSession, addr: IpAddr, max_hops: u8) -> io::Result<()> {
+    let socket = UdpSocket::bind(if addr.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" })?;
+    let fd = socket.as_raw_fd();
+    let (level, recverr, ttl_opt) = if addr.is_ipv6() {
+        (libc::SOL_IPV6, libc::IPV6_RECVERR, libc::IPV6_UNICAST_HOPS)
+    } else {
+        (libc::SOL_IP, libc::IP_RECVERR, libc::IP_TTL)
+    };
+    setsockopt_int(fd, level, recverr, 1)?;
+
+    for ttl in 1..=max_hops {
+        setsockopt_int(fd, level, ttl_opt, ttl as libc::c_int)?;
+        session.hops.lock().push(TraceHop { ttl, ..Default::default() });
+        let mut done = false;
+
+        for probe in 0..TRACE_PROBES {
+            if session.cancelled.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            let port = TRACE_BASE_PORT + (ttl as u16 - 1) * TRACE_PROBES as u16 + probe as u16;
+            let start = Instant::now();
+            socket.send_to(&[0u8; 32], SocketAddr::new(addr, port))?;
+
+            let mut rtt = None;
+            let deadline = start + PROBE_TIMEOUT;
+            while rtt.is_none() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                let revents = wait_for(fd, remaining);
+                if revents & libc::POLLERR != 0 {
+                    // Errors for earlier, timed-out probes carry a different port
+                    if let Some(err) = read_error_queue(fd).filter(|e| e.dest_port == port) {
+                        rtt = Some(start.elapsed().as_secs_f64() * 1000.0);
+                        let mut hops = session.hops.lock();
+                        if let Some(hop) = hops.last_mut() {
+                            if hop.address.is_none() {
+                                hop.address = err.offender.map(|ip| ip.to_string());
+                            }
+                            if !err.note.is_empty() {
+                                hop.note = err.note.to_string();
+                            }
+                        }
+                        done |= err.done;
+                    }
+                } else if revents & libc::POLLIN != 0 {
+                    // Something answered on the UDP port itself: that is the destination
+                    let mut buf = [0u8; 512];
+                    if let Ok((_, from)) = socket.recv_from(&mut buf) {
+                        rtt = Some(start.elapsed().as_secs_f64() * 1000.0);
+                        if let Some(hop) = session.hops.lock().last_mut() {
+                            hop.address.get_or_insert_with(|| from.ip().to_string());
+                        }
+                        done = true;
+                    }
+                }
+            }
+            if let Some(hop) = session.hops.lock().last_mut() {
+                hop.rtts_ms.push(rtt);
+            }
+        }
+
+        if done {
+            let reached = session.hops.lock().last().is_some_and(|h| h.note.is_empty());
+            session.reached.store(reached, Ordering::Relaxed);
+            break;
+        }
+    }
+    Ok(())
+}
+
+
+pub const DNS_TYPE_A: u16 = 1;
+pub const DNS_TYPE_AAAA: u16 = 28;
+const DNS_TYPE_CNAME: u16 = 5;
+
+/// Nameservers from /etc/resolv.conf. A trailing `%scope` of link-local IPv6
+/// servers is dropped.
+pub fn parse_resolv_conf(content: &str) -> Vec<IpAddr> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next()? != "nameserver" {
+                return None;
+            }
+            parts.next()?.split('%').next()?.parse().ok()
+        })
+        .collect()
+}
+
+pub fn build_dns_query(id: u16, name: &str, qtype: u16) -> Result<Vec<u8>, String> {
+    // Header: id, RD flag, one question
+    let mut pkt = id.to_be_bytes().to_vec();
+    pkt.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("'{}' is not a valid host name", name));
+        }
+        pkt.push(label.len() as u8);
+        pkt.extend_from_slice(label.as_bytes());
+    }
+    pkt.push(0);
+    pkt.extend_from_slice(&qtype.to_be_bytes());
+    pkt.extend_from_slice(&1u16.to_be_bytes());
+    Ok(pkt)
+}
+
+/// Reads a possibly compressed name; returns it and the offset just past it.
+fn read_dns_name(buf: &[u8], mut pos: usize) -> Result<(String, usize), String> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    for _ in 0..64 {
+        let len = *buf.get(pos).ok_or("truncated name")? as usize;
+        if len == 0 {
+            return Ok((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xc0 == 0xc0 {
+            let low = *buf.get(pos + 1).ok_or("truncated name pointer")? as usize;
+            end.get_or_insert(pos + 2);
+            pos = ((len & 0x3f) << 8) | low;
+            continue;
+        }
+        let label = buf.get(pos + 1..pos + 1 + len).ok_or("truncated label")?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        pos += 1 + len;
+    }
+    Err("name compression loop".to_string())
+}
+
+fn rcode_name(rcode: u8) -> String {
+    match rcode {
+        0 => "NOERROR".to_string(),
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        other => format!("RCODE{}", other),
+    }
+}
+
+/// (rcode name, answers) of a response to query `id`.
+pub fn parse_dns_response(buf: &[u8], id: u16) -> Result<(String, Vec<String>), String> {
+    if buf.len() < 12 {
+        return Err("response too short".to_string());
+    }
+    let word = |pos: usize| -> Result<u16, String> {
+        buf.get(pos..pos + 2).map(|b| u16::from_be_bytes([b[0], b[1]])).ok_or_else(|| "truncated record".to_string())
+    };
+    if word(0)? != id {
+        return Err("response id does not match the query".to_string());
+    }
+    let flags = word(2)?;
+    if flags & 0x8000 == 0 {
+        return Err("not a DNS response".to_string());
+    }
+
+    let mut pos = 12;
+    for _ in 0..word(4)? {
+        pos = read_dns_name(buf, pos)?.1 + 4;
+    }
+    let mut answers = Vec::new();
+    for _ in 0..word(6)? {
+        pos = read_dns_name(buf, pos)?.1;
+        let rtype = word(pos)?;
+        let rdlen = word(pos + 8)? as usize;
+        let rdata_start = pos + 10;
+        let rdata = buf.get(rdata_start..rdata_start + rdlen).ok_or("truncated record data")?;
+        answers.push(match (rtype, rdlen) {
+            (DNS_TYPE_A, 4) => Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+            (DNS_TYPE_AAAA, 16) => Ipv6Addr::from(<[u8; 16]>::try_from(rdata).unwrap_or_default()).to_string(),
+            (DNS_TYPE_CNAME, _) => format!("CNAME {}", read_dns_name(buf, rdata_start)?.0),
+            (other, _) => format!("type {} record", other),
+        });
+        pos = rdata_start + rdlen;
+    }
+    Ok((rcode_name((flags & 0x0f) as u8), answers))
+}
+
+/// Sends one query over UDP straight to `server`, bypassing the system resolver
+/// so each configured nameserver is measured on its own.
+pub async fn query_dns(server: SocketAddr, name: &str, qtype: u16) -> DnsLookup {
+    let mut lookup = DnsLookup {
+        server: server.ip().to_string(),
+        record_type: if qtype == DNS_TYPE_AAAA { "AAAA" } else { "A" }.to_string(),
+        ..Default::default()
+    };
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+    let id = (nanos as u16) ^ (std::process::id() as u16) ^ qtype;
+    let query = match build_dns_query(id, name, qtype) {
+        Ok(q) => q,
+        Err(e) => {
+            lookup.error = Some(e);
+            return lookup;
+        }
+    };
+
+    let result = async {
+        let socket = tokio::net::UdpSocket::bind(if server.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" }).await?;
+        let start = Instant::now();
+        socket.send_to(&query, server).await?;
+        let mut buf = [0u8; 1500];
+        loop {
+            let (n, from) = socket.recv_from(&mut buf).await?;
+            if from == server && buf[..n].starts_with(&id.to_be_bytes()) {
+                return Ok::<_, io::Error>((start.elapsed(), buf[..n].to_vec()));
+            }
+        }
+    };
+    match tokio::time::timeout(DNS_TIMEOUT, result).await {
+        Ok(Ok((elapsed, response))) => match parse_dns_response(&response, id) {
+            Ok((rcode, answers)) => {
+                lookup.latency_ms = Some(elapsed.as_secs_f64() * 1000.0);
+                lookup.rcode = rcode;
+                lookup.answers = answers;
+            }
+            Err(e) => lookup.error = Some(e),
+        },
+        Ok(Err(e)) => lookup.error = Some(e.to_string()),
+        Err(_) => lookup.error = Some(format!("no answer within {}s", DNS_TIMEOUT.as_secs())),
+    }
+    lookup
+}
+
+/// A and AAAA lookups of `name` against every resolver in /etc/resolv.conf.
+pub async fn lookup_all(name: &str) -> Vec<DnsLookup> {
+    let resolv = tokio::fs::read_to_string("/etc/resolv.conf").await.unwrap_or_default();
+    let servers = parse_resolv_conf(&resolv);
+    if servers.is_empty() {
+        return vec![DnsLookup {
+            error: Some("No nameservers in /etc/resolv.conf".to_string()),
+            ..Default::default()
+        }];
+    }
+    let queries = servers.iter().flat_map(|ip| {
+        [DNS_TYPE_A, DNS_TYPE_AAAA].map(|qtype| query_dns(SocketAddr::new(*ip, 53), name, qtype))
+    });
+    futures_util::future::join_all(queries).await
+}
+
+
+/// Connects to every address `host` resolves to (up to four) and times the handshake.
+pub async fn tcp_check(host: &str, port: u16) -> Vec<TcpCheck> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = match tokio::net::lookup_host((host, port)).await {
+        Ok(addrs) => addrs.take(4).collect(),
+        Err(e) => {
+            return vec![TcpCheck {
+                address: host.to_string(),
+                error: Some(format!("Cannot resolve: {}", e)),
+                ..Default::default()
+            }];
+        }
+    };
+
+    let checks = addrs.into_iter().map(|addr| async move {
+        let start = Instant::now();
+        let mut check = TcpCheck { address: addr.to_string(), ..Default::default() };
+        match tokio::time::timeout(TCP_TIMEOUT, tokio::net::TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => check.latency_ms = Some(start.elapsed().as_secs_f64() * 1000.0),
+            Ok(Err(e)) => check.error = Some(e.to_string()),
+            Err(_) => check.error = Some(format!("no answer within {}s", TCP_TIMEOUT.as_secs())),
+        }
+        check
+    });
+    futures_util::future::join_all(checks).await
+}
+
+/// Result slot for one-shot checks (DNS, TCP) polled from the GUI.
+pub struct DiagJob<T> {
+    result: Mutex<Option<T>>,
+}
+
+impl<T: Clone> DiagJob<T> {
+    pub fn result(&self) -> Option<T> {
+        self.result.lock().clone()
+    }
+}
+
+pub fn spawn_job<T, F>(fut: F) -> Arc<DiagJob<T>>
+where
+    T: Send + 'static,
+    F: std::future::Future<Output = T> + Send + 'static,
+{
+    let job = Arc::new(DiagJob { result: Mutex::new(None) });
+    let task_job = job.clone();
+    tokio::spawn(async move {
+        let result = fut.await;
+        *task_job.result.lock() = Some(result);
+    });
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn wait_until(finished: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if finished() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("diagnostic did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_dns_against_local_server() {
+        assert_eq!(
+            parse_resolv_conf("# generated\nnameserver 127.0.0.53\noptions edns0\nnameserver fe80::1%eth0\n"),
+            vec!["127.0.0.53".parse::<IpAddr>().unwrap(), "fe80::1".parse().unwrap()]
+        );
+
+        // Stand-in server: answers with a CNAME to a compressed name and an A record
+        let server = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            let (n, from) = server.recv_from(&mut buf).await.unwrap();
+            let mut resp = buf[..n].to_vec();
+            resp[2..4].copy_from_slice(&[0x81, 0x80]);
+            resp[6..8].copy_from_slice(&2u16.to_be_bytes());
+            resp.extend_from_slice(&[0xc0, 0x0c, 0, 5, 0, 1, 0, 0, 0, 60, 0, 6, 3, b'w', b'e', b'b', 0xc0, 0x0c]);
+            resp.extend_from_slice(&[0xc0, 0x0c, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 10, 1, 2, 3]);
+            server.send_to(&resp, from).await.unwrap();
+        });
+
+        let lookup = query_dns(server_addr, "example.test", DNS_TYPE_A).await;
+        assert_eq!(lookup.error, None);
+        assert_eq!(lookup.rcode, "NOERROR");
+        assert_eq!(lookup.answers, vec!["CNAME web.example.test", "10.1.2.3"]);
+        assert!(lookup.latency_ms.is_some());
+
+        let query = build_dns_query(0x1234, "example.test", DNS_TYPE_A).unwrap();
+        let mut nxdomain = query.clone();
+        nxdomain[2..4].copy_from_slice(&[0x81, 0x83]);
+        assert_eq!(parse_dns_response(&nxdomain, 0x1234).unwrap(), ("NXDOMAIN".to_string(), vec![]));
+        assert!(parse_dns_response(&query, 0x1234).is_err());
+        assert!(build_dns_query(1, "bad..name", DNS_TYPE_A).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_check_localhost() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let open = tcp_check("127.0.0.1", port).await;
+        assert_eq!(open.len(), 1);
+        assert!(open[0].latency_ms.is_some() && open[0].error.is_none());
+
+        drop(listener);
+        let closed = tcp_check("127.0.0.1", port).await;
+        assert!(closed[0].latency_ms.is_none() && closed[0].error.is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ping_and_traceroute_localhost() {
+        let pkt = build_echo_request(false, 0xbeef, 7, b"abcd");
+        assert_eq!(icmp_checksum(&pkt), 0);
+        let mut reply = pkt.clone();
+        reply[0] = 0;
+        assert_eq!(parse_echo_reply(&reply, false, false), Some((0xbeef, 7)));
+        assert_eq!(parse_echo_reply(&pkt, false, false), None);
+
+        let ping = start_ping("127.0.0.1", Some(3), Duration::from_millis(50));
+        wait_until(|| ping.is_finished()).await;
+        // Sandboxes without ping sockets or CAP_NET_RAW cannot send ICMP at all
+        if ping.error().is_none() {
+            let stats = ping.stats();
+            assert_eq!((stats.sent, stats.received), (3, 3));
+            assert_eq!(stats.loss_percent(), 0.0);
+            assert_eq!(ping.rtt_history(10).len(), 3);
+        }
+
+        let trace = start_traceroute("127.0.0.1", 5);
+        wait_until(|| trace.is_finished()).await;
+        assert_eq!(trace.error(), None);
+        let hops = trace.hops();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].address.as_deref(), Some("127.0.0.1"));
+        assert!(trace.reached());
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::Path;
+
+use crate::monitors::{read_string, read_u64};
+
+// ARPHRD_* from linux/if_arp.h
+const ARPHRD_LOOPBACK: u32 = 772;
+const ARPHRD_NONE: u32 = 65534;
+const IFF_UP: u32 = 0x1;
+
+/// Link-level details of one interface from /sys/class/net/<if>.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceInfo {
+    pub kind: String,
+    pub is_virtual: bool,
+    pub is_up: bool,
+    pub operstate: String,
+    pub carrier: Option<bool>,
+    pub speed_mbps: Option<u32>,
+    pub duplex: String,
+    pub mtu: u32,
+    pub mac: String,
+    pub driver: String,
+}
+
+pub fn read_interface_info(name: &str) -> InterfaceInfo {
+    read_interface_info_from(&Path::new("/sys/class/net").join(name), name)
+}
+
+pub fn read_interface_info_from(dir: &Path, name: &str) -> InterfaceInfo {
+    let uevent = read_string(&dir.join("uevent")).unwrap_or_default();
+    let devtype = uevent
+        .lines()
+        .find_map(|l| l.strip_prefix("DEVTYPE="))
+        .unwrap_or("")
+        .to_string();
+    let arp_type = read_u64(&dir.join("type")).unwrap_or(0) as u32;
+    let flags = read_string(&dir.join("flags"))
+        .and_then(|f| u32::from_str_radix(f.trim_start_matches("0x"), 16).ok())
+        .unwrap_or(0);
+    let has_device = dir.join("device").exists();
+    let driver = fs::read_link(dir.join("device/driver"))
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    // Stacked devices (veth, vlan, macvlan) point iflink at their peer or parent
+    let stacked = match (read_u64(&dir.join("iflink")), read_u64(&dir.join("ifindex"))) {
+        (Some(iflink), Some(ifindex)) => iflink != ifindex,
+        _ => false,
+    };
+
+    let kind = if arp_type == ARPHRD_LOOPBACK || name == "lo" {
+        "loopback"
+    } else if devtype == "wlan" || dir.join("wireless").exists() || dir.join("phy80211").exists() {
+        "wifi"
+    } else if devtype == "bridge" || dir.join("bridge").exists() {
+        "bridge"
+    } else if devtype == "bond" || dir.join("bonding").exists() {
+        "bond"
+    } else if devtype == "vlan" {
+        "vlan"
+    } else if dir.join("tun_flags").exists() || devtype == "wireguard" || arp_type == ARPHRD_NONE {
+        "tun"
+    } else if has_device {
+        "ethernet"
+    } else if stacked || name.starts_with("veth") {
+        "veth"
+    } else {
+        "virtual"
+    };
+
+    let operstate = read_string(&dir.join("operstate")).unwrap_or_else(|| "unknown".to_string());
+    // Reading carrier/speed fails with EINVAL while the interface is administratively down
+    let carrier = read_u64(&dir.join("carrier")).map(|c| c == 1);
+    let is_up = operstate == "up"
+        || (operstate == "unknown" && flags & IFF_UP != 0 && carrier != Some(false));
+
+    InterfaceInfo {
+        kind: kind.to_string(),
+        is_virtual: !has_device,
+        is_up,
+        operstate,
+        carrier,
+        speed_mbps: read_string(&dir.join("speed"))
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|s| *s > 0)
+            .map(|s| s as u32),
+        duplex: read_string(&dir.join("duplex")).filter(|d| d != "unknown").unwrap_or_default(),
+        mtu: read_u64(&dir.join("mtu")).unwrap_or(0) as u32,
+        mac: read_string(&dir.join("address")).unwrap_or_default(),
+        driver,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+    use std::os::unix::fs::symlink;
+    use std::path::PathBuf;
+
+    fn iface(root: &Path, name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = root.join(name);
+        for (file, content) in files {
+            write_fixture(dir.join(file), content);
+        }
+        dir
+    }
+
+    #[test]
+    fn test_interface_classification() {
+        let root = std::env::temp_dir().join(format!("puls-netif-{}", std::process::id()));
+        let common = [("type", "1\n"), ("flags", "0x1003\n"), ("mtu", "1500\n"), ("address", "52:54:00:12:34:56\n")];
+
+        let eth = iface(&root, "enp3s0", &[
+            ("uevent", "INTERFACE=enp3s0\nIFINDEX=2\n"), ("operstate", "up\n"), ("carrier", "1\n"),
+            ("speed", "1000\n"), ("duplex", "full\n"), ("iflink", "2\n"), ("ifindex", "2\n"),
+        ]);
+        for (f, c) in &common {
+            write_fixture(eth.join(f), c);
+        }
+        write_fixture(root.join("drivers/e1000e/.keep"), "");
+        fs::create_dir_all(eth.join("device")).unwrap();
+        symlink(root.join("drivers/e1000e"), eth.join("device/driver")).unwrap();
+
+        let wifi = iface(&root, "wlp2s0", &[("uevent", "DEVTYPE=wlan\nINTERFACE=wlp2s0\n"), ("operstate", "dormant\n")]);
+        fs::create_dir_all(wifi.join("device")).unwrap();
+        iface(&root, "br0", &[("uevent", "DEVTYPE=bridge\n"), ("operstate", "down\n"), ("speed", "-1\n")]);
+        iface(&root, "veth1a2b", &[("iflink", "7\n"), ("ifindex", "8\n"), ("operstate", "up\n")]);
+        iface(&root, "tun0", &[("tun_flags", "0x1001\n"), ("type", "65534\n"), ("operstate", "unknown\n"), ("flags", "0x1091\n")]);
+        iface(&root, "bond0", &[("bonding/mode", "active-backup 1\n")]);
+        iface(&root, "enp3s0.100", &[("uevent", "DEVTYPE=vlan\n"), ("iflink", "2\n"), ("ifindex", "9\n")]);
+        iface(&root, "lo", &[("type", "772\n"), ("operstate", "unknown\n"), ("flags", "0x9\n"), ("carrier", "1\n")]);
+
+        let read = |name: &str| read_interface_info_from(&root.join(name), name);
+        let eth_info = read("enp3s0");
+        let infos: Vec<(String, bool, bool)> = ["wlp2s0", "br0", "veth1a2b", "tun0", "bond0", "enp3s0.100", "lo"]
+            .iter()
+            .map(|n| {
+                let i = read(n);
+                (i.kind, i.is_virtual, i.is_up)
+            })
+            .collect();
+        let br = read("br0");
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(eth_info.kind, "ethernet");
+        assert!(!eth_info.is_virtual);
+        assert!(eth_info.is_up);
+        assert_eq!(eth_info.speed_mbps, Some(1000));
+        assert_eq!(eth_info.duplex, "full");
+        assert_eq!(eth_info.mtu, 1500);
+        assert_eq!(eth_info.mac, "52:54:00:12:34:56");
+        assert_eq!(eth_info.driver, "e1000e");
+        assert_eq!(eth_info.carrier, Some(true));
+
+        assert_eq!(infos, vec![
+            ("wifi".to_string(), false, false),
+            ("bridge".to_string(), true, false),
+            ("veth".to_string(), true, true),
+            ("tun".to_string(), true, true),
+            ("bond".to_string(), true, false),
+            ("vlan".to_string(), true, false),
+            ("loopback".to_string(), true, true),
+        ]);
+        assert_eq!(br.speed_mbps, None);
+        assert_eq!(br.operstate, "down");
+    }
+}
@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_char, c_uint, c_void};
+use std::ptr;
+
+use libloading::Library;
+
+use crate::types::{GpuInfo, GpuProcess, NvidiaGpuControls};
+
+// NVML is loaded at runtime so the binary still starts on machines without the
+// NVIDIA driver. Only the handful of entry points below are used.
+const LIBRARY_NAMES: &[&str] = &["libnvidia-ml.so.1", "libnvidia-ml.so"];
+
+const NVML_SUCCESS: c_uint = 0;
+const NVML_ERROR_NOT_FOUND: c_uint = 6;
+const NVML_ERROR_INSUFFICIENT_SIZE: c_uint = 7;
+const NVML_TEMPERATURE_GPU: c_uint = 0;
+const NVML_CLOCK_GRAPHICS: c_uint = 0;
+const NVML_CLOCK_MEM: c_uint = 2;
+const NVML_MEMORY_ERROR_TYPE_CORRECTED: c_uint = 0;
+const NVML_MEMORY_ERROR_TYPE_UNCORRECTED: c_uint = 1;
+const NVML_VOLATILE_ECC: c_uint = 0;
+const NVML_VALUE_NOT_AVAILABLE: u64 = u64::MAX;
+const NAME_BUFFER_SIZE: usize = 96;
+const DRIVER_BUFFER_SIZE: usize = 80;
+
+const THROTTLE_REASONS: &[(u64, &str)] = &[
+    (0x1, "Idle"),
+    (0x2, "Application Clocks"),
+    (0x4, "SW Power Cap"),
+    (0x8, "HW Slowdown"),
+    (0x10, "Sync Boost"),
+    (0x20, "SW Thermal"),
+    (0x40, "HW Thermal"),
+    (0x80, "HW Power Brake"),
+    (0x100, "Display Clock"),
+];
+
+type Device = *mut c_void;
+type Ret = c_uint;
+type ProcessListFn = unsafe extern "C" fn(Device, *mut c_uint, *mut c_void) -> Ret;
+type UtilFn = unsafe extern "C" fn(Device, *mut c_uint, *mut c_uint) -> Ret;
+type ValueFn = unsafe extern "C" fn(Device, *mut c_uint) -> Ret;
+type ProcessUtilFn = unsafe extern "C" fn(Device, *mut ProcessSample, *mut c_uint, u64) -> Ret;
+
+#[repr(C)]
+#[derive(Default)]
+struct Utilization {
+    gpu: c_uint,
+    memory: c_uint,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Memory {
+    total: u64,
+    free: u64,
+    used: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct ProcessInfoV1 {
+    pid: c_uint,
+    used_gpu_memory: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct ProcessInfoV2 {
+    pid: c_uint,
+    used_gpu_memory: u64,
+    gpu_instance_id: c_uint,
+    compute_instance_id: c_uint,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct ProcessSample {
+    pid: c_uint,
+    timestamp: u64,
+    sm_util: c_uint,
+    mem_util: c_uint,
+    enc_util: c_uint,
+    dec_util: c_uint,
+}
+
+struct Api {
+    init: unsafe extern "C" fn() -> Ret,
+    shutdown: unsafe extern "C" fn() -> Ret,
+    error_string: Option<unsafe extern "C" fn(Ret) -> *const c_char>,
+    device_count: unsafe extern "C" fn(*mut c_uint) -> Ret,
+    device_by_index: unsafe extern "C" fn(c_uint, *mut Device) -> Ret,
+    device_name: unsafe extern "C" fn(Device, *mut c_char, c_uint) -> Ret,
+    driver_version: unsafe extern "C" fn(*mut c_char, c_uint) -> Ret,
+    utilization: unsafe extern "C" fn(Device, *mut Utilization) -> Ret,
+    memory: unsafe extern "C" fn(Device, *mut Memory) -> Ret,
+    temperature: unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> Ret,
+    clock: unsafe extern "C" fn(Device, c_uint, *mut c_uint) -> Ret,
+    power_usage: Option<ValueFn>,
+    power_limit: Option<ValueFn>,
+    fan_speed: Option<ValueFn>,
+    pcie_gen: Option<ValueFn>,
+    pcie_width: Option<ValueFn>,
+    throttle_reasons: Option<unsafe extern "C" fn(Device, *mut u64) -> Ret>,
+    ecc_errors: Option<unsafe extern "C" fn(Device, c_uint, c_uint, *mut u64) -> Ret>,
+    encoder: Option<UtilFn>,
+    decoder: Option<UtilFn>,
+    // The _v2/_v3 entry points fill the larger v2 struct, the unversioned ones the v1 struct
+    compute_processes: Option<(ProcessListFn, bool)>,
+    graphics_processes: Option<(ProcessListFn, bool)>,
+    process_utilization: Option<ProcessUtilFn>,
+    power_limit_constraints: Option<unsafe extern "C" fn(Device, *mut c_uint, *mut c_uint) -> Ret>,
+    power_limit_default: Option<ValueFn>,
+    power_limit_current: Option<ValueFn>,
+    persistence_mode: Option<ValueFn>,
+    set_power_limit: Option<unsafe extern "C" fn(Device, c_uint) -> Ret>,
+    set_persistence_mode: Option<unsafe extern "C" fn(Device, c_uint) -> Ret>,
+}
+
+
+/// # Safety
+/// `T` must be a function pointer type matching the C signature of `name`.
+unsafe fn symbol<T: Copy>(lib: &Library, name: &str) -> Result<T, String> {
+    lib.get::<T>(name.as_bytes())
+        .map(|s| *s)
+        .map_err(|e| format!("{}: {}", name, e))
+}
+
+/// # Safety
+/// Same requirement as [`symbol`] for every name in `names`.
+unsafe fn first_symbol<T: Copy>(lib: &Library, names: &[&str]) -> Option<(T, usize)> {
+    names.iter().enumerate().find_map(|(i, name)| symbol::<T>(lib, name).ok().map(|f| (f, i)))
+}
+
+pub struct Nvml {
+    api: Api,
+    // Must outlive every function pointer in `api`
+    _lib: Library,
+    last_sample: HashMap<u32, u64>,
+}
+
+impl Nvml {
+    pub fn load() -> Result<Self, String> {
+        let mut last_err = String::new();
+        let lib = LIBRARY_NAMES
+            .iter()
+            .find_map(|name| match unsafe { Library::new(name) } {
+                Ok(lib) => Some(lib),
+                Err(e) => {
+                    last_err = e.to_string();
+                    None
+                }
+            })
+            .ok_or(last_err)?;
+
+        let api = unsafe {
+            Api {
+                init: symbol(&lib, "nvmlInit_v2")?,
+                shutdown: symbol(&lib, "nvmlShutdown")?,
+                error_string: symbol(&lib, "nvmlErrorString").ok(),
+                device_count: symbol(&lib, "nvmlDeviceGetCount_v2")?,
+                device_by_index: symbol(&lib, "nvmlDeviceGetHandleByIndex_v2")?,
+                device_name: symbol(&lib, "nvmlDeviceGetName")?,
+                driver_version: symbol(&lib, "nvmlSystemGetDriverVersion")?,
+                utilization: symbol(&lib, "nvmlDeviceGetUtilizationRates")?,
+                memory: symbol(&lib, "nvmlDeviceGetMemoryInfo")?,
+                temperature: symbol(&lib, "nvmlDeviceGetTemperature")?,
+                clock: symbol(&lib, "nvmlDeviceGetClockInfo")?,
+                power_usage: symbol(&lib, "nvmlDeviceGetPowerUsage").ok(),
+                power_limit: symbol(&lib, "nvmlDeviceGetEnforcedPowerLimit").ok(),
+                fan_speed: symbol(&lib, "nvmlDeviceGetFanSpeed").ok(),
+                pcie_gen: symbol(&lib, "nvmlDeviceGetCurrPcieLinkGeneration").ok(),
+                pcie_width: symbol(&lib, "nvmlDeviceGetCurrPcieLinkWidth").ok(),
+                throttle_reasons: first_symbol(&lib, &["nvmlDeviceGetCurrentClocksEventReasons", "nvmlDeviceGetCurrentClocksThrottleReasons"]).map(|(f, _)| f),
+                ecc_errors: symbol(&lib, "nvmlDeviceGetTotalEccErrors").ok(),
+                encoder: symbol(&lib, "nvmlDeviceGetEncoderUtilization").ok(),
+                decoder: symbol(&lib, "nvmlDeviceGetDecoderUtilization").ok(),
+                compute_processes: first_symbol(&lib, &["nvmlDeviceGetComputeRunningProcesses_v3", "nvmlDeviceGetComputeRunningProcesses_v2", "nvmlDeviceGetComputeRunningProcesses"])
+                    .map(|(f, i)| (f, i < 2)),
+                graphics_processes: first_symbol(&lib, &["nvmlDeviceGetGraphicsRunningProcesses_v3", "nvmlDeviceGetGraphicsRunningProcesses_v2", "nvmlDeviceGetGraphicsRunningProcesses"])
+                    .map(|(f, i)| (f, i < 2)),
+                process_utilization: symbol(&lib, "nvmlDeviceGetProcessUtilization").ok(),
+                power_limit_constraints: symbol(&lib, "nvmlDeviceGetPowerManagementLimitConstraints").ok(),
+                power_limit_default: symbol(&lib, "nvmlDeviceGetPowerManagementDefaultLimit").ok(),
+                power_limit_current: symbol(&lib, "nvmlDeviceGetPowerManagementLimit").ok(),
+                persistence_mode: symbol(&lib, "nvmlDeviceGetPersistenceMode").ok(),
+                set_power_limit: symbol(&lib, "nvmlDeviceSetPowerManagementLimit").ok(),
+                set_persistence_mode: symbol(&lib, "nvmlDeviceSetPersistenceMode").ok(),
+            }
+        };
+
+        let ret = unsafe { (api.init)() };
+        if ret != NVML_SUCCESS {
+            return Err(format!("nvmlInit failed: {}", error_text(&api, ret)));
+        }
+        Ok(Self { api, _lib: lib, last_sample: HashMap::new() })
+    }
+
+    fn check(&self, ret: Ret, what: &str) -> Result<(), String> {
+        if ret == NVML_SUCCESS {
+            Ok(())
+        } else {
+            Err(format!("{}: {}", what, error_text(&self.api, ret)))
+        }
+    }
+
+    pub fn gpus(&mut self) -> Result<Vec<GpuInfo>, String> {
+        let mut count: c_uint = 0;
+        self.check(unsafe { (self.api.device_count)(&mut count) }, "nvmlDeviceGetCount")?;
+
+        let mut driver = [0 as c_char; DRIVER_BUFFER_SIZE];
+        let driver_version = if unsafe { (self.api.driver_version)(driver.as_mut_ptr(), driver.len() as c_uint) } == NVML_SUCCESS {
+            buffer_to_string(&mut driver)
+        } else {
+            "Unknown".to_string()
+        };
+
+        let mut gpus = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let device = self.device(index)?;
+            let mut gpu = self.device_info(device);
+            gpu.nvidia_index = Some(index);
+            gpu.driver_version = driver_version.clone();
+            gpu.processes = self.processes(index, device);
+            gpus.push(gpu);
+        }
+        Ok(gpus)
+    }
+
+    fn device(&self, index: u32) -> Result<Device, String> {
+        let mut device: Device = ptr::null_mut();
+        self.check(unsafe { (self.api.device_by_index)(index, &mut device) }, "nvmlDeviceGetHandleByIndex")?;
+        Ok(device)
+    }
+
+    pub fn controls(&self, index: u32) -> Result<NvidiaGpuControls, String> {
+        let device = self.device(index)?;
+        let value = |f: Option<ValueFn>| -> Option<u32> {
+            let mut v: c_uint = 0;
+            f.filter(|f| unsafe { f(device, &mut v) } == NVML_SUCCESS).map(|_| v)
+        };
+        let (mut min, mut max): (c_uint, c_uint) = (0, 0);
+        let constraints = self.api.power_limit_constraints
+            .filter(|f| unsafe { f(device, &mut min, &mut max) } == NVML_SUCCESS)
+            .map(|_| (min, max));
+        Ok(NvidiaGpuControls {
+            power_limit: value(self.api.power_limit_current),
+            power_limit_min: constraints.map(|c| c.0),
+            power_limit_max: constraints.map(|c| c.1),
+            power_limit_default: value(self.api.power_limit_default),
+            persistence: value(self.api.persistence_mode).map(|m| m != 0),
+        })
+    }
+
+    /// Needs root; `milliwatts` must be within the device's constraints.
+    pub fn set_power_limit(&self, index: u32, milliwatts: u32) -> Result<(), String> {
+        let device = self.device(index)?;
+        let f = self.api.set_power_limit.ok_or("power limit control not supported by this driver")?;
+        self.check(unsafe { f(device, milliwatts) }, "nvmlDeviceSetPowerManagementLimit")
+    }
+
+    pub fn set_persistence_mode(&self, index: u32, enabled: bool) -> Result<(), String> {
+        let device = self.device(index)?;
+        let f = self.api.set_persistence_mode.ok_or("persistence mode not supported by this driver")?;
+        self.check(unsafe { f(device, enabled as c_uint) }, "nvmlDeviceSetPersistenceMode")
+    }
+
+    fn device_info(&self, device: Device) -> GpuInfo {
+        let api = &self.api;
+        let value = |f: Option<ValueFn>| -> Option<u32> {
+            let mut v: c_uint = 0;
+            f.filter(|f| unsafe { f(device, &mut v) } == NVML_SUCCESS).map(|_| v)
+        };
+        let util_rate = |f: Option<UtilFn>| -> Option<u32> {
+            let (mut v, mut period): (c_uint, c_uint) = (0, 0);
+            f.filter(|f| unsafe { f(device, &mut v, &mut period) } == NVML_SUCCESS).map(|_| v)
+        };
+        let ecc = |kind: c_uint| -> Option<u64> {
+            let mut v: u64 = 0;
+            api.ecc_errors
+                .filter(|f| unsafe { f(device, kind, NVML_VOLATILE_ECC, &mut v) } == NVML_SUCCESS)
+                .map(|_| v)
+        };
+
+        let mut name = [0 as c_char; NAME_BUFFER_SIZE];
+        let name = if unsafe { (api.device_name)(device, name.as_mut_ptr(), name.len() as c_uint) } == NVML_SUCCESS {
+            buffer_to_string(&mut name)
+        } else {
+            "NVIDIA GPU".to_string()
+        };
+
+        let mut util = Utilization::default();
+        unsafe { (api.utilization)(device, &mut util) };
+        let mut mem = Memory::default();
+        unsafe { (api.memory)(device, &mut mem) };
+        let mut temperature: c_uint = 0;
+        unsafe { (api.temperature)(device, NVML_TEMPERATURE_GPU, &mut temperature) };
+        let mut graphics_clock: c_uint = 0;
+        unsafe { (api.clock)(device, NVML_CLOCK_GRAPHICS, &mut graphics_clock) };
+        let mut memory_clock: c_uint = 0;
+        unsafe { (api.clock)(device, NVML_CLOCK_MEM, &mut memory_clock) };
+
+        let mut reasons: u64 = 0;
+        let throttle_reasons = match api.throttle_reasons {
+            Some(f) if unsafe { f(device, &mut reasons) } == NVML_SUCCESS => throttle_reason_names(reasons),
+            _ => Vec::new(),
+        };
+
+        let encoder_utilization = util_rate(api.encoder);
+        let decoder_utilization = util_rate(api.decoder);
+        let engines = [("Graphics", Some(util.gpu)), ("Encoder", encoder_utilization), ("Decoder", decoder_utilization)]
+            .into_iter()
+            .filter_map(|(engine, busy)| busy.map(|b| (engine.to_string(), b)))
+            .collect();
+
+        GpuInfo {
+            name,
+            brand: "NVIDIA".to_string(),
+            utilization: util.gpu,
+            memory_used: mem.used,
+            memory_total: mem.total,
+            temperature,
+            power_usage: value(api.power_usage).unwrap_or(0),
+            power_limit: value(api.power_limit),
+            graphics_clock,
+            memory_clock,
+            fan_speed: value(api.fan_speed),
+            pci_link_gen: value(api.pcie_gen),
+            pci_link_width: value(api.pcie_width),
+            backend: "NVML".to_string(),
+            throttle_reasons,
+            ecc_corrected: ecc(NVML_MEMORY_ERROR_TYPE_CORRECTED),
+            ecc_uncorrected: ecc(NVML_MEMORY_ERROR_TYPE_UNCORRECTED),
+            encoder_utilization,
+            decoder_utilization,
+            engines,
+            ..Default::default()
+        }
+    }
+
+    fn processes(&mut self, index: u32, device: Device) -> Vec<GpuProcess> {
+        let mut procs: Vec<GpuProcess> = Vec::new();
+        for (list, kind) in [(self.api.compute_processes, "C"), (self.api.graphics_processes, "G")] {
+            let Some((f, v2)) = list else { continue };
+            for (pid, used) in running_processes(f, v2, device) {
+                let memory_used = Some(used).filter(|&m| m != NVML_VALUE_NOT_AVAILABLE);
+                match procs.iter_mut().find(|p| p.pid == pid) {
+                    Some(p) => {
+                        p.kind = "C+G".to_string();
+                        p.memory_used = p.memory_used.or(memory_used);
+                    }
+                    None => procs.push(GpuProcess {
+                        pid,
+                        name: process_name(pid),
+                        kind: kind.to_string(),
+                        memory_used,
+                        sm_utilization: None,
+                    }),
+                }
+            }
+        }
+
+        if let Some(f) = self.api.process_utilization {
+            let last_seen = self.last_sample.get(&index).copied().unwrap_or(0);
+            if let Some(samples) = process_samples(f, device, last_seen) {
+                let mut newest: HashMap<u32, &ProcessSample> = HashMap::new();
+                for s in &samples {
+                    if newest.get(&s.pid).is_none_or(|n| n.timestamp < s.timestamp) {
+                        newest.insert(s.pid, s);
+                    }
+                }
+                if let Some(ts) = samples.iter().map(|s| s.timestamp).max() {
+                    self.last_sample.insert(index, ts);
+                }
+                // No sample since the last poll means the process did not run on the GPU
+                for p in &mut procs {
+                    p.sm_utilization = Some(newest.get(&p.pid).map(|s| s.sm_util).unwrap_or(0));
+                }
+            }
+        }
+
+        procs.sort_by(|a, b| b.memory_used.cmp(&a.memory_used).then(a.pid.cmp(&b.pid)));
+        procs
+    }
+}
+
+impl Drop for Nvml {
+    fn drop(&mut self) {
+        unsafe { (self.api.shutdown)() };
+    }
+}
+
+fn error_text(api: &Api, ret: Ret) -> String {
+    api.error_string
+        .map(|f| unsafe { f(ret) })
+        .filter(|p| !p.is_null())
+        .map(|p| unsafe { CStr::from_ptr(p) }.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("NVML error {}", ret))
+}
+
+fn running_processes(f: ProcessListFn, v2: bool, device: Device) -> Vec<(u32, u64)> {
+    let mut count: c_uint = 0;
+    if unsafe { f(device, &mut count, ptr::null_mut()) } != NVML_ERROR_INSUFFICIENT_SIZE {
+        return Vec::new();
+    }
+    // Processes can start between the sizing call and the real one, so leave headroom and retry
+    for _ in 0..3 {
+        let mut n = count + 4;
+        let (ret, list): (Ret, Vec<(u32, u64)>) = if v2 {
+            let mut buf = vec![ProcessInfoV2::default(); n as usize];
+            let ret = unsafe { f(device, &mut n, buf.as_mut_ptr() as *mut c_void) };
+            (ret, buf.iter().take(n as usize).map(|p| (p.pid, p.used_gpu_memory)).collect())
+        } else {
+            let mut buf = vec![ProcessInfoV1::default(); n as usize];
+            let ret = unsafe { f(device, &mut n, buf.as_mut_ptr() as *mut c_void) };
+            (ret, buf.iter().take(n as usize).map(|p| (p.pid, p.used_gpu_memory)).collect())
+        };
+        match ret {
+            NVML_SUCCESS => return list,
+            NVML_ERROR_INSUFFICIENT_SIZE => count = n,
+            _ => break,
+        }
+    }
+    Vec::new()
+}
+
+fn process_samples(f: ProcessUtilFn, device: Device, last_seen: u64) -> Option<Vec<ProcessSample>> {
+    let mut count: c_uint = 0;
+    match unsafe { f(device, ptr::null_mut(), &mut count, last_seen) } {
+        NVML_ERROR_NOT_FOUND => return Some(Vec::new()),
+        NVML_ERROR_INSUFFICIENT_SIZE | NVML_SUCCESS => {}
+        _ => return None,
+    }
+    let mut buf = vec![ProcessSample::default(); count as usize];
+    match unsafe { f(device, buf.as_mut_ptr(), &mut count, last_seen) } {
+        NVML_SUCCESS => {
+            buf.truncate(count as usize);
+            Some(buf)
+        }
+        NVML_ERROR_NOT_FOUND => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+fn buffer_to_string(buf: &mut [c_char]) -> String {
+    if let Some(last) = buf.last_mut() {
+        *last = 0;
+    }
+    unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy().trim().to_string()
+}
+
+fn process_name(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| "?".to_string())
+}
+
+pub fn throttle_reason_names(mask: u64) -> Vec<String> {
+    THROTTLE_REASONS
+        .iter()
+        .filter(|(bit, _)| mask & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
@@ -0,0 +1,242 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::types::{LeakSuspect, OomKill};
+
+const MAX_OOM_ENTRIES: usize = 20;
+// Ignore small steady growth such as caches warming up
+const MIN_LEAK_GROWTH: u64 = 16 * 1024 * 1024;
+
+pub fn read_oom_kill_count() -> Option<u64> {
+    fs::read_to_string("/proc/vmstat")
+        .ok()
+        .and_then(|c| parse_vmstat_oom_kill(&c))
+}
+
+pub fn parse_vmstat_oom_kill(content: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Kernel OOM reports for the current boot, newest first.
+pub fn read_oom_kills() -> Vec<OomKill> {
+    let output = match Command::new("journalctl")
+        .args([
+            "-k",
+            "--no-pager",
+            "--output=short-iso",
+            "--lines=500",
+            "--grep=oom-kill:|Killed process",
+        ])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut kills = parse_oom_journal(&String::from_utf8_lossy(&output.stdout));
+    kills.reverse();
+    kills.truncate(MAX_OOM_ENTRIES);
+    kills
+}
+
+/// Pairs each `oom-kill:` summary line with the `Killed process` line that follows it.
+/// Lines look like `2024-05-01T10:00:00+0000 host kernel: Out of memory: Killed process ...`.
+pub fn parse_oom_journal(content: &str) -> Vec<OomKill> {
+    let mut kills = Vec::new();
+    let mut pending_cgroup: HashMap<u32, (String, bool)> = HashMap::new();
+
+    for line in content.lines() {
+        let timestamp = line.split_whitespace().next().unwrap_or("").to_string();
+        let message = match line.split_once("kernel: ") {
+            Some((_, msg)) => msg,
+            None => continue,
+        };
+
+        if let Some(summary) = message.strip_prefix("oom-kill:") {
+            let mut pid = None;
+            let mut memcg = String::new();
+            let mut global = false;
+            for field in summary.split(',') {
+                match field.split_once('=') {
+                    Some(("pid", v)) => pid = v.parse::<u32>().ok(),
+                    Some(("task_memcg", v)) => memcg = v.to_string(),
+                    None if field == "global_oom" => global = true,
+                    _ => {}
+                }
+            }
+            if let Some(pid) = pid {
+                pending_cgroup.insert(pid, (memcg, global));
+            }
+            continue;
+        }
+
+        let idx = match message.find("Killed process ") {
+            Some(idx) => idx,
+            None => continue,
+        };
+        let details = &message[idx + "Killed process ".len()..];
+        let pid = match details.split_whitespace().next().and_then(|p| p.parse::<u32>().ok()) {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let process = details
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_default();
+        let kb_field = |key: &str| {
+            details
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .find_map(|f| f.strip_prefix(key))
+                .and_then(|v| v.trim_end_matches("kB").parse::<u64>().ok())
+                .map(|kb| kb * 1024)
+                .unwrap_or(0)
+        };
+
+        let (cgroup, global) = pending_cgroup.remove(&pid).unwrap_or_else(|| {
+            (String::new(), !message.starts_with("Memory cgroup"))
+        });
+
+        kills.push(OomKill {
+            timestamp,
+            pid,
+            process,
+            cgroup,
+            global,
+            total_vm: kb_field("total-vm:"),
+            anon_rss: kb_field("anon-rss:"),
+            file_rss: kb_field("file-rss:"),
+            shmem_rss: kb_field("shmem-rss:"),
+        });
+    }
+
+    kills
+}
+
+/// Keeps per-process RSS samples and reports processes whose RSS never shrank
+/// across the whole window while growing by a meaningful amount.
+pub struct LeakDetector {
+    window: Duration,
+    samples: HashMap<u32, (String, VecDeque<(Instant, u64)>)>,
+}
+
+impl LeakDetector {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, now: Instant, processes: &[(u32, String, u64)]) -> Vec<LeakSuspect> {
+        let mut seen = std::collections::HashSet::new();
+
+        for (pid, name, rss) in processes {
+            seen.insert(*pid);
+            let entry = self
+                .samples
+                .entry(*pid)
+                .or_insert_with(|| (name.clone(), VecDeque::new()));
+            // A reused PID starts a fresh history
+            if entry.0 != *name {
+                *entry = (name.clone(), VecDeque::new());
+            }
+            entry.1.push_back((now, *rss));
+            // Keep one sample older than the window so the span can cover it fully
+            while entry.1.len() > 2 && now.duration_since(entry.1[1].0) >= self.window {
+                entry.1.pop_front();
+            }
+        }
+        self.samples.retain(|pid, _| seen.contains(pid));
+
+        let mut suspects: Vec<LeakSuspect> = self
+            .samples
+            .iter()
+            .filter_map(|(pid, (name, samples))| self.evaluate(*pid, name, samples))
+            .collect();
+        suspects.sort_by(|a, b| b.growth_rate.partial_cmp(&a.growth_rate).unwrap_or(std::cmp::Ordering::Equal));
+        suspects
+    }
+
+    fn evaluate(&self, pid: u32, name: &str, samples: &VecDeque<(Instant, u64)>) -> Option<LeakSuspect> {
+        let (first_time, first_rss) = *samples.front()?;
+        let (last_time, last_rss) = *samples.back()?;
+        let span = last_time.duration_since(first_time);
+        if span < self.window || last_rss < first_rss + MIN_LEAK_GROWTH {
+            return None;
+        }
+        let monotonic = samples
+            .iter()
+            .zip(samples.iter().skip(1))
+            .all(|((_, a), (_, b))| b >= a);
+        if !monotonic {
+            return None;
+        }
+
+        Some(LeakSuspect {
+            pid,
+            name: name.to_string(),
+            start_rss: first_rss,
+            current_rss: last_rss,
+            window_secs: span.as_secs(),
+            growth_rate: (last_rss - first_rss) as f64 / span.as_secs_f64().max(1.0) * 60.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vmstat_oom_kill() {
+        let content = "nr_free_pages 12345\noom_kill 3\nnuma_hit 42\n";
+        assert_eq!(parse_vmstat_oom_kill(content), Some(3));
+        assert_eq!(parse_vmstat_oom_kill("nr_free_pages 1\n"), None);
+    }
+
+    #[test]
+    fn test_parse_oom_journal() {
+        let content = "2024-05-01T10:00:00+0000 host kernel: oom-kill:constraint=CONSTRAINT_MEMCG,nodemask=(null),cpuset=/,mems_allowed=0,oom_memcg=/system.slice/leaky.service,task_memcg=/system.slice/leaky.service,task=leaky,pid=4242,uid=0\n\
+                       2024-05-01T10:00:00+0000 host kernel: Memory cgroup out of memory: Killed process 4242 (leaky) total-vm:2048000kB, anon-rss:1024000kB, file-rss:512kB, shmem-rss:0kB, UID:0 pgtables:2100kB oom_score_adj:0\n\
+                       2024-05-01T11:30:00+0000 host kernel: Out of memory: Killed process 777 (stress) total-vm:4096kB, anon-rss:2048kB, file-rss:0kB, shmem-rss:0kB, UID:1000 pgtables:40kB oom_score_adj:0\n";
+        let kills = parse_oom_journal(content);
+        assert_eq!(kills.len(), 2);
+        assert_eq!(kills[0].pid, 4242);
+        assert_eq!(kills[0].process, "leaky");
+        assert_eq!(kills[0].cgroup, "/system.slice/leaky.service");
+        assert!(!kills[0].global);
+        assert_eq!(kills[0].anon_rss, 1024000 * 1024);
+        assert_eq!(kills[0].file_rss, 512 * 1024);
+        assert_eq!(kills[1].process, "stress");
+        assert!(kills[1].global);
+        assert_eq!(kills[1].timestamp, "2024-05-01T11:30:00+0000");
+    }
+
+    #[test]
+    fn test_leak_detector() {
+        let mut detector = LeakDetector::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let mb = 1024 * 1024;
+
+        let mut suspects = Vec::new();
+        for step in 0..=6u64 {
+            let now = start + Duration::from_secs(step * 15);
+            suspects = detector.update(now, &[
+                (1, "leaky".to_string(), (100 + step * 10) * mb),
+                (2, "steady".to_string(), 200 * mb),
+                (3, "sawtooth".to_string(), if step % 2 == 0 { 100 * mb } else { 150 * mb }),
+            ]);
+        }
+
+        assert_eq!(suspects.len(), 1);
+        assert_eq!(suspects[0].pid, 1);
+        assert!(suspects[0].window_secs >= 60);
+        assert!(suspects[0].current_rss > suspects[0].start_rss);
+    }
+}
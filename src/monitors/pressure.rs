@@ -0,0 +1,109 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::{PressureStats, PsiResource, PsiValues};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+pub fn read_system_pressure() -> Option<PressureStats> {
+    read_pressure_files(Path::new("/proc/pressure"), "")
+}
+
+pub fn read_cgroup_pressure(cgroup_dir: &Path) -> Option<PressureStats> {
+    read_pressure_files(cgroup_dir, ".pressure")
+}
+
+pub fn service_pressure(service_name: &str) -> Option<PressureStats> {
+    let dir = Path::new(CGROUP_ROOT)
+        .join("system.slice")
+        .join(format!("{}.service", service_name));
+    read_cgroup_pressure(&dir)
+}
+
+pub fn container_pressure(container_id: &str) -> Option<PressureStats> {
+    container_cgroup(container_id).and_then(|dir| read_cgroup_pressure(&dir))
+}
+
+// systemd cgroup driver first, then the cgroupfs layout
+fn container_cgroup(container_id: &str) -> Option<PathBuf> {
+    let root = Path::new(CGROUP_ROOT);
+    [
+        root.join("system.slice").join(format!("docker-{}.scope", container_id)),
+        root.join("docker").join(container_id),
+    ]
+    .into_iter()
+    .find(|p| p.exists())
+}
+
+fn read_pressure_files(dir: &Path, suffix: &str) -> Option<PressureStats> {
+    let read = |name: &str| {
+        fs::read_to_string(dir.join(format!("{}{}", name, suffix)))
+            .ok()
+            .and_then(|c| parse_psi(&c))
+    };
+
+    let stats = PressureStats {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    };
+
+    if stats.cpu.is_none() && stats.memory.is_none() && stats.io.is_none() {
+        None
+    } else {
+        Some(stats)
+    }
+}
+
+/// Parses the two-line PSI format:
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0`
+pub fn parse_psi(content: &str) -> Option<PsiResource> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let mut values = PsiValues::default();
+        for part in parts {
+            if let Some((key, val)) = part.split_once('=') {
+                match key {
+                    "avg10" => values.avg10 = val.parse().unwrap_or(0.0),
+                    "avg60" => values.avg60 = val.parse().unwrap_or(0.0),
+                    "avg300" => values.avg300 = val.parse().unwrap_or(0.0),
+                    "total" => values.total_us = val.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+        match kind {
+            Some("some") => some = Some(values),
+            Some("full") => full = Some(values),
+            _ => {}
+        }
+    }
+
+    some.map(|some| PsiResource { some, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_psi() {
+        let content = "some avg10=1.50 avg60=0.75 avg300=0.10 total=123456\n\
+                       full avg10=0.20 avg60=0.05 avg300=0.00 total=7890\n";
+        let psi = parse_psi(content).unwrap();
+        assert_eq!(psi.some.avg10, 1.50);
+        assert_eq!(psi.some.total_us, 123456);
+        assert_eq!(psi.full.unwrap().avg60, 0.05);
+    }
+
+    #[test]
+    fn test_parse_psi_cpu_without_full() {
+        let psi = parse_psi("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        assert!(psi.full.is_none());
+        assert!(parse_psi("").is_none());
+    }
+}
@@ -0,0 +1,152 @@
+use std::ffi::CString;
+use std::process::Command;
+
+use crate::types::QuotaEntry;
+
+const QUOTA_OPTIONS: [&str; 10] = [
+    "usrquota", "grpquota", "prjquota", "quota", "usrjquota", "grpjquota",
+    "uquota", "gquota", "pquota", "uqnoenforce",
+];
+
+/// (total, used, free) inodes from statvfs. Filesystems without a fixed inode
+/// table (btrfs, many FUSE mounts) report zero and yield `None`.
+pub fn inode_usage(mount_point: &str) -> Option<(u64, u64, u64)> {
+    let path = CString::new(mount_point).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 || stat.f_files == 0 {
+        return None;
+    }
+    let total = stat.f_files as u64;
+    let free = stat.f_ffree as u64;
+    Some((total, total.saturating_sub(free), free))
+}
+
+/// Mount points whose mount or superblock options enable user, group or project quotas.
+pub fn quota_mounts(mountinfo: &str) -> Vec<String> {
+    let mut mounts: Vec<String> = mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (pre, post) = line.split_once(" - ")?;
+            let pre: Vec<&str> = pre.split_whitespace().collect();
+            let super_opts = post.split_whitespace().nth(2).unwrap_or("");
+            let enabled = pre
+                .get(5)
+                .into_iter()
+                .chain(std::iter::once(&super_opts))
+                .flat_map(|opts| opts.split(','))
+                .any(|opt| QUOTA_OPTIONS.contains(&opt.split('=').next().unwrap_or("")));
+            if !enabled {
+                return None;
+            }
+            pre.get(4).map(|m| crate::monitors::blockdev::unescape_mount(m))
+        })
+        .collect();
+    // Bind mounts repeat a mount point further down; keep the first occurrence
+    let mut seen = std::collections::HashSet::new();
+    mounts.retain(|m| seen.insert(m.clone()));
+    mounts
+}
+
+/// Runs repquota for every quota-enabled mount, skipping mounts it fails on.
+/// repquota needs root; when every mount fails the first error is returned so
+/// the GUI can explain why the table is empty.
+pub fn read_quotas() -> Result<Vec<QuotaEntry>, String> {
+    let mountinfo = std::fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    let mounts = quota_mounts(&mountinfo);
+    let mut entries = Vec::new();
+    let mut first_error = None;
+    let mut failed = 0;
+    for mount in &mounts {
+        let output = Command::new("repquota")
+            .args(["-u", "-g", "-p", mount])
+            .output()
+            .map_err(|e| format!("repquota not available: {}", e))?;
+        if !output.status.success() {
+            failed += 1;
+            first_error.get_or_insert_with(|| String::from_utf8_lossy(&output.stderr).trim().to_string());
+            continue;
+        }
+        entries.extend(parse_repquota(&String::from_utf8_lossy(&output.stdout), mount));
+    }
+    match first_error {
+        Some(e) if failed == mounts.len() => Err(e),
+        _ => Ok(entries),
+    }
+}
+
+/// Parses `repquota -p` output. Data lines look like
+/// `alice +- 1048580 1048576 2097152 604800 120 0 0 0` with block counts in KiB.
+pub fn parse_repquota(content: &str, mount_point: &str) -> Vec<QuotaEntry> {
+    let mut kind = String::new();
+    let mut entries = Vec::new();
+
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("*** Report for ") {
+            kind = rest.split_whitespace().next().unwrap_or("").to_string();
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 || fields[1].len() != 2 || !fields[1].chars().all(|c| c == '-' || c == '+') {
+            continue;
+        }
+        let num = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        entries.push(QuotaEntry {
+            mount_point: mount_point.to_string(),
+            kind: kind.clone(),
+            name: fields[0].to_string(),
+            space_used: num(2) * 1024,
+            space_soft: num(3) * 1024,
+            space_hard: num(4) * 1024,
+            files_used: num(6),
+            files_soft: num(7),
+            files_hard: num(8),
+            over_limit: fields[1].contains('+'),
+        });
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_mounts() {
+        let content = "29 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw\n\
+                       30 29 8:3 / /home rw,relatime shared:2 - ext4 /dev/sda3 rw,usrquota,grpquota\n\
+                       31 29 8:4 / /srv/build\\040cache rw - xfs /dev/sda4 rw,attr2,inode64,prjquota\n\
+                       32 29 8:5 / /data rw - ext4 /dev/sda5 rw,usrjquota=aquota.user,jqfmt=vfsv1\n\
+                       33 29 8:3 /alice /home rw - ext4 /dev/sda3 rw,usrquota,grpquota\n";
+        assert_eq!(quota_mounts(content), vec!["/home", "/srv/build cache", "/data"]);
+    }
+
+    #[test]
+    fn test_parse_repquota() {
+        let content = "*** Report for user quotas on device /dev/sda3\n\
+                       Block grace time: 7days; Inode grace time: 7days\n\
+                       \x20                       Block limits                File limits\n\
+                       User            used    soft    hard  grace    used  soft  hard  grace\n\
+                       ----------------------------------------------------------------------\n\
+                       root      --      20       0       0      0       2     0     0      0\n\
+                       alice     +-  1048580 1048576 2097152 1717000000  120     0     0      0\n\
+                       \n\
+                       *** Report for group quotas on device /dev/sda3\n\
+                       Group           used    soft    hard  grace    used  soft  hard  grace\n\
+                       ----------------------------------------------------------------------\n\
+                       builders  -+   40960       0       0      0   99000 50000 100000 1717000000\n";
+        let entries = parse_repquota(content, "/home");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].kind, "user");
+        assert_eq!(entries[0].name, "root");
+        assert!(!entries[0].over_limit);
+        assert_eq!(entries[1].space_used, 1048580 * 1024);
+        assert_eq!(entries[1].space_hard, 2097152 * 1024);
+        assert!(entries[1].over_limit);
+        assert_eq!(entries[2].kind, "group");
+        assert_eq!(entries[2].files_used, 99000);
+        assert_eq!(entries[2].files_hard, 100000);
+        assert!(entries[2].over_limit);
+        assert_eq!(entries[2].mount_point, "/home");
+    }
+}
@@ -0,0 +1,610 @@
+// A subset of lm-sensors' sensors.conf: `chip`, `label`, `ignore`, `compute`
+// and `set`, applied to the readings from hwmon.rs.
+//
+//     chip "nct6775-*"
+//         label in3 "+3.3V"
+//         compute in3 @*2, @/2
+//         set in3_min 3.1
+//         ignore fan5
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::types::SensorInfo;
+
+const CONFIG_FILE: &str = "sensors.conf";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Value,
+    Number(f64),
+    Neg(Box<Expr>),
+    Exp(Box<Expr>),
+    Ln(Box<Expr>),
+    Binary(Box<Expr>, char, Box<Expr>),
+}
+
+impl Expr {
+    pub fn parse(text: &str) -> Result<Expr, String> {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
+            return Err("empty expression".to_string());
+        }
+        let mut pos = 0;
+        let expr = parse_sum(&chars, &mut pos)?;
+        if pos < chars.len() {
+            return Err(format!("unexpected '{}' in '{}'", chars[pos], text.trim()));
+        }
+        Ok(expr)
+    }
+
+    pub fn eval(&self, value: f64) -> f64 {
+        match self {
+            Expr::Value => value,
+            Expr::Number(n) => *n,
+            Expr::Neg(e) => -e.eval(value),
+            Expr::Exp(e) => e.eval(value).exp(),
+            Expr::Ln(e) => e.eval(value).ln(),
+            Expr::Binary(a, op, b) => {
+                let (a, b) = (a.eval(value), b.eval(value));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    _ => a / b,
+                }
+            }
+        }
+    }
+
+    fn uses_value(&self) -> bool {
+        match self {
+            Expr::Value => true,
+            Expr::Number(_) => false,
+            Expr::Neg(e) | Expr::Exp(e) | Expr::Ln(e) => e.uses_value(),
+            Expr::Binary(a, _, b) => a.uses_value() || b.uses_value(),
+        }
+    }
+}
+
+fn parse_sum(chars: &[char], pos: &mut usize) -> Result<Expr, String> {
+    let mut expr = parse_product(chars, pos)?;
+    while let Some(&op) = chars.get(*pos).filter(|c| **c == '+' || **c == '-') {
+        *pos += 1;
+        expr = Expr::Binary(Box::new(expr), op, Box::new(parse_product(chars, pos)?));
+    }
+    Ok(expr)
+}
+
+fn parse_product(chars: &[char], pos: &mut usize) -> Result<Expr, String> {
+    let mut expr = parse_factor(chars, pos)?;
+    while let Some(&op) = chars.get(*pos).filter(|c| **c == '*' || **c == '/') {
+        *pos += 1;
+        expr = Expr::Binary(Box::new(expr), op, Box::new(parse_factor(chars, pos)?));
+    }
+    Ok(expr)
+}
+
+// `^` is e^x and `` ` `` is ln(x), as in lm-sensors
+fn parse_factor(chars: &[char], pos: &mut usize) -> Result<Expr, String> {
+    let c = *chars.get(*pos).ok_or("expression ends early")?;
+    *pos += 1;
+    match c {
+        '@' => Ok(Expr::Value),
+        '-' => Ok(Expr::Neg(Box::new(parse_factor(chars, pos)?))),
+        '^' => Ok(Expr::Exp(Box::new(parse_factor(chars, pos)?))),
+        '`' => Ok(Expr::Ln(Box::new(parse_factor(chars, pos)?))),
+        '(' => {
+            let expr = parse_sum(chars, pos)?;
+            if chars.get(*pos) != Some(&')') {
+                return Err("missing ')'".to_string());
+            }
+            *pos += 1;
+            Ok(expr)
+        }
+        c if c.is_ascii_digit() || c == '.' => {
+            let start = *pos - 1;
+            while chars.get(*pos).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+                *pos += 1;
+            }
+            let number: String = chars[start..*pos].iter().collect();
+            number.parse().map(Expr::Number).map_err(|_| format!("bad number '{}'", number))
+        }
+        c => Err(format!("unexpected '{}'", c)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+    Min,
+    Max,
+    Crit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    Label { id: String, text: String },
+    Ignore { id: String },
+    // The reverse expression is kept for lm-sensors compatibility; limits are never written back
+    Compute { id: String, forward: String, reverse: Option<String>, expr: Expr },
+    Set { id: String, limit: Limit, value: f64 },
+    Comment(String),
+}
+
+impl Statement {
+    fn id(&self) -> Option<&str> {
+        match self {
+            Statement::Label { id, .. }
+            | Statement::Ignore { id }
+            | Statement::Compute { id, .. }
+            | Statement::Set { id, .. } => Some(id),
+            Statement::Comment(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChipSection {
+    // Empty for comments above the first `chip` line
+    pub patterns: Vec<String>,
+    pub statements: Vec<Statement>,
+}
+
+/// Everything the config says about one sensor, later statements winning.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SensorSettings {
+    pub label: Option<String>,
+    pub hidden: bool,
+    pub compute: Option<(String, Option<String>)>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SensorsConfig {
+    pub sections: Vec<ChipSection>,
+}
+
+impl SensorsConfig {
+    pub fn parse(content: &str) -> Result<SensorsConfig, String> {
+        let mut config = SensorsConfig::default();
+        for (n, line) in content.lines().enumerate() {
+            let err = |msg: String| format!("line {}: {}", n + 1, msg);
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                match config.sections.last_mut() {
+                    Some(section) => section.statements.push(Statement::Comment(trimmed.to_string())),
+                    None => config.sections.push(ChipSection { patterns: Vec::new(), statements: vec![Statement::Comment(trimmed.to_string())] }),
+                }
+                continue;
+            }
+
+            let (keyword, rest) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+            let rest = strip_comment(rest).trim();
+            if keyword == "chip" {
+                let patterns = tokenize(rest).map_err(err)?;
+                if patterns.is_empty() {
+                    return Err(err("chip needs at least one name".to_string()));
+                }
+                config.sections.push(ChipSection { patterns, statements: Vec::new() });
+                continue;
+            }
+            // `bus` lines map i2c adapters for libsensors and mean nothing here
+            if keyword == "bus" {
+                continue;
+            }
+
+            let statement = parse_statement(keyword, rest).map_err(err)?;
+            match config.sections.last_mut() {
+                Some(section) if !section.patterns.is_empty() => section.statements.push(statement),
+                _ => return Err(err(format!("'{}' before any chip line", keyword))),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            if !section.patterns.is_empty() {
+                let names: Vec<String> = section.patterns.iter().map(|p| format!("\"{}\"", p)).collect();
+                out.push_str(&format!("chip {}\n", names.join(" ")));
+            }
+            let indent = if section.patterns.is_empty() { "" } else { "    " };
+            for statement in &section.statements {
+                let line = match statement {
+                    Statement::Comment(text) if text.is_empty() => String::new(),
+                    Statement::Comment(text) => format!("{}{}", indent, text),
+                    Statement::Label { id, text } => format!("{}label {} \"{}\"", indent, id, text.replace('\\', "\\\\").replace('"', "\\\"")),
+                    Statement::Ignore { id } => format!("{}ignore {}", indent, id),
+                    Statement::Compute { id, forward, reverse: Some(reverse), .. } => format!("{}compute {} {}, {}", indent, id, forward, reverse),
+                    Statement::Compute { id, forward, reverse: None, .. } => format!("{}compute {} {}", indent, id, forward),
+                    Statement::Set { id, limit, value } => format!("{}set {}_{} {}", indent, id, limit_suffix(*limit), value),
+                };
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    pub fn settings_for(&self, chip: &str, id: &str) -> SensorSettings {
+        let mut settings = SensorSettings::default();
+        let statements = self.sections.iter()
+            .filter(|s| s.patterns.iter().any(|p| glob_match(p, chip)))
+            .flat_map(|s| s.statements.iter())
+            .filter(|s| s.id() == Some(id));
+        for statement in statements {
+            match statement {
+                Statement::Label { text, .. } => settings.label = Some(text.clone()),
+                Statement::Ignore { .. } => settings.hidden = true,
+                Statement::Compute { forward, reverse, .. } => settings.compute = Some((forward.clone(), reverse.clone())),
+                Statement::Set { limit: Limit::Min, value, .. } => settings.min = Some(*value),
+                Statement::Set { limit: Limit::Max, value, .. } => settings.max = Some(*value),
+                Statement::Set { limit: Limit::Crit, value, .. } => settings.crit = Some(*value),
+                Statement::Comment(_) => {}
+            }
+        }
+        settings
+    }
+
+    /// Replaces what a `chip "<chip>"` section says about `id`, adding the section if needed.
+    pub fn set_sensor(&mut self, chip: &str, id: &str, settings: &SensorSettings) -> Result<(), String> {
+        let mut statements = Vec::new();
+        if let Some(text) = &settings.label {
+            statements.push(Statement::Label { id: id.to_string(), text: text.clone() });
+        }
+        if let Some((forward, reverse)) = &settings.compute {
+            statements.push(Statement::Compute {
+                id: id.to_string(),
+                expr: Expr::parse(forward)?,
+                forward: forward.clone(),
+                reverse: reverse.clone(),
+            });
+        }
+        for (limit, value) in [(Limit::Min, settings.min), (Limit::Max, settings.max), (Limit::Crit, settings.crit)] {
+            if let Some(value) = value {
+                statements.push(Statement::Set { id: id.to_string(), limit, value });
+            }
+        }
+        if settings.hidden {
+            statements.push(Statement::Ignore { id: id.to_string() });
+        }
+
+        let index = match self.sections.iter().position(|s| s.patterns.len() == 1 && s.patterns[0] == chip) {
+            Some(index) => index,
+            None => {
+                self.sections.push(ChipSection { patterns: vec![chip.to_string()], statements: Vec::new() });
+                self.sections.len() - 1
+            }
+        };
+        let section = &mut self.sections[index];
+        section.statements.retain(|s| s.id() != Some(id));
+        section.statements.extend(statements);
+        Ok(())
+    }
+
+    /// Other sections whose chip patterns match `chip` and that say something about
+    /// `id`. lm-sensors has no way to undo a label or `ignore` from an earlier or
+    /// wildcard section, so clearing those in the exact section does not win.
+    pub fn other_sources(&self, chip: &str, id: &str) -> Vec<String> {
+        self.sections.iter()
+            .filter(|s| !(s.patterns.len() == 1 && s.patterns[0] == chip))
+            .filter(|s| s.patterns.iter().any(|p| glob_match(p, chip)))
+            .filter(|s| s.statements.iter().any(|st| st.id() == Some(id)))
+            .map(|s| s.patterns.iter().map(|p| format!("\"{}\"", p)).collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    /// Relabels, rescales and overrides limits; ignored sensors are kept but marked hidden.
+    pub fn apply(&self, sensors: &mut [SensorInfo]) {
+        for sensor in sensors.iter_mut() {
+            let settings = self.settings_for(&sensor.chip, &sensor.id);
+            if let Some(label) = settings.label {
+                sensor.label = label;
+            }
+            sensor.hidden = settings.hidden;
+            if let Some(expr) = self.compute_expr(&sensor.chip, &sensor.id) {
+                sensor.value = expr.eval(sensor.value);
+                sensor.temp = sensor.value as f32;
+                for limit in [&mut sensor.min, &mut sensor.max, &mut sensor.critical] {
+                    *limit = limit.map(|v| expr.eval(v as f64) as f32);
+                }
+            }
+            // `set` limits are already in computed units
+            sensor.min = settings.min.map(|v| v as f32).or(sensor.min);
+            sensor.max = settings.max.map(|v| v as f32).or(sensor.max);
+            sensor.critical = settings.crit.map(|v| v as f32).or(sensor.critical);
+        }
+    }
+
+    fn compute_expr(&self, chip: &str, id: &str) -> Option<&Expr> {
+        self.sections.iter()
+            .filter(|s| s.patterns.iter().any(|p| glob_match(p, chip)))
+            .flat_map(|s| s.statements.iter())
+            .filter_map(|s| match s {
+                Statement::Compute { id: sid, expr, .. } if sid == id => Some(expr),
+                _ => None,
+            })
+            .next_back()
+    }
+}
+
+fn parse_statement(keyword: &str, rest: &str) -> Result<Statement, String> {
+    let (id, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if id.is_empty() {
+        return Err(format!("'{}' needs a sensor name", keyword));
+    }
+    let args = args.trim();
+    match keyword {
+        "label" => {
+            let text = tokenize(args)?.into_iter().next().ok_or("label needs a text")?;
+            Ok(Statement::Label { id: id.to_string(), text })
+        }
+        "ignore" => Ok(Statement::Ignore { id: id.to_string() }),
+        "compute" => {
+            let (forward, reverse) = match args.split_once(',') {
+                Some((f, r)) => (f.trim(), Some(r.trim())),
+                None => (args, None),
+            };
+            let expr = Expr::parse(forward)?;
+            if let Some(reverse) = reverse {
+                Expr::parse(reverse)?;
+            }
+            Ok(Statement::Compute {
+                id: id.to_string(),
+                forward: forward.to_string(),
+                reverse: reverse.map(str::to_string),
+                expr,
+            })
+        }
+        "set" => {
+            let (sensor, suffix) = id.rsplit_once('_').ok_or_else(|| format!("'{}' is not <sensor>_<limit>", id))?;
+            let limit = match suffix {
+                "min" => Limit::Min,
+                "max" => Limit::Max,
+                "crit" => Limit::Crit,
+                _ => return Err(format!("unsupported limit '{}', use min, max or crit", suffix)),
+            };
+            let expr = Expr::parse(args)?;
+            if expr.uses_value() {
+                return Err("set takes a constant, not '@'".to_string());
+            }
+            Ok(Statement::Set { id: sensor.to_string(), limit, value: expr.eval(0.0) })
+        }
+        _ => Err(format!("unknown statement '{}'", keyword)),
+    }
+}
+
+fn limit_suffix(limit: Limit) -> &'static str {
+    match limit {
+        Limit::Min => "min",
+        Limit::Max => "max",
+        Limit::Crit => "crit",
+    }
+}
+
+fn strip_comment(s: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if std::mem::take(&mut escaped) {
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &s[..i],
+            _ => {}
+        }
+    }
+    s
+}
+
+/// Splits on whitespace, keeping "quoted strings" (with \" escapes) together.
+fn tokenize(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => token.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err("unterminated quote".to_string()),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek().filter(|c| !c.is_whitespace()) {
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+/// `*` matches any run of characters, as in lm-sensors chip names.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else { return false };
+            (0..=name.len()).filter(|&i| name.is_char_boundary(i)).any(|i| glob_match(rest, &name[i..]))
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/puls-g3/sensors.conf`, defaulting to `~/.config/puls-g3`.
+pub fn config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("puls-g3").join(CONFIG_FILE))
+}
+
+/// Reads the config file; a missing file is an empty config.
+pub fn load() -> Result<SensorsConfig, String> {
+    let Some(path) = config_path() else { return Ok(SensorsConfig::default()) };
+    match fs::read_to_string(&path) {
+        Ok(content) => SensorsConfig::parse(&content).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SensorsConfig::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+pub fn save(config: &SensorsConfig) -> Result<(), String> {
+    let path = config_path().ok_or("no home directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let tmp = path.with_extension("conf.tmp");
+    fs::write(&tmp, config.to_file_string()).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// The config as the monitor sees it, re-read whenever the file changes so
+/// edits from the Sensors tab or a text editor apply on the next refresh.
+#[derive(Default)]
+pub struct SensorsConfigWatcher {
+    config: SensorsConfig,
+    modified: Option<SystemTime>,
+    error: Option<String>,
+}
+
+impl SensorsConfigWatcher {
+    /// Returns true if the config changed since the last call.
+    pub fn refresh(&mut self) -> bool {
+        let modified = config_path().and_then(|p| fs::metadata(p).ok()).and_then(|m| m.modified().ok());
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        match load() {
+            Ok(config) => {
+                self.config = config;
+                self.error = None;
+            }
+            // Keep the last good config while the file is being edited
+            Err(e) => self.error = Some(e),
+        }
+        true
+    }
+
+    pub fn config(&self) -> &SensorsConfig {
+        &self.config
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expressions() {
+        let expr = Expr::parse("(@ - 0.5) * 2 + -1").unwrap();
+        assert_eq!(expr.eval(3.0), 4.0);
+        assert_eq!(Expr::parse("@/ ( 1 + 2 )").unwrap().eval(9.0), 3.0);
+        assert!((Expr::parse("`^@").unwrap().eval(1.5) - 1.5).abs() < 1e-9);
+        assert!(Expr::parse("@ *").is_err());
+        assert!(Expr::parse("(@ + 1").is_err());
+        assert!(Expr::parse("@ x 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_apply_and_roundtrip() {
+        let content = "\
+# Board: X570
+chip \"nct6798-isa-*\" \"nct6775-*\"
+    label in3 \"+3.3V\"   # ATX rail
+    compute in3 @*2, @/2
+    set in3_min 3.3 * 0.95
+    set in3_max 3.3 * 1.05
+    ignore fan5
+
+chip \"k10temp-pci-00c3\"
+    label temp1 \"CPU\"
+    set temp1_max 85
+";
+        let config = SensorsConfig::parse(content).unwrap();
+        let sensor = |chip: &str, id: &str, value: f64| SensorInfo {
+            chip: chip.to_string(),
+            id: id.to_string(),
+            label: id.to_string(),
+            value,
+            max: Some(1.8),
+            ..Default::default()
+        };
+        let mut sensors = vec![
+            sensor("nct6798-isa-0290", "in3", 1.68),
+            sensor("nct6798-isa-0290", "fan5", 0.0),
+            sensor("k10temp-pci-00c3", "temp1", 51.0),
+            sensor("it8688-isa-0a40", "in3", 1.68),
+        ];
+        config.apply(&mut sensors);
+
+        assert_eq!(sensors[0].label, "+3.3V");
+        assert!((sensors[0].value - 3.36).abs() < 1e-9);
+        assert!((sensors[0].min.unwrap() - 3.135).abs() < 1e-4);
+        assert!((sensors[0].max.unwrap() - 3.465).abs() < 1e-4);
+        assert!(sensors[1].hidden);
+        assert_eq!((sensors[2].label.as_str(), sensors[2].max), ("CPU", Some(85.0)));
+        assert_eq!((sensors[3].label.as_str(), sensors[3].value), ("in3", 1.68));
+
+        assert_eq!(SensorsConfig::parse(&config.to_file_string()).unwrap(), config);
+
+        assert_eq!(SensorsConfig::parse("label in1 \"x\"\n").unwrap_err(), "line 1: 'label' before any chip line");
+        assert!(SensorsConfig::parse("chip \"a\"\n  set in1_hyst 3\n").unwrap_err().starts_with("line 2:"));
+        assert!(SensorsConfig::parse("chip \"a\"\n  frobnicate in1\n").is_err());
+    }
+
+    #[test]
+    fn test_set_sensor() {
+        let mut config = SensorsConfig::parse("chip \"nct6775-*\"\n    ignore in1\n").unwrap();
+        let settings = SensorSettings {
+            label: Some("Vcore".to_string()),
+            compute: Some(("@ * 1.5".to_string(), None)),
+            max: Some(1.45),
+            ..Default::default()
+        };
+        config.set_sensor("nct6775-isa-0290", "in0", &settings).unwrap();
+        assert_eq!(config.settings_for("nct6775-isa-0290", "in0"), settings);
+        assert!(config.settings_for("nct6775-isa-0290", "in1").hidden);
+
+        config.set_sensor("nct6775-isa-0290", "in0", &SensorSettings { hidden: true, ..Default::default() }).unwrap();
+        assert_eq!(
+            config.to_file_string(),
+            "chip \"nct6775-*\"\n    ignore in1\nchip \"nct6775-isa-0290\"\n    ignore in0\n"
+        );
+        assert!(config.set_sensor("x", "in0", &SensorSettings { compute: Some(("@*".to_string(), None)), ..Default::default() }).is_err());
+
+        // The wildcard ignore still applies after unticking Hide in the exact section
+        config.set_sensor("nct6775-isa-0290", "in1", &SensorSettings::default()).unwrap();
+        assert!(config.settings_for("nct6775-isa-0290", "in1").hidden);
+        assert_eq!(config.other_sources("nct6775-isa-0290", "in1"), vec!["\"nct6775-*\""]);
+        assert!(config.other_sources("nct6775-isa-0290", "in0").is_empty());
+
+        let label = SensorSettings { label: Some(r#"C:\ "5V" #1"#.to_string()), ..Default::default() };
+        config.set_sensor("nct6775-isa-0290", "in2", &label).unwrap();
+        let reparsed = SensorsConfig::parse(&config.to_file_string()).unwrap();
+        assert_eq!(reparsed.settings_for("nct6775-isa-0290", "in2"), label);
+
+        assert!(glob_match("*-isa-*", "nct6775-isa-0290"));
+        assert!(!glob_match("nct6775-*", "it8688-isa-0a40"));
+    }
+}
@@ -0,0 +1,460 @@
+use std::fs;
+use std::process::Command;
+
+use crate::types::{DriveHealth, HealthVerdict};
+
+// NVMe data units are thousands of 512-byte blocks
+const NVME_DATA_UNIT: u64 = 512 * 1000;
+const NVME_LOG_SMART: u32 = 0x02;
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+// _IOWR('N', 0x41, struct nvme_admin_cmd)
+const NVME_IOCTL_ADMIN_CMD: libc::c_ulong = 0xC048_4E41;
+// Exit status smartctl uses when `-n standby` skips a sleeping drive. Bit 0 means
+// a command line error, which never comes with data, so it can't mask a result.
+const SMARTCTL_STANDBY_EXIT: i32 = 9;
+// HDIO_DRIVE_CMD takes {command, sector, feature, count} followed by the returned data
+const HDIO_DRIVE_CMD: libc::c_ulong = 0x031f;
+const ATA_SMART: u8 = 0xB0;
+const ATA_SMART_READ_VALUES: u8 = 0xD0;
+const ATA_CHECK_POWER_MODE: u8 = 0xE5;
+const ATA_SMART_ATTRIBUTES: usize = 30;
+
+#[repr(C)]
+#[derive(Default)]
+struct NvmeAdminCmd {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+/// Whole-disk names (`sda`, `nvme0n1`) that SMART data can be read for.
+pub fn list_drives() -> Vec<String> {
+    let mut drives: Vec<String> = fs::read_dir("/sys/block")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with("sr") && crate::monitors::diskstats::is_physical_disk(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    drives.sort();
+    drives
+}
+
+/// smartctl covers SATA and NVMe; the NVMe log page and ATA SMART READ DATA ioctls
+/// (root) and sysfs are fallbacks for systems without smartmontools. `None` when
+/// the drive is in standby, which neither smartctl nor the ATA path wakes it from.
+pub fn read_drive_health(drive: &str) -> Option<DriveHealth> {
+    let direct = match read_smartctl(drive) {
+        Probe::Unavailable => read_ata_ioctl(drive),
+        probe => probe,
+    };
+    let found = match direct {
+        Probe::Asleep => return None,
+        Probe::Health(health) => Some(*health),
+        Probe::Unavailable => None,
+    };
+    let mut health = found
+        .or_else(|| read_nvme_ioctl(drive))
+        .unwrap_or_else(|| read_sysfs(drive));
+    health.device = drive.to_string();
+    if health.model.is_empty() {
+        health.model = fs::read_to_string(format!("/sys/block/{}/device/model", drive))
+            .map(|m| m.trim().to_string())
+            .unwrap_or_default();
+    }
+    evaluate(&mut health);
+    Some(health)
+}
+
+enum Probe {
+    Asleep,
+    Health(Box<DriveHealth>),
+    Unavailable,
+}
+
+fn read_smartctl(drive: &str) -> Probe {
+    let standby = format!("standby,{}", SMARTCTL_STANDBY_EXIT);
+    let Ok(output) = Command::new("smartctl")
+        .args(["--json", "-a", "-n", &standby, &format!("/dev/{}", drive)])
+        .output()
+    else {
+        return Probe::Unavailable;
+    };
+    if output.status.code() == Some(SMARTCTL_STANDBY_EXIT) {
+        return Probe::Asleep;
+    }
+    // smartctl encodes warnings in the exit status bits, so judge by the JSON instead
+    match parse_smartctl_json(&String::from_utf8_lossy(&output.stdout)) {
+        Some(health) => Probe::Health(Box::new(health)),
+        None => Probe::Unavailable,
+    }
+}
+
+pub fn parse_smartctl_json(content: &str) -> Option<DriveHealth> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let smart_passed = json["smart_status"]["passed"].as_bool();
+    let nvme = &json["nvme_smart_health_information_log"];
+    let ata = json["ata_smart_attributes"]["table"].as_array();
+    if smart_passed.is_none() && nvme.is_null() && ata.is_none() {
+        return None;
+    }
+
+    let mut health = DriveHealth {
+        source: "smartctl".to_string(),
+        model: json["model_name"].as_str().unwrap_or("").to_string(),
+        serial: json["serial_number"].as_str().unwrap_or("").to_string(),
+        firmware: json["firmware_version"].as_str().unwrap_or("").to_string(),
+        smart_passed,
+        power_on_hours: json["power_on_time"]["hours"].as_u64(),
+        power_cycles: json["power_cycle_count"].as_u64(),
+        temperature: json["temperature"]["current"].as_f64().map(|t| t as f32),
+        ..Default::default()
+    };
+
+    if !nvme.is_null() {
+        health.critical_warning = nvme["critical_warning"].as_u64().map(|v| v as u8);
+        health.available_spare = nvme["available_spare"].as_u64().map(|v| v as u8);
+        health.spare_threshold = nvme["available_spare_threshold"].as_u64().map(|v| v as u8);
+        health.percentage_used = nvme["percentage_used"].as_u64().map(|v| v as u8);
+        health.data_read = nvme["data_units_read"].as_u64().map(|v| v * NVME_DATA_UNIT);
+        health.data_written = nvme["data_units_written"].as_u64().map(|v| v * NVME_DATA_UNIT);
+        health.unsafe_shutdowns = nvme["unsafe_shutdowns"].as_u64();
+        health.media_errors = nvme["media_errors"].as_u64();
+        health.power_on_hours = health.power_on_hours.or(nvme["power_on_hours"].as_u64());
+        health.power_cycles = health.power_cycles.or(nvme["power_cycles"].as_u64());
+    }
+
+    if let Some(table) = ata {
+        let raw = |id: u64| {
+            table
+                .iter()
+                .find(|a| a["id"].as_u64() == Some(id))
+                .and_then(|a| a["raw"]["value"].as_u64())
+        };
+        health.reallocated_sectors = raw(5);
+        health.pending_sectors = raw(197);
+        health.uncorrectable_sectors = raw(198);
+        health.unsafe_shutdowns = raw(192).or(raw(174));
+        let sector = json["logical_block_size"].as_u64().unwrap_or(512);
+        health.data_written = raw(241).map(|lbas| lbas * sector);
+        health.data_read = raw(242).map(|lbas| lbas * sector);
+        // Normalized SSD wear indicators count down from 100
+        health.percentage_used = table
+            .iter()
+            .find(|a| matches!(a["id"].as_u64(), Some(177) | Some(231) | Some(233)))
+            .and_then(|a| a["value"].as_u64())
+            .map(|v| 100u64.saturating_sub(v).min(255) as u8);
+    }
+
+    let last_test = json["ata_smart_self_test_log"]["standard"]["table"]
+        .as_array()
+        .and_then(|t| t.first())
+        .map(|t| (t["type"]["string"].as_str(), t["status"]["string"].as_str(), t["status"]["passed"].as_bool()))
+        .or_else(|| {
+            json["nvme_self_test_log"]["table"]
+                .as_array()
+                .and_then(|t| t.first())
+                .map(|t| (
+                    t["self_test_code"]["string"].as_str(),
+                    t["self_test_result"]["string"].as_str(),
+                    t["self_test_result"]["value"].as_u64().map(|v| v == 0),
+                ))
+        });
+    if let Some((kind, status, passed)) = last_test {
+        health.self_test = Some(format!("{}: {}", kind.unwrap_or("Test"), status.unwrap_or("unknown")));
+        health.self_test_passed = passed;
+    }
+
+    Some(health)
+}
+
+fn read_nvme_ioctl(drive: &str) -> Option<DriveHealth> {
+    use std::os::unix::io::AsRawFd;
+
+    // nvme0n1 -> /dev/nvme0 (admin commands go to the controller node)
+    let controller = drive.strip_prefix("nvme")?.split('n').next()?;
+    let file = fs::File::open(format!("/dev/nvme{}", controller)).ok()?;
+
+    let mut log = [0u8; 512];
+    let mut cmd = NvmeAdminCmd {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        nsid: 0xFFFF_FFFF,
+        addr: log.as_mut_ptr() as u64,
+        data_len: log.len() as u32,
+        cdw10: NVME_LOG_SMART | (((log.len() as u32 / 4) - 1) << 16),
+        ..Default::default()
+    };
+    // SAFETY: cmd points at a correctly sized nvme_admin_cmd and addr at a live 512-byte buffer
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), NVME_IOCTL_ADMIN_CMD, &mut cmd as *mut NvmeAdminCmd) };
+    if ret != 0 {
+        return None;
+    }
+    Some(parse_nvme_smart_log(&log))
+}
+
+/// Decodes the 512-byte NVMe SMART / Health Information log page (log id 02h).
+pub fn parse_nvme_smart_log(log: &[u8; 512]) -> DriveHealth {
+    let u128_at = |off: usize| {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&log[off..off + 16]);
+        u128::from_le_bytes(bytes).min(u64::MAX as u128) as u64
+    };
+    let kelvin = u16::from_le_bytes([log[1], log[2]]);
+
+    DriveHealth {
+        source: "nvme ioctl".to_string(),
+        critical_warning: Some(log[0]),
+        temperature: (kelvin > 0).then_some(kelvin as f32 - 273.15),
+        available_spare: Some(log[3]),
+        spare_threshold: Some(log[4]),
+        percentage_used: Some(log[5]),
+        data_read: Some(u128_at(32).saturating_mul(NVME_DATA_UNIT)),
+        data_written: Some(u128_at(48).saturating_mul(NVME_DATA_UNIT)),
+        power_cycles: Some(u128_at(112)),
+        power_on_hours: Some(u128_at(128)),
+        unsafe_shutdowns: Some(u128_at(144)),
+        media_errors: Some(u128_at(160)),
+        ..Default::default()
+    }
+}
+
+fn read_ata_ioctl(drive: &str) -> Probe {
+    use std::os::unix::io::AsRawFd;
+
+    if !drive.starts_with("sd") {
+        return Probe::Unavailable;
+    }
+    let Ok(file) = fs::File::open(format!("/dev/{}", drive)) else {
+        return Probe::Unavailable;
+    };
+    let fd = file.as_raw_fd();
+
+    // The power state comes back in the count byte: 0x00/0x01 is standby
+    let mut power = [ATA_CHECK_POWER_MODE, 0, 0, 0];
+    // SAFETY: HDIO_DRIVE_CMD with a zero count reads and writes only the 4-byte header
+    if unsafe { libc::ioctl(fd, HDIO_DRIVE_CMD, power.as_mut_ptr()) } == 0 && power[2] <= 0x01 {
+        return Probe::Asleep;
+    }
+
+    let mut buf = [0u8; 4 + 512];
+    buf[..4].copy_from_slice(&[ATA_SMART, 0, ATA_SMART_READ_VALUES, 1]);
+    // SAFETY: a count of 1 makes the kernel copy one 512-byte sector after the header
+    if unsafe { libc::ioctl(fd, HDIO_DRIVE_CMD, buf.as_mut_ptr()) } != 0 {
+        return Probe::Unavailable;
+    }
+    let mut data = [0u8; 512];
+    data.copy_from_slice(&buf[4..]);
+    Probe::Health(Box::new(parse_ata_smart_data(&data)))
+}
+
+/// Decodes the 512-byte ATA SMART READ DATA sector: 30 twelve-byte attribute
+/// entries from offset 2, each with a 48-bit little-endian raw value.
+pub fn parse_ata_smart_data(data: &[u8; 512]) -> DriveHealth {
+    let raw = |id: u8| {
+        data[2..2 + ATA_SMART_ATTRIBUTES * 12]
+            .chunks_exact(12)
+            .find(|entry| entry[0] == id)
+            .map(|entry| {
+                let mut bytes = [0u8; 8];
+                bytes[..6].copy_from_slice(&entry[5..11]);
+                u64::from_le_bytes(bytes)
+            })
+    };
+
+    DriveHealth {
+        source: "ata ioctl".to_string(),
+        reallocated_sectors: raw(5),
+        pending_sectors: raw(197),
+        uncorrectable_sectors: raw(198),
+        // Some vendors pack minutes into the upper bytes
+        power_on_hours: raw(9).map(|v| v & 0xFFFF_FFFF),
+        power_cycles: raw(12),
+        unsafe_shutdowns: raw(192).or_else(|| raw(174)),
+        temperature: raw(194).map(|v| (v & 0xFF) as f32),
+        ..Default::default()
+    }
+}
+
+fn read_sysfs(drive: &str) -> DriveHealth {
+    let read = |name: &str| {
+        fs::read_to_string(format!("/sys/block/{}/device/{}", drive, name))
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    DriveHealth {
+        source: "sysfs".to_string(),
+        percentage_used: read("percentage_used").map(|v| v.min(255) as u8),
+        power_cycles: read("power_cycles"),
+        ..Default::default()
+    }
+}
+
+/// Fills `verdict` and `notes` from whatever attributes were available.
+pub fn evaluate(health: &mut DriveHealth) {
+    let mut fail = Vec::new();
+    let mut warn = Vec::new();
+
+    if health.smart_passed == Some(false) {
+        fail.push("SMART overall assessment failed".to_string());
+    }
+    if let Some(cw) = health.critical_warning.filter(|cw| *cw != 0) {
+        fail.push(format!("NVMe critical warning 0x{:02x}", cw));
+    }
+    if let (Some(spare), Some(threshold)) = (health.available_spare, health.spare_threshold) {
+        if spare < threshold {
+            fail.push(format!("spare {}% below threshold {}%", spare, threshold));
+        }
+    }
+    if health.self_test_passed == Some(false) {
+        fail.push("last self-test failed".to_string());
+    }
+    match health.percentage_used {
+        Some(used) if used >= 100 => fail.push(format!("rated endurance used ({}%)", used)),
+        Some(used) if used >= 80 => warn.push(format!("{}% of rated endurance used", used)),
+        _ => {}
+    }
+    for (label, value) in [
+        ("reallocated sectors", health.reallocated_sectors),
+        ("pending sectors", health.pending_sectors),
+        ("uncorrectable sectors", health.uncorrectable_sectors),
+        ("media errors", health.media_errors),
+    ] {
+        if let Some(v) = value.filter(|v| *v > 0) {
+            warn.push(format!("{} {}", v, label));
+        }
+    }
+    if health.temperature.is_some_and(|t| t >= 70.0) {
+        warn.push("running hot".to_string());
+    }
+
+    let has_data = health.smart_passed.is_some()
+        || health.critical_warning.is_some()
+        || health.percentage_used.is_some()
+        || health.reallocated_sectors.is_some();
+
+    health.verdict = if !fail.is_empty() {
+        HealthVerdict::Fail
+    } else if !warn.is_empty() {
+        HealthVerdict::Warn
+    } else if has_data {
+        HealthVerdict::Pass
+    } else {
+        HealthVerdict::Unknown
+    };
+    fail.extend(warn);
+    health.notes = fail;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smartctl_ata() {
+        let json = r#"{
+            "model_name": "WDC WD40EFRX",
+            "serial_number": "WD-123",
+            "smart_status": {"passed": true},
+            "power_on_time": {"hours": 31000},
+            "power_cycle_count": 120,
+            "temperature": {"current": 38},
+            "logical_block_size": 512,
+            "ata_smart_attributes": {"table": [
+                {"id": 5, "value": 200, "raw": {"value": 8}},
+                {"id": 197, "value": 200, "raw": {"value": 0}},
+                {"id": 192, "value": 200, "raw": {"value": 45}}
+            ]},
+            "ata_smart_self_test_log": {"standard": {"table": [
+                {"type": {"string": "Short offline"}, "status": {"string": "Completed without error", "passed": true}}
+            ]}}
+        }"#;
+        let mut health = parse_smartctl_json(json).unwrap();
+        evaluate(&mut health);
+        assert_eq!(health.model, "WDC WD40EFRX");
+        assert_eq!(health.power_on_hours, Some(31000));
+        assert_eq!(health.reallocated_sectors, Some(8));
+        assert_eq!(health.unsafe_shutdowns, Some(45));
+        assert_eq!(health.self_test.as_deref(), Some("Short offline: Completed without error"));
+        assert_eq!(health.verdict, HealthVerdict::Warn);
+    }
+
+    #[test]
+    fn test_parse_smartctl_nvme() {
+        let json = r#"{
+            "smart_status": {"passed": true},
+            "nvme_smart_health_information_log": {
+                "critical_warning": 0, "available_spare": 100, "available_spare_threshold": 10,
+                "percentage_used": 3, "data_units_written": 2000, "unsafe_shutdowns": 12,
+                "media_errors": 0, "power_on_hours": 900, "power_cycles": 300
+            }
+        }"#;
+        let mut health = parse_smartctl_json(json).unwrap();
+        evaluate(&mut health);
+        assert_eq!(health.data_written, Some(2000 * 512 * 1000));
+        assert_eq!(health.power_on_hours, Some(900));
+        assert_eq!(health.verdict, HealthVerdict::Pass);
+        assert!(parse_smartctl_json(r#"{"smartctl": {"exit_status": 2}}"#).is_none());
+    }
+
+    #[test]
+    fn test_parse_nvme_smart_log() {
+        let mut log = [0u8; 512];
+        log[0] = 0x04; // reliability degraded
+        log[1..3].copy_from_slice(&313u16.to_le_bytes());
+        log[3] = 5;
+        log[4] = 10;
+        log[5] = 42;
+        log[48] = 100;
+        log[128] = 0x10;
+        log[129] = 0x27;
+        log[160] = 2;
+        let mut health = parse_nvme_smart_log(&log);
+        evaluate(&mut health);
+        assert_eq!(health.percentage_used, Some(42));
+        assert_eq!(health.data_written, Some(100 * 512 * 1000));
+        assert_eq!(health.power_on_hours, Some(10000));
+        assert_eq!(health.media_errors, Some(2));
+        assert!((health.temperature.unwrap() - 39.85).abs() < 0.01);
+        assert_eq!(health.verdict, HealthVerdict::Fail);
+        assert_eq!(health.notes.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_ata_smart_data() {
+        let mut data = [0u8; 512];
+        let attrs: [(u8, u64); 6] = [(9, 0x0002_0000_1F40), (12, 815), (194, 0x0014_0033), (5, 8), (197, 0), (198, 0)];
+        for (i, (id, raw)) in attrs.iter().enumerate() {
+            let off = 2 + i * 12;
+            data[off] = *id;
+            data[off + 3] = 100;
+            data[off + 5..off + 11].copy_from_slice(&raw.to_le_bytes()[..6]);
+        }
+        let mut health = parse_ata_smart_data(&data);
+        evaluate(&mut health);
+        assert_eq!(health.source, "ata ioctl");
+        assert_eq!(health.power_on_hours, Some(8000));
+        assert_eq!(health.power_cycles, Some(815));
+        assert_eq!(health.temperature, Some(51.0));
+        assert_eq!(health.reallocated_sectors, Some(8));
+        assert_eq!(health.pending_sectors, Some(0));
+        assert_eq!(health.unsafe_shutdowns, None);
+        assert_eq!(health.verdict, HealthVerdict::Warn);
+    }
+}
@@ -0,0 +1,307 @@
+use std::fs;
+
+use crate::types::DimmInfo;
+
+const DMI_TABLE: &str = "/sys/firmware/dmi/tables/DMI";
+// The sysfs table is root-only. With --cache-dmi a root run keeps the decoded
+// slots (never the full table, which holds system serials and the UUID) for
+// the user who started it through sudo or pkexec.
+const DMI_CACHE_DIR: &str = "/var/cache/puls-g3";
+const DIMM_CACHE: &str = "/var/cache/puls-g3/dimms.tsv";
+
+const TYPE_MEMORY_DEVICE: u8 = 17;
+const TYPE_END_OF_TABLE: u8 = 127;
+
+pub fn read_dimms(cache: bool) -> Vec<DimmInfo> {
+    match fs::read(DMI_TABLE) {
+        Ok(table) => {
+            let dimms = parse_memory_devices(&table);
+            if cache && users::get_current_uid() == 0 {
+                store_cache(&dimms);
+            }
+            dimms
+        }
+        Err(_) => fs::read_to_string(DIMM_CACHE)
+            .map(|content| parse_dimm_cache(&content))
+            .unwrap_or_default(),
+    }
+}
+
+fn store_cache(dimms: &[DimmInfo]) {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    if fs::create_dir_all(DMI_CACHE_DIR).is_err() {
+        return;
+    }
+    let _ = fs::remove_file(DIMM_CACHE);
+    let file = fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(DIMM_CACHE);
+    if let Ok(mut file) = file {
+        if file.write_all(dimm_cache_string(dimms).as_bytes()).is_err() {
+            return;
+        }
+        // Hand the file to the invoking user so their unprivileged runs can read it
+        let owner = ["SUDO_UID", "PKEXEC_UID"].iter()
+            .find_map(|var| std::env::var(var).ok().and_then(|v| v.parse::<u32>().ok()));
+        if let Some(uid) = owner {
+            let _ = std::os::unix::fs::chown(DIMM_CACHE, Some(uid), None);
+        }
+    }
+}
+
+fn opt<T: ToString>(v: Option<T>) -> String {
+    v.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// One tab-separated line per slot.
+pub fn dimm_cache_string(dimms: &[DimmInfo]) -> String {
+    let clean = |s: &str| s.replace(['\t', '\n'], " ");
+    dimms.iter()
+        .map(|d| {
+            [
+                clean(&d.locator), clean(&d.bank_locator), d.size.to_string(),
+                clean(&d.memory_type), clean(&d.form_factor),
+                opt(d.speed_mts), opt(d.configured_speed_mts),
+                clean(&d.manufacturer), clean(&d.serial), clean(&d.part_number),
+                opt(d.rank), opt(d.configured_voltage_mv),
+            ].join("\t") + "\n"
+        })
+        .collect()
+}
+
+pub fn parse_dimm_cache(content: &str) -> Vec<DimmInfo> {
+    content.lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() != 12 {
+                return None;
+            }
+            Some(DimmInfo {
+                locator: f[0].to_string(),
+                bank_locator: f[1].to_string(),
+                size: f[2].parse().ok()?,
+                memory_type: f[3].to_string(),
+                form_factor: f[4].to_string(),
+                speed_mts: f[5].parse().ok(),
+                configured_speed_mts: f[6].parse().ok(),
+                manufacturer: f[7].to_string(),
+                serial: f[8].to_string(),
+                part_number: f[9].to_string(),
+                rank: f[10].parse().ok(),
+                configured_voltage_mv: f[11].parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Walks the raw SMBIOS structure table and decodes every type 17 entry.
+pub fn parse_memory_devices(table: &[u8]) -> Vec<DimmInfo> {
+    let mut dimms = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= table.len() {
+        let struct_type = table[offset];
+        let length = table[offset + 1] as usize;
+        if length < 4 || offset + length > table.len() {
+            break;
+        }
+
+        let formatted = &table[offset..offset + length];
+        let strings_start = offset + length;
+        let mut end = strings_start;
+        while end + 1 < table.len() && !(table[end] == 0 && table[end + 1] == 0) {
+            end += 1;
+        }
+        let strings = read_strings(&table[strings_start..end.min(table.len())]);
+
+        if struct_type == TYPE_MEMORY_DEVICE {
+            if let Some(dimm) = parse_type17(formatted, &strings) {
+                dimms.push(dimm);
+            }
+        } else if struct_type == TYPE_END_OF_TABLE {
+            break;
+        }
+
+        offset = end + 2;
+    }
+
+    dimms
+}
+
+fn parse_type17(data: &[u8], strings: &[String]) -> Option<DimmInfo> {
+    if data.len() < 0x15 {
+        return None;
+    }
+
+    let byte = |off: usize| data.get(off).copied();
+    let word = |off: usize| {
+        if off + 2 <= data.len() {
+            Some(u16::from_le_bytes([data[off], data[off + 1]]))
+        } else {
+            None
+        }
+    };
+    let dword = |off: usize| {
+        if off + 4 <= data.len() {
+            Some(u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]]))
+        } else {
+            None
+        }
+    };
+    let string = |off: usize| {
+        byte(off)
+            .filter(|idx| *idx > 0)
+            .and_then(|idx| strings.get(idx as usize - 1))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let size = match word(0x0C)? {
+        0 | 0xFFFF => 0,
+        0x7FFF => dword(0x1C).map(|mb| (mb & 0x7FFF_FFFF) as u64 * 1024 * 1024).unwrap_or(0),
+        raw if raw & 0x8000 != 0 => (raw & 0x7FFF) as u64 * 1024,
+        raw => raw as u64 * 1024 * 1024,
+    };
+
+    let speed = |base: usize, extended: usize| match word(base) {
+        Some(0) | None => None,
+        Some(0xFFFF) => dword(extended).filter(|v| *v > 0),
+        Some(v) => Some(v as u32),
+    };
+
+    Some(DimmInfo {
+        locator: string(0x10),
+        bank_locator: string(0x11),
+        size,
+        memory_type: memory_type_name(byte(0x12).unwrap_or(2)).to_string(),
+        form_factor: form_factor_name(byte(0x0E).unwrap_or(2)).to_string(),
+        speed_mts: speed(0x15, 0x54),
+        configured_speed_mts: speed(0x20, 0x58),
+        manufacturer: string(0x17),
+        serial: string(0x18),
+        part_number: string(0x1A),
+        rank: byte(0x1B).map(|a| a & 0x0F).filter(|r| *r > 0),
+        configured_voltage_mv: word(0x26).filter(|v| *v > 0),
+    })
+}
+
+fn read_strings(area: &[u8]) -> Vec<String> {
+    area.split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect()
+}
+
+fn memory_type_name(code: u8) -> &'static str {
+    match code {
+        0x01 => "Other",
+        0x03 => "DRAM",
+        0x0F => "SDRAM",
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x14 => "DDR2 FB-DIMM",
+        0x18 => "DDR3",
+        0x1A => "DDR4",
+        0x1B => "LPDDR",
+        0x1C => "LPDDR2",
+        0x1D => "LPDDR3",
+        0x1E => "LPDDR4",
+        0x1F => "Logical non-volatile",
+        0x20 => "HBM",
+        0x21 => "HBM2",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        0x24 => "HBM3",
+        _ => "Unknown",
+    }
+}
+
+fn form_factor_name(code: u8) -> &'static str {
+    match code {
+        0x01 => "Other",
+        0x03 => "SIMM",
+        0x04 => "SIP",
+        0x05 => "Chip",
+        0x06 => "DIP",
+        0x07 => "ZIP",
+        0x08 => "Proprietary Card",
+        0x09 => "DIMM",
+        0x0A => "TSOP",
+        0x0B => "Row of chips",
+        0x0C => "RIMM",
+        0x0D => "SODIMM",
+        0x0E => "SRIMM",
+        0x0F => "FB-DIMM",
+        0x10 => "Die",
+        0x11 => "CAMM",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type17(size: u16, ext_size: u32, speed: u16, configured: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 0x28];
+        data[0] = TYPE_MEMORY_DEVICE;
+        data[1] = 0x28;
+        data[0x0C..0x0E].copy_from_slice(&size.to_le_bytes());
+        data[0x0E] = 0x0D; // SODIMM
+        data[0x10] = 1; // "DIMM A1"
+        data[0x11] = 2; // "BANK 0"
+        data[0x12] = 0x22; // DDR5
+        data[0x15..0x17].copy_from_slice(&speed.to_le_bytes());
+        data[0x17] = 3; // manufacturer
+        data[0x1A] = 4; // part number
+        data[0x1B] = 0x02; // dual rank
+        data[0x1C..0x20].copy_from_slice(&ext_size.to_le_bytes());
+        data[0x20..0x22].copy_from_slice(&configured.to_le_bytes());
+        data[0x26..0x28].copy_from_slice(&1100u16.to_le_bytes());
+        data.extend_from_slice(b"DIMM A1\0BANK 0\0Samsung\0M425R2GA3BB0-CWMOD\0\0");
+        data
+    }
+
+    #[test]
+    fn test_parse_populated_and_empty_slots() {
+        let mut table = type17(16384, 0, 5600, 5200);
+        table.extend(type17(0, 0, 0, 0));
+        table.extend_from_slice(&[TYPE_END_OF_TABLE, 4, 0, 0, 0, 0]);
+
+        let dimms = parse_memory_devices(&table);
+        assert_eq!(dimms.len(), 2);
+
+        let dimm = &dimms[0];
+        assert_eq!(dimm.locator, "DIMM A1");
+        assert_eq!(dimm.size, 16 * 1024 * 1024 * 1024);
+        assert_eq!(dimm.memory_type, "DDR5");
+        assert_eq!(dimm.form_factor, "SODIMM");
+        assert_eq!(dimm.speed_mts, Some(5600));
+        assert_eq!(dimm.configured_speed_mts, Some(5200));
+        assert_eq!(dimm.manufacturer, "Samsung");
+        assert_eq!(dimm.part_number, "M425R2GA3BB0-CWMOD");
+        assert_eq!(dimm.rank, Some(2));
+
+        assert_eq!(dimms[1].size, 0);
+        assert_eq!(dimms[1].speed_mts, None);
+    }
+
+    #[test]
+    fn test_extended_size() {
+        let table = type17(0x7FFF, 65536, 4800, 4800);
+        let dimms = parse_memory_devices(&table);
+        assert_eq!(dimms[0].size, 64 * 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_dimm_cache_roundtrip() {
+        let mut table = type17(16384, 0, 5600, 5200);
+        table.extend(type17(0, 0, 0, 0));
+        let dimms = parse_memory_devices(&table);
+        let cached = parse_dimm_cache(&dimm_cache_string(&dimms));
+        assert_eq!(cached.len(), 2);
+        assert_eq!(cached[0].part_number, "M425R2GA3BB0-CWMOD");
+        assert_eq!((cached[0].size, cached[0].rank, cached[0].configured_voltage_mv), (dimms[0].size, Some(2), Some(1100)));
+        assert_eq!(cached[1].speed_mts, None);
+    }
+}
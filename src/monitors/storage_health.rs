@@ -0,0 +1,338 @@
+use std::fs;
+use std::path::Path;
+
+use crate::monitors::{list_dir, read_string, read_u64};
+use crate::monitors::diskstats::block_device_name;
+use crate::types::{BtrfsDevice, BtrfsFilesystem, MdArray, StorageHealth, ZfsPool};
+
+const SYNC_ACTIONS: [&str; 5] = ["recovery", "resync", "reshape", "check", "repair"];
+
+pub fn read_storage_health() -> StorageHealth {
+    let mdstat = fs::read_to_string("/proc/mdstat").unwrap_or_default();
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    read_storage_health_from(
+        &mdstat,
+        Path::new("/sys/fs/btrfs"),
+        Path::new("/proc/spl/kstat/zfs"),
+        &mountinfo,
+    )
+}
+
+pub fn read_storage_health_from(mdstat: &str, sys_btrfs: &Path, kstat_zfs: &Path, mountinfo: &str) -> StorageHealth {
+    let mut health = StorageHealth {
+        md_arrays: parse_mdstat(mdstat),
+        btrfs: read_btrfs(sys_btrfs, mountinfo),
+        zfs_pools: read_zfs_pools(kstat_zfs),
+        warnings: Vec::new(),
+    };
+    health.warnings = collect_warnings(&health);
+    health
+}
+
+/// Parses arrays such as
+/// `md1 : active raid5 sdd1[3] sdc1[1] sdb2[0](F)` followed by the
+/// `[3/2] [UU_]` status line and an optional `recovery = 8.5% ... finish=1.2min` line.
+pub fn parse_mdstat(content: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+
+    for line in content.lines() {
+        if let Some((name, rest)) = line.split_once(" : ").filter(|(n, _)| n.starts_with("md")) {
+            let mut array = MdArray {
+                name: name.trim().to_string(),
+                ..Default::default()
+            };
+            for token in rest.split_whitespace() {
+                if token == "active" {
+                    array.active = true;
+                } else if token.starts_with('(') {
+                    array.read_only = token.contains("read-only");
+                } else if let Some((dev, flags)) = token.split_once('[') {
+                    let dev = dev.to_string();
+                    if flags.contains("(F)") {
+                        array.failed_devices.push(dev.clone());
+                    } else if flags.contains("(S)") {
+                        array.spare_devices.push(dev.clone());
+                    }
+                    array.devices.push(dev);
+                } else if array.active && array.level.is_empty() {
+                    array.level = token.to_string();
+                }
+            }
+            arrays.push(array);
+            continue;
+        }
+
+        let array = match arrays.last_mut() {
+            Some(array) if line.starts_with(char::is_whitespace) => array,
+            _ => continue,
+        };
+
+        for token in line.split_whitespace() {
+            if let Some((total, active)) = token
+                .strip_prefix('[')
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.split_once('/'))
+            {
+                array.total_disks = total.parse().unwrap_or(0);
+                array.active_disks = active.parse().unwrap_or(0);
+            } else if token.starts_with('[') && token.ends_with(']') && token.chars().all(|c| "[]U_".contains(c)) {
+                array.member_status = token.trim_matches(|c| c == '[' || c == ']').to_string();
+            }
+        }
+
+        for action in SYNC_ACTIONS {
+            let rest = match line.find(action).map(|i| line[i + action.len()..].trim_start()) {
+                Some(rest) if rest.starts_with('=') => rest[1..].trim_start(),
+                _ => continue,
+            };
+            array.sync_action = Some(action.to_string());
+            array.sync_progress = rest.split('%').next().and_then(|p| p.trim().parse().ok());
+            array.sync_finish = line
+                .split_whitespace()
+                .find_map(|t| t.strip_prefix("finish="))
+                .map(|f| f.to_string())
+                .or_else(|| (!rest.contains('%')).then(|| rest.split_whitespace().next().unwrap_or("").to_lowercase()));
+            break;
+        }
+    }
+
+    arrays
+}
+
+fn read_btrfs(sys_btrfs: &Path, mountinfo: &str) -> Vec<BtrfsFilesystem> {
+    let mut filesystems: Vec<BtrfsFilesystem> = list_dir(sys_btrfs)
+        .into_iter()
+        .filter(|uuid| sys_btrfs.join(uuid).join("allocation").is_dir())
+        .map(|uuid| {
+            let base = sys_btrfs.join(&uuid);
+            let alloc = |kind: &str| {
+                let dir = base.join("allocation").join(kind);
+                (
+                    read_u64(&dir.join("total_bytes")).unwrap_or(0),
+                    read_u64(&dir.join("bytes_used")).unwrap_or(0),
+                )
+            };
+            let mut disks = list_dir(&base.join("devices"));
+            disks.sort();
+            let mut devices: Vec<BtrfsDevice> = list_dir(&base.join("devinfo"))
+                .into_iter()
+                .map(|devid| {
+                    let dir = base.join("devinfo").join(&devid);
+                    let mut dev = BtrfsDevice {
+                        missing: read_u64(&dir.join("missing")).unwrap_or(0) != 0,
+                        devid,
+                        ..Default::default()
+                    };
+                    let stats = fs::read_to_string(dir.join("error_stats")).unwrap_or_default();
+                    for line in stats.lines() {
+                        let (key, value) = match line.split_once(' ') {
+                            Some((k, v)) => (k, v.trim().parse().unwrap_or(0)),
+                            None => continue,
+                        };
+                        match key {
+                            "write_errs" => dev.write_errs = value,
+                            "read_errs" => dev.read_errs = value,
+                            "flush_errs" => dev.flush_errs = value,
+                            "corruption_errs" => dev.corruption_errs = value,
+                            "generation_errs" => dev.generation_errs = value,
+                            _ => {}
+                        }
+                    }
+                    dev
+                })
+                .collect();
+            devices.sort_by_key(|d| d.devid.parse::<u64>().unwrap_or(0));
+
+            BtrfsFilesystem {
+                label: read_string(&base.join("label")).unwrap_or_default(),
+                mountpoint: btrfs_mountpoint(mountinfo, &disks),
+                uuid,
+                disks,
+                data: alloc("data"),
+                metadata: alloc("metadata"),
+                system: alloc("system"),
+                devices,
+            }
+        })
+        .collect();
+    filesystems.sort_by(|a, b| a.mountpoint.cmp(&b.mountpoint));
+    filesystems
+}
+
+// btrfs mounts show an anonymous major:minor, so match on the mount source instead.
+// With several subvolumes mounted the shortest path is usually the one users recognise.
+fn btrfs_mountpoint(mountinfo: &str, disks: &[String]) -> String {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (pre, post) = line.split_once(" - ")?;
+            let mut post = post.split_whitespace();
+            if post.next()? != "btrfs" {
+                return None;
+            }
+            let source = block_device_name(post.next()?);
+            if !disks.contains(&source) {
+                return None;
+            }
+            pre.split_whitespace().nth(4).map(crate::monitors::blockdev::unescape_mount)
+        })
+        .min_by_key(|m| m.len())
+        .unwrap_or_default()
+}
+
+fn read_zfs_pools(kstat_zfs: &Path) -> Vec<ZfsPool> {
+    let mut pools: Vec<ZfsPool> = list_dir(kstat_zfs)
+        .into_iter()
+        .filter_map(|name| {
+            let state = read_string(&kstat_zfs.join(&name).join("state"))?;
+            Some(ZfsPool { name, state })
+        })
+        .collect();
+    pools.sort_by(|a, b| a.name.cmp(&b.name));
+    pools
+}
+
+fn collect_warnings(health: &StorageHealth) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for md in &health.md_arrays {
+        if !md.active {
+            warnings.push(format!("{} is inactive", md.name));
+        } else if md.is_degraded() {
+            let mut msg = format!(
+                "{} ({}) is degraded: {}/{} disks [{}]",
+                md.name, md.level, md.active_disks, md.total_disks, md.member_status
+            );
+            if !md.failed_devices.is_empty() {
+                msg.push_str(&format!(", failed: {}", md.failed_devices.join(", ")));
+            }
+            if let (Some(action), Some(pct)) = (&md.sync_action, md.sync_progress) {
+                msg.push_str(&format!(", {} {:.1}%", action, pct));
+            }
+            warnings.push(msg);
+        }
+    }
+
+    for fs in &health.btrfs {
+        let name = if fs.label.is_empty() { &fs.uuid } else { &fs.label };
+        for dev in &fs.devices {
+            if dev.missing {
+                warnings.push(format!("btrfs {}: device {} is missing", name, dev.devid));
+            } else if dev.total_errors() > 0 {
+                warnings.push(format!(
+                    "btrfs {}: device {} has {} errors (read {}, write {}, flush {}, corruption {}, generation {})",
+                    name, dev.devid, dev.total_errors(), dev.read_errs, dev.write_errs,
+                    dev.flush_errs, dev.corruption_errs, dev.generation_errs
+                ));
+            }
+        }
+    }
+
+    for pool in &health.zfs_pools {
+        if pool.state != "ONLINE" {
+            warnings.push(format!("ZFS pool {} is {}", pool.name, pool.state));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitors::write_fixture;
+
+    const MDSTAT: &str = "Personalities : [raid1] [raid6] [raid5] [raid4]\n\
+                          md0 : active raid1 sdb1[1] sda1[0]\n      \
+                          1046528 blocks super 1.2 [2/2] [UU]\n\
+                          \n\
+                          md1 : active raid5 sdd1[3] sdc1[1] sdb2[0](F)\n      \
+                          2093056 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]\n      \
+                          [=>...................]  recovery =  8.5% (89472/1046528) finish=1.2min speed=12781K/sec\n\
+                          \n\
+                          md2 : active (auto-read-only) raid1 sde[0] sdf[1](S)\n      \
+                          1046528 blocks super 1.2 [1/1] [U]\n      \
+                          \tresync=DELAYED\n\
+                          \n\
+                          md127 : inactive sdg[0](S)\n      \
+                          1046528 blocks super 1.2\n\
+                          \n\
+                          unused devices: <none>\n";
+
+    #[test]
+    fn test_parse_mdstat() {
+        let arrays = parse_mdstat(MDSTAT);
+        assert_eq!(arrays.len(), 4);
+
+        assert_eq!(arrays[0].level, "raid1");
+        assert_eq!(arrays[0].devices, vec!["sdb1", "sda1"]);
+        assert!(!arrays[0].is_degraded());
+
+        let md1 = &arrays[1];
+        assert_eq!(md1.level, "raid5");
+        assert_eq!((md1.total_disks, md1.active_disks), (3, 2));
+        assert_eq!(md1.member_status, "UU_");
+        assert_eq!(md1.failed_devices, vec!["sdb2"]);
+        assert_eq!(md1.sync_action.as_deref(), Some("recovery"));
+        assert_eq!(md1.sync_progress, Some(8.5));
+        assert_eq!(md1.sync_finish.as_deref(), Some("1.2min"));
+        assert!(md1.is_degraded());
+
+        let md2 = &arrays[2];
+        assert!(md2.read_only);
+        assert_eq!(md2.level, "raid1");
+        assert_eq!(md2.spare_devices, vec!["sdf"]);
+        assert_eq!(md2.sync_action.as_deref(), Some("resync"));
+        assert_eq!(md2.sync_progress, None);
+        assert_eq!(md2.sync_finish.as_deref(), Some("delayed"));
+
+        assert!(!arrays[3].active);
+        assert_eq!(arrays[3].level, "");
+    }
+
+    #[test]
+    fn test_storage_health_warnings() {
+        let root = std::env::temp_dir().join(format!("puls-fshealth-{}", std::process::id()));
+        let fs_dir = root.join("btrfs/0f6c2d1e-aaaa-bbbb-cccc-1234567890ab");
+        write_fixture(fs_dir.join("label"), "data\n");
+        write_fixture(fs_dir.join("allocation/data/total_bytes"), "107374182400");
+        write_fixture(fs_dir.join("allocation/data/bytes_used"), "53687091200");
+        write_fixture(fs_dir.join("allocation/metadata/total_bytes"), "2147483648");
+        write_fixture(fs_dir.join("allocation/metadata/bytes_used"), "1073741824");
+        write_fixture(fs_dir.join("allocation/system/total_bytes"), "33554432");
+        write_fixture(fs_dir.join("allocation/system/bytes_used"), "16384");
+        write_fixture(fs_dir.join("devices/sdx1"), "");
+        write_fixture(fs_dir.join("devices/sdy1"), "");
+        write_fixture(fs_dir.join("devinfo/1/missing"), "0");
+        write_fixture(fs_dir.join("devinfo/1/error_stats"), "write_errs 0\nread_errs 3\nflush_errs 0\ncorruption_errs 1\ngeneration_errs 0\n");
+        write_fixture(fs_dir.join("devinfo/2/missing"), "1");
+        write_fixture(root.join("btrfs/features/mixed_groups"), "0");
+        write_fixture(root.join("zfs/tank/state"), "DEGRADED\n");
+        write_fixture(root.join("zfs/backup/state"), "ONLINE\n");
+
+        let mountinfo = "40 1 0:45 /@data /srv rw - btrfs /dev/sdx1 rw,subvolid=256\n\
+                         41 1 0:45 / /mnt/pool rw - btrfs /dev/sdx1 rw,subvolid=5\n";
+        let health = read_storage_health_from(MDSTAT, &root.join("btrfs"), &root.join("zfs"), mountinfo);
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(health.btrfs.len(), 1);
+        let btrfs = &health.btrfs[0];
+        assert_eq!(btrfs.label, "data");
+        assert_eq!(btrfs.mountpoint, "/srv");
+        assert_eq!(btrfs.disks, vec!["sdx1", "sdy1"]);
+        assert_eq!(btrfs.data, (107374182400, 53687091200));
+        assert_eq!(btrfs.system, (33554432, 16384));
+        assert_eq!(btrfs.devices[0].total_errors(), 4);
+        assert!(btrfs.devices[1].missing);
+
+        assert_eq!(health.zfs_pools.len(), 2);
+        assert_eq!(health.zfs_pools[1].name, "tank");
+
+        assert_eq!(health.warnings.len(), 5);
+        assert!(health.warnings[0].starts_with("md1 (raid5) is degraded: 2/3 disks [UU_], failed: sdb2, recovery 8.5%"));
+        assert_eq!(health.warnings[1], "md127 is inactive");
+        assert!(health.warnings[2].contains("device 1 has 4 errors"));
+        assert_eq!(health.warnings[3], "btrfs data: device 2 is missing");
+        assert_eq!(health.warnings[4], "ZFS pool tank is DEGRADED");
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
+use parking_lot::Mutex;
 use sysinfo::{DiskUsage, Networks, Pid, System, Components};
 use users::{Users, UsersCache};
 use chrono::prelude::*;
@@ -15,11 +17,32 @@ pub struct SystemMonitor {
     prev_net_usage: HashMap<String, NetworkStats>,
     last_update: Instant,
     self_pid: u32,
-    mem_cache: Option<(String, String, String)>,
+    cpu_topology: CpuTopology,
+    dimms: Vec<DimmInfo>,
+    disk_io: crate::monitors::diskstats::DiskIoTracker,
+    // Last check per drive; `None` until a drive has been seen awake.
+    // Filled in by a blocking task so smartctl never stalls a collection tick.
+    drive_health: Arc<Mutex<DriveHealthCache>>,
+    smart_task: Option<tokio::task::JoinHandle<()>>,
+    sensor_extremes: HashMap<String, (f64, f64)>,
+    sensors_config: crate::monitors::sensors_conf::SensorsConfigWatcher,
+}
+
+type DriveHealthCache = HashMap<String, (Instant, Option<DriveHealth>)>;
+
+const SMART_REFRESH: std::time::Duration = std::time::Duration::from_secs(300);
+
+fn verdict_rank(verdict: HealthVerdict) -> u8 {
+    match verdict {
+        HealthVerdict::Unknown => 0,
+        HealthVerdict::Pass => 1,
+        HealthVerdict::Warn => 2,
+        HealthVerdict::Fail => 3,
+    }
 }
 
 impl SystemMonitor {
-    pub fn new() -> Self {
+    pub fn new(cache_dmi: bool) -> Self {
         let mut system = System::new_all();
         system.refresh_all();
         
@@ -33,7 +56,13 @@ impl SystemMonitor {
             prev_net_usage: HashMap::new(),
             last_update: Instant::now(),
             self_pid: std::process::id(),
-            mem_cache: None,
+            cpu_topology: crate::monitors::cpu_topology::read_cpu_topology(),
+            dimms: crate::monitors::smbios::read_dimms(cache_dmi),
+            disk_io: crate::monitors::diskstats::DiskIoTracker::new(),
+            drive_health: Arc::default(),
+            smart_task: None,
+            sensor_extremes: HashMap::new(),
+            sensors_config: Default::default(),
         }
     }
     
@@ -46,6 +75,8 @@ impl SystemMonitor {
             ("Cores".into(), format!("{} Physical / {} Logical", 
                 self.system.physical_core_count().unwrap_or(0), 
                 self.system.cpus().len())),
+            ("Topology".into(), self.cpu_topology.summary()),
+            ("Caches".into(), self.cpu_topology.cache_summary()),
             ("Total Memory".into(), format_size(self.system.total_memory())),
             ("Boot Time".into(), {
                 let boot_time = System::boot_time(); if boot_time > 0 {
@@ -73,6 +104,23 @@ impl SystemMonitor {
         ]
     }
 
+    pub fn get_cpu_topology(&self) -> CpuTopology {
+        self.cpu_topology.clone()
+    }
+
+    pub fn get_dimms(&self) -> Vec<DimmInfo> {
+        self.dimms.clone()
+    }
+
+    /// RSS of every user-space process, independent of the process tab filters.
+    pub fn process_memory_samples(&self) -> Vec<(u32, String, u64)> {
+        self.system.processes()
+            .iter()
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .map(|(pid, process)| (pid.as_u32(), process.name().to_string_lossy().to_string(), process.memory()))
+            .collect()
+    }
+
     pub fn get_total_memory(&self) -> u64 {
         self.system.total_memory()
     }
@@ -225,12 +273,31 @@ impl SystemMonitor {
         }).collect()
     }
     
-    pub fn get_disks(&self) -> Vec<DetailedDiskInfo> {
+    pub fn update_block_io(&mut self) -> Vec<BlockDeviceIo> {
+        self.disk_io.refresh()
+    }
+
+    pub fn get_disks(&self, block_io: &[BlockDeviceIo], drive_health: &[DriveHealth]) -> Vec<DetailedDiskInfo> {
         let disks = sysinfo::Disks::new_with_refreshed_list();
         disks.iter().map(|disk| {
             let used = disk.total_space().saturating_sub(disk.available_space());
             let disk_name = disk.name().to_string_lossy();
             
+            let dev_str = disk_name.to_string();
+            let dev_name = crate::monitors::diskstats::block_device_name(&dev_str);
+            let io = block_io.iter().find(|d| d.name == dev_name).cloned().unwrap_or_default();
+
+            // Mounts on partitions or dm/md devices map to the physical drives underneath
+            let backing = crate::monitors::blockdev::backing_disks(&dev_name);
+            let drive = backing.first().map(String::as_str).unwrap_or(dev_name.as_str());
+            let health = drive_health.iter()
+                .filter(|h| backing.contains(&h.device))
+                .max_by_key(|h| verdict_rank(h.verdict));
+            let health_pct = health.and_then(|h| h.percentage_used).map(|used| 100u8.saturating_sub(used));
+            let power_cycles = health.and_then(|h| h.power_cycles);
+            let verdict = health.map(|h| h.verdict).unwrap_or_default();
+            let is_nvme = drive.starts_with("nvme");
+
             let temp = self.components.iter()
                 .find(|c| {
                     let label = c.label().to_string();
@@ -238,38 +305,8 @@ impl SystemMonitor {
                 })
                 .and_then(|c| c.temperature())
                 .or_else(|| {
-                    if let Ok(hwmon_entries) = std::fs::read_dir("/sys/class/hwmon") {
-                        for entry in hwmon_entries.flatten() {
-                            let path = entry.path();
-                            if let Ok(name) = std::fs::read_to_string(path.join("name")) {
-                                if name.trim() == "nvme" {
-                                    if let Ok(val) = std::fs::read_to_string(path.join("temp1_input")) {
-                                        if let Ok(mdeg) = val.trim().parse::<f32>() {
-                                            return Some(mdeg / 1000.0);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    
-                    let dev_str_fb = disk_name.to_string();
-                    let block_dev_fb = dev_str_fb.split('/').last().unwrap_or(&dev_str_fb);
-                    let base_fb = if block_dev_fb.contains("nvme") {
-                        block_dev_fb.rfind('p')
-                            .and_then(|pos| {
-                                if pos > 0 && block_dev_fb[pos+1..].chars().all(|c| c.is_ascii_digit()) {
-                                    Some(&block_dev_fb[..pos])
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or(block_dev_fb)
-                    } else {
-                        block_dev_fb.trim_end_matches(|c: char| c.is_ascii_digit())
-                    };
                     // /sys/block/<dev>/device/hwmon/hwmon*/temp1_input
-                    let hwmon_path = format!("/sys/block/{}/device/hwmon", base_fb);
+                    let hwmon_path = format!("/sys/block/{}/device/hwmon", drive);
                     std::fs::read_dir(&hwmon_path).ok().and_then(|entries| {
                         for entry in entries.flatten() {
                             let temp_file = entry.path().join("temp1_input");
@@ -281,53 +318,15 @@ impl SystemMonitor {
                         }
                         None
                     })
-                });
-            
-            let read_rate = 0;
-            let write_rate = 0;
-            let dev_str = disk_name.to_string();
-            let block_dev = dev_str.split('/').last().unwrap_or(&dev_str);
-            let base_dev = if block_dev.contains("nvme") {
-                block_dev.rfind('p')
-                    .and_then(|pos| {
-                        if pos > 0 && block_dev[pos+1..].chars().all(|c| c.is_ascii_digit()) {
-                            Some(&block_dev[..pos])
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(block_dev)
-            } else {
-                block_dev.trim_end_matches(|c: char| c.is_ascii_digit())
-            };
-
-            if let Ok(stat) = std::fs::read_to_string(format!("/sys/block/{}/stat", base_dev)) {
-                let parts: Vec<&str> = stat.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    let _sectors_read = parts[2].parse::<u64>().unwrap_or(0);
-                    let _sectors_written = parts[6].parse::<u64>().unwrap_or(0);
-                }
-            }
-
-            let mut health_pct: Option<u8> = None;
-            let mut power_cycles: Option<u64> = None;
-            let is_nvme = base_dev.starts_with("nvme");
-            
-            if is_nvme {
-                if let Ok(val) = std::fs::read_to_string(format!("/sys/block/{}/device/percentage_used", base_dev)) {
-                    if let Ok(pct) = val.trim().parse::<u8>() {
-                        health_pct = Some(100u8.saturating_sub(pct));
-                    }
-                }
-                if let Ok(val) = std::fs::read_to_string(format!("/sys/block/{}/device/power_cycles", base_dev)) {
-                    power_cycles = val.trim().parse::<u64>().ok();
-                }
-            }
+                })
+                .or_else(|| health.and_then(|h| h.temperature));
 
-            let is_ssd = std::fs::read_to_string(format!("/sys/block/{}/queue/rotational", base_dev))
+            let is_ssd = std::fs::read_to_string(format!("/sys/block/{}/queue/rotational", drive))
                 .ok()
                 .and_then(|v| v.trim().parse::<u8>().ok())
                 .map(|v| v == 0);
+            let (inodes_total, inodes_used, inodes_free) =
+                crate::monitors::quota::inode_usage(&disk.mount_point().to_string_lossy()).unwrap_or_default();
             
             DetailedDiskInfo {
                 name: disk.mount_point().to_string_lossy().into_owned(),
@@ -336,18 +335,60 @@ impl SystemMonitor {
                 total: disk.total_space(),
                 free: disk.available_space(),
                 used,
-                read_rate,
-                write_rate,
-                read_ops: 0,
-                write_ops: 0,
+                inodes_total,
+                inodes_used,
+                inodes_free,
+                read_rate: io.read_rate,
+                write_rate: io.write_rate,
+                read_ops: io.read_iops.round() as u64,
+                write_ops: io.write_iops.round() as u64,
+                read_latency_ms: io.read_latency_ms,
+                write_latency_ms: io.write_latency_ms,
+                queue_depth: io.queue_depth,
+                util_pct: io.util_pct,
                 is_ssd,
                 temp,
                 health_pct,
                 power_cycles,
                 is_nvme,
+                verdict,
             }
         }).collect()
     }
+
+    /// SMART queries are slow, so results are reused for a few minutes and refreshed
+    /// in the background. Returns what is known so far.
+    pub fn refresh_drive_health(&mut self) -> Vec<DriveHealth> {
+        let now = Instant::now();
+        let drives = crate::monitors::smart::list_drives();
+        let mut cache = self.drive_health.lock();
+        cache.retain(|name, _| drives.contains(name));
+
+        let stale: Vec<String> = drives.iter()
+            .filter(|d| cache.get(*d).is_none_or(|(checked, _)| now.duration_since(*checked) >= SMART_REFRESH))
+            .cloned()
+            .collect();
+        let idle = self.smart_task.as_ref().is_none_or(|task| task.is_finished());
+        if !stale.is_empty() && idle {
+            let shared = self.drive_health.clone();
+            self.smart_task = Some(tokio::task::spawn_blocking(move || {
+                for drive in stale {
+                    let health = crate::monitors::smart::read_drive_health(&drive);
+                    let mut cache = shared.lock();
+                    let entry = cache.entry(drive).or_insert((now, None));
+                    entry.0 = Instant::now();
+                    // A drive in standby keeps its last reading
+                    if health.is_some() {
+                        entry.1 = health;
+                    }
+                }
+            }));
+        }
+
+        drives.iter()
+            .filter_map(|d| cache.get(d).and_then(|(_, h)| h.clone()))
+            .collect()
+    }
     
     pub fn get_networks(&mut self) -> Vec<DetailedNetInfo> {
         let now = Instant::now();
@@ -373,6 +414,10 @@ impl SystemMonitor {
                         tx: data.total_transmitted(),
                     }
                 );
+
+                let info = crate::monitors::netif::read_interface_info(interface_name);
+                // Addresses come from getifaddrs, which the kernel answers over rtnetlink
+                let addresses = data.ip_networks().iter().map(|ip| ip.to_string()).collect();
                 
                 DetailedNetInfo {
                     name: interface_name.clone(),
@@ -384,8 +429,17 @@ impl SystemMonitor {
                     packets_tx: data.total_packets_transmitted(),
                     errors_rx: data.total_errors_on_received(),
                     errors_tx: data.total_errors_on_transmitted(),
-                    interface_type: "Unknown".to_string(),
-                    is_up: true, 
+                    interface_type: info.kind,
+                    is_up: info.is_up,
+                    is_virtual: info.is_virtual,
+                    operstate: info.operstate,
+                    carrier: info.carrier,
+                    speed_mbps: info.speed_mbps,
+                    duplex: info.duplex,
+                    mtu: info.mtu,
+                    mac: info.mac,
+                    addresses,
+                    driver: info.driver,
                 }
             })
             .collect();
@@ -402,17 +456,12 @@ impl SystemMonitor {
         let boot_time = System::boot_time();
         let uptime = current_timestamp().saturating_sub(boot_time);
         
-        let mem_available = self.system.available_memory();
-        let mem_free = self.system.free_memory();
-        let mem_cached = mem_available.saturating_sub(mem_free);
-
         let (mem_type, mem_gen, mem_speed, mem_temp) = self.get_memory_details();
 
         GlobalUsage {
             cpu: self.system.global_cpu_usage(),
             mem_used: self.system.used_memory(),
             mem_total: self.system.total_memory(),
-            mem_cached,
             swap_used: self.system.used_swap(),
             swap_total: self.system.total_swap(),
             gpu_util,
@@ -420,8 +469,6 @@ impl SystemMonitor {
             net_up: total_net_up,
             disk_read: total_disk_read,
             disk_write: total_disk_write,
-            disk_read_ops: 0, 
-            disk_write_ops: 0,
             memory_type: mem_type,
             memory_generation: mem_gen,
             memory_speed: mem_speed,
@@ -485,168 +532,72 @@ impl SystemMonitor {
         None
     }
 
-    pub fn get_sensors(&self) -> Vec<SensorInfo> {
-        let mut sensors = Vec::new();
-        
-        for c in self.components.iter() {
-            sensors.push(SensorInfo {
-                label: c.label().to_string(),
-                chip: String::new(),
-                sensor_type: "temp".to_string(),
-                value: c.temperature().unwrap_or(0.0) as f64,
-                unit: "°C".to_string(),
-                temp: c.temperature().unwrap_or(0.0),
-                max: c.max(),
-                critical: c.critical(),
-            });
-        }
-        
-        if let Ok(entries) = std::fs::read_dir("/sys/class/hwmon") {
-            for entry in entries.flatten() {
-                let hwmon_path = entry.path();
-                let chip_name = std::fs::read_to_string(hwmon_path.join("name"))
-                    .unwrap_or_default().trim().to_string();
-                if let Ok(files) = std::fs::read_dir(&hwmon_path) {
-                    for file in files.flatten() {
-                        let fname = file.file_name().to_string_lossy().to_string();
-                        if fname.starts_with("fan") && fname.ends_with("_input") {
-                            let idx = fname.trim_start_matches("fan").trim_end_matches("_input");
-                            let label_file = hwmon_path.join(format!("fan{}_label", idx));
-                            let label = std::fs::read_to_string(&label_file)
-                                .map(|s| s.trim().to_string())
-                                .unwrap_or_else(|_| format!("{} Fan {}", chip_name, idx));
-                            if let Ok(val) = std::fs::read_to_string(file.path()) {
-                                if let Ok(rpm) = val.trim().parse::<f64>() {
-                                    sensors.push(SensorInfo {
-                                        label,
-                                        chip: chip_name.clone(),
-                                        sensor_type: "fan".to_string(),
-                                        value: rpm,
-                                        unit: "RPM".to_string(),
-                                        temp: rpm as f32,
-                                        max: None,
-                                        critical: None,
-                                    });
-                                }
-                            }
-                        }
-                        
-                        if fname.starts_with("in") && fname.ends_with("_input") && fname[2..].starts_with(|c: char| c.is_ascii_digit()) {
-                            let idx = fname.trim_start_matches("in").trim_end_matches("_input");
-                            let label_file = hwmon_path.join(format!("in{}_label", idx));
-                            let label = std::fs::read_to_string(&label_file)
-                                .map(|s| s.trim().to_string())
-                                .unwrap_or_else(|_| format!("{} Voltage {}", chip_name, idx));
-                            
-                            if let Ok(val) = std::fs::read_to_string(file.path()) {
-                                if let Ok(mv) = val.trim().parse::<f64>() {
-                                    let volts = mv / 1000.0;
-                                    sensors.push(SensorInfo {
-                                        label,
-                                        chip: chip_name.clone(),
-                                        sensor_type: "in".to_string(),
-                                        value: volts,
-                                        unit: "V".to_string(),
-                                        temp: volts as f32,
-                                        max: None,
-                                        critical: None,
-                                    });
-                                }
-                            }
-                        }
-                        
-                        if fname.starts_with("power") && (fname.ends_with("_input") || fname.ends_with("_average")) {
-                            let idx_end = if fname.ends_with("_input") { "_input" } else { "_average" };
-                            let idx = fname.trim_start_matches("power").trim_end_matches(idx_end);
-                            let label_file = hwmon_path.join(format!("power{}_label", idx));
-                            let label = std::fs::read_to_string(&label_file)
-                                .map(|s| s.trim().to_string())
-                                .unwrap_or_else(|_| format!("{} Power {}", chip_name, idx));
-                            
-                            if sensors.iter().any(|s| s.label == label && s.sensor_type == "power") {
-                                continue;
-                            }
-                            
-                            if let Ok(val) = std::fs::read_to_string(file.path()) {
-                                if let Ok(uw) = val.trim().parse::<f64>() {
-                                    let watts = uw / 1_000_000.0;
-                                    sensors.push(SensorInfo {
-                                        label,
-                                        chip: chip_name.clone(),
-                                        sensor_type: "power".to_string(),
-                                        value: watts,
-                                        unit: "W".to_string(),
-                                        temp: watts as f32,
-                                        max: None,
-                                        critical: None,
-                                    });
-                                }
-                            }
-                        }
-                        
-                        if fname.starts_with("curr") && fname.ends_with("_input") {
-                            let idx = fname.trim_start_matches("curr").trim_end_matches("_input");
-                            let label_file = hwmon_path.join(format!("curr{}_label", idx));
-                            let label = std::fs::read_to_string(&label_file)
-                                .map(|s| s.trim().to_string())
-                                .unwrap_or_else(|_| format!("{} Current {}", chip_name, idx));
-                            
-                            if let Ok(val) = std::fs::read_to_string(file.path()) {
-                                if let Ok(ma) = val.trim().parse::<f64>() {
-                                    let amps = ma / 1000.0;
-                                    sensors.push(SensorInfo {
-                                        label,
-                                        chip: chip_name.clone(),
-                                        sensor_type: "curr".to_string(),
-                                        value: amps,
-                                        unit: "A".to_string(),
-                                        temp: amps as f32,
-                                        max: None,
-                                        critical: None,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
+    pub fn get_sensors(&mut self) -> Vec<SensorInfo> {
+        let mut sensors = crate::monitors::hwmon::read_sensors();
+
+        // sysinfo only matters where hwmon has no temperatures, e.g. thermal zones alone
+        if !sensors.iter().any(|s| s.sensor_type == "temp") {
+            for (i, c) in self.components.iter().enumerate() {
+                let temp = c.temperature().unwrap_or(0.0);
+                // sysinfo labels are "<chip> <sensor>"
+                let (chip, label) = c.label().split_once(' ').unwrap_or(("thermal", c.label()));
+                sensors.push(SensorInfo {
+                    label: label.to_string(),
+                    chip: chip.to_string(),
+                    id: format!("temp{}", i + 1),
+                    sensor_type: "temp".to_string(),
+                    value: temp as f64,
+                    unit: "°C".to_string(),
+                    temp,
+                    max: c.max(),
+                    critical: c.critical(),
+                    session_min: temp as f64,
+                    session_max: temp as f64,
+                    ..Default::default()
+                });
             }
         }
-        
-        let type_order = |t: &str| -> u8 {
-            match t {
-                "temp" => 0,
-                "fan" => 1,
-                "in" => 2,
-                "power" => 3,
-                "curr" => 4,
-                _ => 5,
-            }
-        };
-        sensors.sort_by(|a, b| {
-            type_order(&a.sensor_type).cmp(&type_order(&b.sensor_type))
-                .then_with(|| a.label.cmp(&b.label))
-        });
-        
+
+        // Extremes recorded under the old labels or scaling no longer compare
+        if self.sensors_config.refresh() {
+            self.sensor_extremes.clear();
+        }
+        self.sensors_config.config().apply(&mut sensors);
+
+        for sensor in sensors.iter_mut() {
+            let (min, max) = self.sensor_extremes
+                .entry(format!("{}/{}", sensor.chip, sensor.id))
+                .or_insert((sensor.value, sensor.value));
+            *min = min.min(sensor.value);
+            *max = max.max(sensor.value);
+            sensor.session_min = *min;
+            sensor.session_max = *max;
+        }
+
         sensors
     }
     
+    pub fn sensor_config_error(&self) -> Option<String> {
+        self.sensors_config.error().cloned()
+    }
+
     pub fn refresh(&mut self) {
         self.system.refresh_cpu_all();
         self.system.refresh_memory();
         self.components.refresh(true);
     }
     
-    pub fn calculate_total_disk_io(&self, processes: &[ProcessInfo]) -> (u64, u64) {
-        let total_read = processes.iter()
-            .map(|p| p.disk_read.trim_end_matches(" B/s").trim_end_matches(" KB/s").trim_end_matches(" MB/s")
-                .parse::<f64>().unwrap_or(0.0) as u64)
-            .sum();
-        let total_write = processes.iter()
-            .map(|p| p.disk_write.trim_end_matches(" B/s").trim_end_matches(" KB/s").trim_end_matches(" MB/s")
-                .parse::<f64>().unwrap_or(0.0) as u64)
-            .sum();
-        
-        (total_read, total_write)
+    /// Sums physical disks only so dm/md/partitions on top of them aren't counted twice.
+    pub fn calculate_total_disk_io(&self, block_io: &[BlockDeviceIo]) -> (u64, u64, u64, u64) {
+        let physical = block_io.iter().filter(|d| d.physical);
+        let (mut read, mut write, mut read_ops, mut write_ops) = (0, 0, 0.0, 0.0);
+        for disk in physical {
+            read += disk.read_rate;
+            write += disk.write_rate;
+            read_ops += disk.read_iops;
+            write_ops += disk.write_iops;
+        }
+        (read, write, read_ops.round() as u64, write_ops.round() as u64)
     }
     
     pub fn calculate_total_network_io(&self, networks: &[DetailedNetInfo]) -> (u64, u64) {
@@ -655,54 +606,24 @@ impl SystemMonitor {
         (total_down, total_up)
     }
 
-    pub fn get_memory_details(&mut self) -> (String, String, String, Option<f32>) {
-        if let Some((m_type, m_gen, m_speed)) = &self.mem_cache {
-            let mem_temp = self.components.iter()
-                .find(|c| {
-                    let label = c.label().to_lowercase();
-                    label.contains("dimm") || label.contains("dram") || label.contains("memory")
-                })
-                .and_then(|c| c.temperature());
-            return (m_type.clone(), m_gen.clone(), m_speed.clone(), mem_temp);
-        }
-
-        let mut mem_type = "N/A".to_string();
-        let mut mem_gen = "N/A".to_string();
-        let mut mem_speed = "N/A".to_string();
+    pub fn get_memory_details(&self) -> (String, String, String, Option<f32>) {
+        let populated: Vec<&DimmInfo> = self.dimms.iter().filter(|d| d.size > 0).collect();
 
-        let is_root = users::get_current_uid() == 0;
-        let cmd_output = if is_root {
-            std::process::Command::new("dmidecode").arg("-t").arg("memory").output().ok()
-        } else {
-            None
-        };
-
-        if let Some(output) = cmd_output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                for line in stdout.lines() {
-                    let line = line.trim();
-                    if line.starts_with("Type:") {
-                        mem_type = line.replace("Type:", "").trim().to_string();
-                    } else if line.starts_with("Speed:") && !line.contains("Unknown") {
-                        mem_speed = line.replace("Speed:", "").trim().to_string();
-                    }
-                }
-            }
-        }
-
-        if mem_gen == "N/A" {
-            if let Ok(board) = std::fs::read_to_string("/sys/class/dmi/id/board_name") {
-                let board = board.to_lowercase();
-                if board.contains("adl") || board.contains("raptor") {
-                    mem_gen = "DDR5".to_string();
-                } else if board.contains("tgl") || board.contains("cml") {
-                    mem_gen = "DDR4".to_string();
-                }
+        let (mem_type, mem_gen, mem_speed) = match populated.first() {
+            Some(first) => {
+                let mem_type = format!("{} {}", first.memory_type, first.form_factor);
+                let rated = populated.iter().filter_map(|d| d.speed_mts).min();
+                let configured = populated.iter().filter_map(|d| d.configured_speed_mts).min();
+                let mem_speed = match (rated, configured) {
+                    (Some(r), Some(c)) if r != c => format!("{} MT/s (rated {} MT/s)", c, r),
+                    (_, Some(c)) => format!("{} MT/s", c),
+                    (Some(r), None) => format!("{} MT/s", r),
+                    (None, None) => "N/A".to_string(),
+                };
+                (mem_type, first.memory_type.clone(), mem_speed)
             }
-        }
-
-        self.mem_cache = Some((mem_type.clone(), mem_gen.clone(), mem_speed.clone()));
+            None => ("N/A".to_string(), "N/A".to_string(), "N/A".to_string()),
+        };
 
         let mem_temp = self.components.iter()
             .find(|c| {
@@ -717,7 +638,7 @@ impl SystemMonitor {
 
 impl Default for SystemMonitor {
     fn default() -> Self {
-        Self::new()
+        Self::new(false)
     }
 }
 
@@ -772,7 +693,7 @@ mod tests {
 
     #[test]
     fn test_system_monitor_creation() {
-        let monitor = SystemMonitor::new();
+        let monitor = SystemMonitor::new(false);
         assert!(monitor.system.cpus().len() > 0);
     }
     
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, Local, NaiveDateTime};
+
+use crate::types::{DetailedNetInfo, TrafficPeriod, TrafficUsage};
+
+const LEDGER_FILE: &str = "traffic.tsv";
+const LEDGER_HEADER: &str = "# puls-g3 traffic ledger v1";
+// The sample since the last save is recovered from the kernel counters on the
+// next start as long as the machine has not rebooted in between
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Granularity {
+    Hour,
+    Day,
+    Month,
+}
+
+impl Granularity {
+    const ALL: [Granularity; 3] = [Granularity::Hour, Granularity::Day, Granularity::Month];
+
+    fn tag(self) -> &'static str {
+        match self {
+            Granularity::Hour => "hour",
+            Granularity::Day => "day",
+            Granularity::Month => "month",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|g| g.tag() == tag)
+    }
+
+    /// Keys sort chronologically as strings: `2026-10-18T14`, `2026-10-18`, `2026-10`.
+    fn key(self, time: &NaiveDateTime) -> String {
+        let format = match self {
+            Granularity::Hour => "%Y-%m-%dT%H",
+            Granularity::Day => "%Y-%m-%d",
+            Granularity::Month => "%Y-%m",
+        };
+        time.format(format).to_string()
+    }
+
+    fn retention(self) -> usize {
+        match self {
+            Granularity::Hour => 48,
+            Granularity::Day => 62,
+            Granularity::Month => 24,
+        }
+    }
+}
+
+/// vnstat-style byte accounting. Kernel counters reset on reboot, so the ledger
+/// stores the last counters it saw together with the boot id and only adds deltas.
+#[derive(Debug, Default)]
+pub struct TrafficLedger {
+    boot_id: String,
+    counters: HashMap<String, (u64, u64)>,
+    periods: BTreeMap<(String, Granularity, String), (u64, u64)>,
+    // A brand-new ledger cannot tell when the traffic counted so far happened,
+    // so its first sample is only a baseline
+    fresh: bool,
+}
+
+impl TrafficLedger {
+    pub fn new(boot_id: &str) -> Self {
+        Self {
+            boot_id: boot_id.to_string(),
+            fresh: true,
+            ..Default::default()
+        }
+    }
+
+    /// Loads a saved ledger. Counters saved during another boot are dropped, so
+    /// everything counted since this boot is attributed to the first sample.
+    pub fn parse(content: &str, boot_id: &str) -> Self {
+        let mut ledger = Self {
+            boot_id: boot_id.to_string(),
+            ..Default::default()
+        };
+        let mut same_boot = false;
+
+        for line in content.lines().filter(|l| !l.starts_with('#')) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let num = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+            match fields.as_slice() {
+                ["boot", id] => same_boot = *id == boot_id,
+                ["counter", name, ..] => {
+                    if let (Some(rx), Some(tx)) = (num(2), num(3)) {
+                        ledger.counters.insert(name.to_string(), (rx, tx));
+                    }
+                }
+                [tag, name, key, ..] => {
+                    if let (Some(g), Some(rx), Some(tx)) = (Granularity::from_tag(tag), num(3), num(4)) {
+                        ledger.periods.insert((name.to_string(), g, key.to_string()), (rx, tx));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !same_boot {
+            ledger.counters.clear();
+        }
+        ledger
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut out = format!("{}\nboot\t{}\n", LEDGER_HEADER, self.boot_id);
+        let mut counters: Vec<_> = self.counters.iter().collect();
+        counters.sort();
+        for (name, (rx, tx)) in counters {
+            out.push_str(&format!("counter\t{}\t{}\t{}\n", name, rx, tx));
+        }
+        for ((name, g, key), (rx, tx)) in &self.periods {
+            out.push_str(&format!("{}\t{}\t{}\t{}\t{}\n", g.tag(), name, key, rx, tx));
+        }
+        out
+    }
+
+    /// Adds the traffic since the previous sample of each interface's
+    /// since-boot counters to the hour, day and month containing `now`.
+    pub fn record(&mut self, samples: &[(String, u64, u64)], now: &NaiveDateTime) {
+        for (name, rx, tx) in samples {
+            let (last_rx, last_tx) = match self.counters.insert(name.clone(), (*rx, *tx)) {
+                Some(last) => last,
+                None if self.fresh => continue,
+                None => (0, 0),
+            };
+            // A counter that went backwards belongs to a re-created interface
+            let rx_delta = if *rx >= last_rx { rx - last_rx } else { *rx };
+            let tx_delta = if *tx >= last_tx { tx - last_tx } else { *tx };
+            if rx_delta == 0 && tx_delta == 0 {
+                continue;
+            }
+            for g in Granularity::ALL {
+                let entry = self.periods.entry((name.clone(), g, g.key(now))).or_default();
+                entry.0 += rx_delta;
+                entry.1 += tx_delta;
+            }
+        }
+        self.fresh = false;
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let mut counts: HashMap<(String, Granularity), usize> = HashMap::new();
+        for (name, g, _) in self.periods.keys() {
+            *counts.entry((name.clone(), *g)).or_default() += 1;
+        }
+        // Keys are ordered oldest first within each (interface, granularity)
+        self.periods.retain(|(name, g, _), _| {
+            let count = counts.get_mut(&(name.clone(), *g)).unwrap();
+            if *count > g.retention() {
+                *count -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    pub fn usage(&self, now: &NaiveDateTime, caps: &[(String, u64)]) -> Vec<TrafficUsage> {
+        let names: BTreeSet<&String> = self.periods.keys().map(|(name, _, _)| name).collect();
+        let yesterday = *now - chrono::Duration::days(1);
+        let last_month = now
+            .date()
+            .with_day(1)
+            .and_then(|d| d.pred_opt())
+            .unwrap_or(now.date())
+            .and_time(now.time());
+
+        names
+            .into_iter()
+            .map(|name| {
+                let list = |g: Granularity| -> Vec<TrafficPeriod> {
+                    self.periods
+                        .iter()
+                        .filter(|((n, pg, _), _)| n == name && *pg == g)
+                        .map(|((_, _, key), (rx, tx))| TrafficPeriod { period: key.clone(), rx: *rx, tx: *tx })
+                        .collect()
+                };
+                let get = |g: Granularity, time: &NaiveDateTime| {
+                    let key = g.key(time);
+                    let (rx, tx) = self.periods.get(&(name.clone(), g, key.clone())).copied().unwrap_or_default();
+                    TrafficPeriod { period: key, rx, tx }
+                };
+                TrafficUsage {
+                    interface: name.clone(),
+                    today: get(Granularity::Day, now),
+                    yesterday: get(Granularity::Day, &yesterday),
+                    this_month: get(Granularity::Month, now),
+                    last_month: get(Granularity::Month, &last_month),
+                    hours: list(Granularity::Hour),
+                    days: list(Granularity::Day),
+                    months: list(Granularity::Month),
+                    monthly_cap: caps.iter().find(|(n, _)| n == name).map(|(_, cap)| *cap),
+                }
+            })
+            .collect()
+    }
+}
+
+/// `$XDG_DATA_HOME/puls-g3/traffic.tsv`, defaulting to `~/.local/share/puls-g3`.
+pub fn ledger_path() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("puls-g3").join(LEDGER_FILE))
+}
+
+fn read_boot_id() -> String {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+pub struct TrafficAccounting {
+    ledger: TrafficLedger,
+    path: Option<PathBuf>,
+    last_save: Instant,
+}
+
+impl TrafficAccounting {
+    pub fn load() -> Self {
+        let path = ledger_path();
+        let boot_id = read_boot_id();
+        let ledger = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|content| TrafficLedger::parse(&content, &boot_id))
+            .unwrap_or_else(|| TrafficLedger::new(&boot_id));
+        Self {
+            ledger,
+            path,
+            last_save: Instant::now(),
+        }
+    }
+
+    /// Loopback and veth pairs are skipped: their traffic never leaves the machine
+    /// and container veths come and go with random names.
+    pub fn update(&mut self, networks: &[DetailedNetInfo], caps: &[(String, u64)]) -> Vec<TrafficUsage> {
+        let samples: Vec<(String, u64, u64)> = networks
+            .iter()
+            .filter(|n| n.interface_type != "loopback" && n.interface_type != "veth")
+            .map(|n| (n.name.clone(), n.total_down, n.total_up))
+            .collect();
+        let now = Local::now().naive_local();
+        self.ledger.record(&samples, &now);
+
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            if let Err(e) = self.save() {
+                eprintln!("Failed to save traffic ledger: {}", e);
+            }
+            self.last_save = Instant::now();
+        }
+        self.ledger.usage(&now, caps)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = match &self.path {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tsv.tmp");
+        fs::write(&tmp, self.ledger.to_file_string())?;
+        fs::rename(&tmp, path)
+    }
+}
+
+impl Drop for TrafficAccounting {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, 30, 0).unwrap()
+    }
+
+    fn sample(name: &str, rx: u64, tx: u64) -> Vec<(String, u64, u64)> {
+        vec![(name.to_string(), rx, tx)]
+    }
+
+    #[test]
+    fn test_record_and_rollups() {
+        let mut ledger = TrafficLedger::new("boot-a");
+        // First sample of a new ledger is only a baseline
+        ledger.record(&sample("usb0", 5000, 1000), &at(2026, 9, 30, 23));
+        ledger.record(&sample("usb0", 8000, 1500), &at(2026, 9, 30, 23));
+        ledger.record(&sample("usb0", 9000, 1600), &at(2026, 10, 1, 0));
+        // Interface re-created: counters restart from zero
+        ledger.record(&sample("usb0", 200, 100), &at(2026, 10, 1, 1));
+
+        let usage = ledger.usage(&at(2026, 10, 1, 1), &[("usb0".to_string(), 10_000)]);
+        assert_eq!(usage.len(), 1);
+        let u = &usage[0];
+        assert_eq!((u.today.rx, u.today.tx), (1200, 200));
+        assert_eq!((u.yesterday.period.as_str(), u.yesterday.rx, u.yesterday.tx), ("2026-09-30", 3000, 500));
+        assert_eq!((u.this_month.rx, u.last_month.rx), (1200, 3000));
+        assert_eq!(u.last_month.period, "2026-09");
+        assert_eq!(u.hours.len(), 3);
+        assert_eq!(u.hours[0].period, "2026-09-30T23");
+        assert_eq!(u.months.len(), 2);
+        assert_eq!(u.monthly_cap, Some(10_000));
+    }
+
+    #[test]
+    fn test_ledger_round_trip_and_reboot() {
+        let mut ledger = TrafficLedger::new("boot-a");
+        ledger.record(&sample("wlan0", 1000, 100), &at(2026, 10, 18, 9));
+        ledger.record(&sample("wlan0", 4000, 400), &at(2026, 10, 18, 9));
+        let saved = ledger.to_file_string();
+
+        // Same boot: traffic while the app was closed is picked up from the counters
+        let mut same = TrafficLedger::parse(&saved, "boot-a");
+        same.record(&sample("wlan0", 6000, 600), &at(2026, 10, 18, 10));
+        let today = &same.usage(&at(2026, 10, 18, 10), &[])[0].today;
+        assert_eq!((today.rx, today.tx), (5000, 500));
+
+        // After a reboot the counters restart, so everything since boot is new
+        let mut rebooted = TrafficLedger::parse(&saved, "boot-b");
+        rebooted.record(&sample("wlan0", 700, 70), &at(2026, 10, 18, 11));
+        let today = &rebooted.usage(&at(2026, 10, 18, 11), &[])[0].today;
+        assert_eq!((today.rx, today.tx), (3700, 370));
+
+        let mut long = TrafficLedger::new("boot-a");
+        for h in 0..60u64 {
+            long.record(&sample("eth0", h * 10, 0), &(at(2026, 10, 1, 0) + chrono::Duration::hours(h as i64)));
+        }
+        let usage = &long.usage(&at(2026, 10, 3, 11), &[])[0];
+        assert_eq!(usage.hours.len(), 48);
+        assert_eq!(usage.hours[0].period, "2026-10-01T12");
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+
+use crate::types::WifiInfo;
+
+// Most drivers report link quality out of 70 in /proc/net/wireless
+const MAX_LINK_QUALITY: f32 = 70.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WirelessStats {
+    pub link: f32,
+    pub level_dbm: f32,
+    pub noise_dbm: Option<f32>,
+}
+
+/// Per-interface link quality from /proc/net/wireless (two header lines, then
+/// `wlp2s0: 0000   54.  -56.  -256 ...`). A noise of -256 means "not reported".
+pub fn parse_proc_net_wireless(content: &str) -> HashMap<String, WirelessStats> {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let fields: Vec<f32> = rest
+                .split_whitespace()
+                .skip(1)
+                .take(3)
+                .filter_map(|f| f.trim_end_matches('.').parse().ok())
+                .collect();
+            if fields.len() < 3 {
+                return None;
+            }
+            Some((
+                name.trim().to_string(),
+                WirelessStats {
+                    link: fields[0],
+                    level_dbm: fields[1],
+                    noise_dbm: Some(fields[2]).filter(|n| *n > -256.0),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Parses `iw dev <if> link`, which reads the nl80211 station info for the current AP.
+pub fn parse_iw_link(content: &str, interface: &str) -> WifiInfo {
+    let mut info = WifiInfo {
+        interface: interface.to_string(),
+        ..Default::default()
+    };
+
+    for line in content.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("Connected to ") {
+            info.connected = true;
+            info.bssid = rest.split_whitespace().next().unwrap_or("").to_string();
+        } else if let Some(ssid) = line.strip_prefix("SSID: ") {
+            info.ssid = ssid.to_string();
+        } else if let Some(freq) = line.strip_prefix("freq: ") {
+            info.frequency_mhz = freq.trim().parse::<f32>().ok().map(|f| f as u32);
+        } else if let Some(signal) = line.strip_prefix("signal: ") {
+            info.signal_dbm = signal.split_whitespace().next().and_then(|s| s.parse().ok());
+        } else if let Some(rate) = line.strip_prefix("rx bitrate: ") {
+            info.rx_bitrate_mbps = rate.split_whitespace().next().and_then(|r| r.parse().ok());
+        } else if let Some(rate) = line.strip_prefix("tx bitrate: ") {
+            info.tx_bitrate_mbps = rate.split_whitespace().next().and_then(|r| r.parse().ok());
+        }
+    }
+
+    if let Some((channel, band)) = info.frequency_mhz.and_then(channel_for_frequency) {
+        info.channel = Some(channel);
+        info.band = band.to_string();
+    }
+    info
+}
+
+pub fn channel_for_frequency(freq: u32) -> Option<(u32, &'static str)> {
+    match freq {
+        2484 => Some((14, "2.4 GHz")),
+        2412..=2472 => Some(((freq - 2407) / 5, "2.4 GHz")),
+        5160..=5885 => Some(((freq - 5000) / 5, "5 GHz")),
+        5955..=7115 => Some(((freq - 5950) / 5, "6 GHz")),
+        _ => None,
+    }
+}
+
+/// Rough 0-100 scale used by NetworkManager: -100 dBm or worse is 0, -50 dBm or better is 100.
+pub fn signal_percent(dbm: f32) -> f32 {
+    ((dbm + 100.0) * 2.0).clamp(0.0, 100.0)
+}
+
+pub fn read_wifi(interfaces: &[String]) -> Vec<WifiInfo> {
+    let proc_stats = fs::read_to_string("/proc/net/wireless")
+        .map(|c| parse_proc_net_wireless(&c))
+        .unwrap_or_default();
+
+    interfaces
+        .iter()
+        .map(|iface| {
+            let mut info = Command::new("iw")
+                .args(["dev", iface, "link"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| parse_iw_link(&String::from_utf8_lossy(&o.stdout), iface))
+                .unwrap_or_else(|| WifiInfo {
+                    interface: iface.clone(),
+                    ..Default::default()
+                });
+
+            if let Some(stats) = proc_stats.get(iface) {
+                // Without iw, an entry in /proc/net/wireless still means we are associated
+                info.connected |= stats.level_dbm < 0.0;
+                info.signal_dbm = info.signal_dbm.or(Some(stats.level_dbm).filter(|l| *l < 0.0));
+                info.noise_dbm = stats.noise_dbm;
+                info.link_quality = Some((stats.link / MAX_LINK_QUALITY * 100.0).clamp(0.0, 100.0));
+            }
+            info
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_net_wireless() {
+        let content = "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE\n \
+                       face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22\n\
+                       wlp2s0: 0000   54.  -56.  -256        0      0      0      0     12        0\n";
+        let stats = parse_proc_net_wireless(content);
+        assert_eq!(stats["wlp2s0"], WirelessStats { link: 54.0, level_dbm: -56.0, noise_dbm: None });
+    }
+
+    #[test]
+    fn test_parse_iw_link() {
+        let content = "Connected to aa:bb:cc:dd:ee:ff (on wlp2s0)\n\
+                       \tSSID: Office 5G\n\
+                       \tfreq: 5180.0\n\
+                       \tRX: 123456 bytes (789 packets)\n\
+                       \tsignal: -52 dBm\n\
+                       \trx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2\n\
+                       \ttx bitrate: 650.0 MBit/s VHT-MCS 7 80MHz short GI VHT-NSS 2\n";
+        let info = parse_iw_link(content, "wlp2s0");
+        assert!(info.connected);
+        assert_eq!(info.bssid, "aa:bb:cc:dd:ee:ff");
+        assert_eq!(info.ssid, "Office 5G");
+        assert_eq!(info.frequency_mhz, Some(5180));
+        assert_eq!(info.channel, Some(36));
+        assert_eq!(info.band, "5 GHz");
+        assert_eq!(info.signal_dbm, Some(-52.0));
+        assert_eq!(info.rx_bitrate_mbps, Some(866.7));
+        assert_eq!(info.tx_bitrate_mbps, Some(650.0));
+
+        assert!(!parse_iw_link("Not connected.\n", "wlp2s0").connected);
+        assert_eq!(channel_for_frequency(2437), Some((6, "2.4 GHz")));
+        assert_eq!(channel_for_frequency(6115), Some((33, "6 GHz")));
+        assert_eq!(signal_percent(-52.0), 96.0);
+    }
+}
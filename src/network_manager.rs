@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use glib::variant::ObjectPath;
+use glib::{ToVariant, Variant};
+
+use crate::types::NmProfile;
+
+const NM_BUS: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_IFACE: &str = "org.freedesktop.NetworkManager";
+const NM_SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+const NM_SETTINGS_IFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const NM_CONNECTION_IFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+const NM_ACTIVE_IFACE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const NM_DEVICE_IFACE: &str = "org.freedesktop.NetworkManager.Device";
+
+/// Thin client for the NetworkManager D-Bus API on the system bus. Privileged
+/// calls are authorised by NetworkManager through polkit, which prompts the
+/// same way pkexec does for `SystemManager`.
+pub struct NetworkManagerClient {
+    conn: gio::DBusConnection,
+}
+
+impl NetworkManagerClient {
+    /// `None` when there is no system bus or NetworkManager is not running.
+    pub fn connect() -> Option<Self> {
+        let conn = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE).ok()?;
+        let client = Self { conn };
+        let reply = client
+            .call("org.freedesktop.DBus", "/org/freedesktop/DBus", "org.freedesktop.DBus", "NameHasOwner", Some(&(NM_BUS,).to_variant()))
+            .ok()?;
+        reply.child_value(0).get::<bool>().filter(|owned| *owned).map(|_| client)
+    }
+
+    fn call(&self, dest: &str, path: &str, iface: &str, method: &str, params: Option<&Variant>) -> Result<Variant, String> {
+        self.conn
+            .call_sync(
+                Some(dest),
+                path,
+                iface,
+                method,
+                params,
+                None,
+                gio::DBusCallFlags::ALLOW_INTERACTIVE_AUTHORIZATION,
+                -1,
+                gio::Cancellable::NONE,
+            )
+            .map_err(|e| e.message().to_string())
+    }
+
+    fn property(&self, path: &str, iface: &str, name: &str) -> Result<Variant, String> {
+        let reply = self.call(NM_BUS, path, "org.freedesktop.DBus.Properties", "Get", Some(&(iface, name).to_variant()))?;
+        reply
+            .child_value(0)
+            .as_variant()
+            .ok_or_else(|| format!("{} has no property {}", path, name))
+    }
+
+    fn object_path(&self, path: &str, iface: &str, name: &str) -> Option<String> {
+        self.property(path, iface, name)
+            .ok()?
+            .get::<ObjectPath>()
+            .map(String::from)
+            .filter(|p| p != "/")
+    }
+
+    /// Saved connection profiles, active ones first.
+    pub fn list_profiles(&self) -> Result<Vec<NmProfile>, String> {
+        // Settings path -> (active connection path, interfaces it is active on)
+        let mut active: HashMap<String, (String, Vec<String>)> = HashMap::new();
+        for ac in object_paths(&self.property(NM_PATH, NM_IFACE, "ActiveConnections")?) {
+            let devices = self
+                .property(&ac, NM_ACTIVE_IFACE, "Devices")
+                .map(|v| object_paths(&v))
+                .unwrap_or_default();
+            let interfaces = devices
+                .iter()
+                .filter_map(|d| self.property(d, NM_DEVICE_IFACE, "Interface").ok()?.get::<String>())
+                .collect();
+            if let Some(settings) = self.object_path(&ac, NM_ACTIVE_IFACE, "Connection") {
+                active.insert(settings, (ac, interfaces));
+            }
+        }
+
+        let reply = self.call(NM_BUS, NM_SETTINGS_PATH, NM_SETTINGS_IFACE, "ListConnections", None)?;
+        let mut profiles = Vec::new();
+        for path in object_paths(&reply.child_value(0)) {
+            let settings = self
+                .call(NM_BUS, &path, NM_CONNECTION_IFACE, "GetSettings", None)?
+                .child_value(0)
+                .get::<HashMap<String, HashMap<String, Variant>>>()
+                .unwrap_or_default();
+            let field = |key: &str| {
+                settings
+                    .get("connection")
+                    .and_then(|c| c.get(key))
+                    .and_then(|v| v.get::<String>())
+                    .unwrap_or_default()
+            };
+            let (active_path, interfaces) = active.remove(&path).unwrap_or_default();
+            profiles.push(NmProfile {
+                id: field("id"),
+                uuid: field("uuid"),
+                kind: field("type"),
+                interface: if interfaces.is_empty() { field("interface-name") } else { interfaces.join(", ") },
+                active: !active_path.is_empty(),
+                active_path,
+                path,
+            });
+        }
+        profiles.sort_by(|a, b| b.active.cmp(&a.active).then_with(|| a.id.cmp(&b.id)));
+        Ok(profiles)
+    }
+
+    /// Activates a saved profile on whichever device NetworkManager picks for it.
+    pub fn activate(&self, profile_path: &str) -> Result<(), String> {
+        self.activate_on(profile_path, "/")
+    }
+
+    fn activate_on(&self, profile_path: &str, device_path: &str) -> Result<(), String> {
+        let profile = ObjectPath::try_from(profile_path).map_err(|e| e.to_string())?;
+        let device = ObjectPath::try_from(device_path).map_err(|e| e.to_string())?;
+        let specific = ObjectPath::try_from("/").map_err(|e| e.to_string())?;
+        self.call(NM_BUS, NM_PATH, NM_IFACE, "ActivateConnection", Some(&(profile, device, specific).to_variant()))
+            .map(|_| ())
+    }
+
+    pub fn deactivate(&self, active_path: &str) -> Result<(), String> {
+        let active = ObjectPath::try_from(active_path).map_err(|e| e.to_string())?;
+        self.call(NM_BUS, NM_PATH, NM_IFACE, "DeactivateConnection", Some(&(active,).to_variant()))
+            .map(|_| ())
+    }
+
+    /// Whether NetworkManager has an active connection on the interface.
+    pub fn manages(&self, interface: &str) -> bool {
+        self.active_profile(interface).is_ok()
+    }
+
+    /// Re-activates the connection currently active on `interface`, which
+    /// restarts DHCP. Fails if NetworkManager does not manage the interface.
+    pub fn renew(&self, interface: &str) -> Result<(), String> {
+        let (device, profile) = self.active_profile(interface)?;
+        self.activate_on(&profile, &device)
+    }
+
+    // (device path, settings path) of the connection active on the interface
+    fn active_profile(&self, interface: &str) -> Result<(String, String), String> {
+        let device = self
+            .call(NM_BUS, NM_PATH, NM_IFACE, "GetDeviceByIpIface", Some(&(interface,).to_variant()))?
+            .child_value(0)
+            .get::<ObjectPath>()
+            .map(String::from)
+            .ok_or_else(|| format!("{} is not known to NetworkManager", interface))?;
+        let active = self
+            .object_path(&device, NM_DEVICE_IFACE, "ActiveConnection")
+            .ok_or_else(|| format!("{} has no active NetworkManager connection", interface))?;
+        let profile = self
+            .object_path(&active, NM_ACTIVE_IFACE, "Connection")
+            .ok_or_else(|| format!("{} has no active NetworkManager connection", interface))?;
+        Ok((device, profile))
+    }
+}
+
+fn object_paths(value: &Variant) -> Vec<String> {
+    value
+        .get::<Vec<ObjectPath>>()
+        .unwrap_or_default()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
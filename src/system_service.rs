@@ -6,6 +6,8 @@ use std::collections::{HashMap, HashSet};
 use crate::types::{ServiceInfo, LogEntry, ConfigItem};
 use chrono::Local;
 
+const FSTAB_FILE: &str = "/etc/fstab";
+
 pub struct SystemManager {
     has_sudo: bool,
 }
@@ -83,13 +85,21 @@ impl SystemManager {
 
                     let is_running = status_str == "Running" || status_str == "Starting" || status_str == "Reloading";
 
+                    let short_name = name.replace(".service", "");
+                    let pressure = if is_running {
+                        crate::monitors::pressure::service_pressure(&short_name)
+                    } else {
+                        None
+                    };
+
                     services.push(ServiceInfo {
-                        name: name.replace(".service", ""),
+                        name: short_name,
                         description,
                         status: status_str,
                         enabled: is_enabled,
                         can_start: !is_running && self.has_sudo,
                         can_stop: is_running && self.has_sudo,
+                        pressure,
                     });
                 }
             }
@@ -104,13 +114,21 @@ impl SystemManager {
                  let status_str = if active == "active" { "Running" } else { "Stopped" };
                  let is_running = status_str == "Running";
                  
+                 let short_name = name.replace(".service", "");
+                 let pressure = if is_running {
+                     crate::monitors::pressure::service_pressure(&short_name)
+                 } else {
+                     None
+                 };
+
                  services.push(ServiceInfo {
-                     name: name.replace(".service", ""),
+                     name: short_name,
                      description: description.clone(),
                      status: status_str.to_string(),
                      enabled: false,
                      can_start: !is_running && self.has_sudo,
                      can_stop: is_running && self.has_sudo,
+                     pressure,
                  });
             }
         }
@@ -120,25 +138,30 @@ impl SystemManager {
         services
     }
 
-    fn run_systemctl(&self, action: &str, service_name: &str) -> Result<(), String> {
-        let svc = format!("{}.service", service_name);
+    fn run_privileged(&self, program: &str, args: &[&str]) -> Result<(), String> {
         let output = if self.has_sudo {
-            Command::new("systemctl")
-                .args(&[action, &svc])
+            Command::new(program)
+                .args(args)
                 .output()
         } else {
             Command::new("pkexec")
-                .args(&["systemctl", action, &svc])
+                .arg(program)
+                .args(args)
                 .output()
         };
 
         match output {
             Ok(out) if out.status.success() => Ok(()),
-            Ok(out) => Err(String::from_utf8_lossy(&out.stderr).to_string()),
+            Ok(out) => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
             Err(e) => Err(e.to_string()),
         }
     }
 
+    fn run_systemctl(&self, action: &str, service_name: &str) -> Result<(), String> {
+        let svc = format!("{}.service", service_name);
+        self.run_privileged("systemctl", &[action, &svc])
+    }
+
     pub fn start_service(&self, service_name: &str) -> Result<(), String> {
         self.run_systemctl("start", service_name)
     }
@@ -384,6 +407,284 @@ impl SystemManager {
         Ok(backup_file)
     }
 
+    /// Reads the active ruleset without escalating, since it is polled. nft is
+    /// preferred; iptables-save covers legacy setups. Both need root.
+    pub fn get_firewall_ruleset(&self) -> crate::types::FirewallRuleset {
+        let mut ruleset = crate::types::FirewallRuleset::default();
+
+        if command_exists("nft") {
+            match Command::new("nft").args(["-j", "list", "ruleset"]).output() {
+                Ok(out) if out.status.success() => {
+                    match crate::firewall::parse_nft_json(&String::from_utf8_lossy(&out.stdout)) {
+                        Ok(tables) => {
+                            ruleset.backend = "nftables".to_string();
+                            ruleset.tables = tables;
+                            return ruleset;
+                        }
+                        Err(e) => ruleset.error = Some(format!("Could not parse nft output: {}", e)),
+                    }
+                }
+                Ok(out) => ruleset.error = Some(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+                Err(e) => ruleset.error = Some(e.to_string()),
+            }
+        }
+
+        for (program, family) in [("iptables-save", "ip"), ("ip6tables-save", "ip6")] {
+            match Command::new(program).arg("-c").output() {
+                Ok(out) if out.status.success() => {
+                    ruleset.tables.extend(crate::firewall::parse_iptables_save(&String::from_utf8_lossy(&out.stdout), family));
+                    ruleset.backend = "iptables".to_string();
+                }
+                Ok(out) if ruleset.error.is_none() => {
+                    ruleset.error = Some(String::from_utf8_lossy(&out.stderr).trim().to_string());
+                }
+                _ => {}
+            }
+        }
+
+        if ruleset.backend.is_empty() {
+            let reason = ruleset.error.take().filter(|e| !e.is_empty()).unwrap_or_else(|| "nft and iptables-save not found".to_string());
+            ruleset.error = Some(if self.has_sudo { reason } else { format!("Reading the ruleset requires root ({})", reason) });
+        } else {
+            ruleset.error = None;
+        }
+        ruleset
+    }
+
+    /// `None` when firewalld is not installed or not running.
+    pub fn get_firewalld_zones(&self) -> Option<Vec<crate::types::FirewalldZone>> {
+        let state = Command::new("firewall-cmd").arg("--state").output().ok()?;
+        if !state.status.success() {
+            return None;
+        }
+        let out = Command::new("firewall-cmd").arg("--list-all-zones").output().ok()?;
+        Some(crate::firewall::parse_firewalld_zones(&String::from_utf8_lossy(&out.stdout)))
+    }
+
+    /// Adds or removes a service (`ssh`) or port (`8080/tcp`) in a firewalld zone,
+    /// both at runtime and in the permanent configuration.
+    pub fn firewalld_change(&self, zone: &str, add: bool, kind: &str, value: &str) -> Result<(), String> {
+        crate::firewall::validate_firewalld_value(kind, value)?;
+        let zone_arg = format!("--zone={}", zone);
+        let change_arg = format!("--{}-{}={}", if add { "add" } else { "remove" }, kind, value);
+        // Both in one escalation; --reload would also drop unrelated runtime-only changes
+        let script = r#"firewall-cmd "$1" "$2" && firewall-cmd --permanent "$1" "$2""#;
+        self.run_privileged("sh", &["-c", script, "sh", &zone_arg, &change_arg])
+    }
+
+    /// Mounts via the fstab entry when there is one, otherwise under /mnt/<name>.
+    pub fn mount_device(&self, device: &str) -> Result<String, String> {
+        let fstab = std::fs::read_to_string(FSTAB_FILE).unwrap_or_default();
+        let listed = crate::fstab::parse_fstab(&fstab).into_iter().find(|e| e.spec == device);
+        if let Some(entry) = listed {
+            self.run_privileged("mount", &[device])?;
+            return Ok(entry.file);
+        }
+
+        let name = device.rsplit('/').next().unwrap_or(device);
+        let target = format!("/mnt/{}", name);
+        self.run_privileged("sh", &["-c", r#"mkdir -p "$2" && mount "$1" "$2""#, "sh", device, &target])?;
+        Ok(target)
+    }
+
+    pub fn unmount(&self, target: &str) -> Result<(), String> {
+        self.run_privileged("umount", &[target])
+    }
+
+    pub fn remount_read_only(&self, target: &str) -> Result<(), String> {
+        self.run_privileged("mount", &["-o", "remount,ro", target])
+    }
+
+    pub fn eject(&self, device: &str) -> Result<(), String> {
+        self.run_privileged("eject", &[device])
+    }
+
+    pub fn set_interface_state(&self, interface: &str, up: bool) -> Result<(), String> {
+        let state = if up { "up" } else { "down" };
+        self.run_privileged("ip", &["link", "set", "dev", interface, state])
+    }
+
+    /// Renews the DHCP lease through whichever client manages the interface.
+    /// Returns the name of the client that handled it.
+    pub fn renew_dhcp(&self, interface: &str) -> Result<String, String> {
+        let nm = crate::network_manager::NetworkManagerClient::connect();
+        if let Some(nm) = nm.filter(|nm| nm.manages(interface)) {
+            nm.renew(interface)?;
+            return Ok("NetworkManager".to_string());
+        }
+
+        let ifindex = std::fs::read_to_string(format!("/sys/class/net/{}/ifindex", interface)).unwrap_or_default();
+        let networkd_link = format!("/run/systemd/netif/links/{}", ifindex.trim());
+        if std::fs::read_to_string(networkd_link).is_ok_and(|s| s.contains("ADMIN_STATE=configured")) {
+            self.run_privileged("networkctl", &["renew", interface])?;
+            return Ok("systemd-networkd".to_string());
+        }
+        if command_exists("dhcpcd") {
+            self.run_privileged("dhcpcd", &["-n", interface])?;
+            return Ok("dhcpcd".to_string());
+        }
+        if command_exists("dhclient") {
+            // Replaces a running instance from its pid file and asks for the current
+            // lease again, so the address is kept while it renews
+            self.run_privileged("dhclient", &["-nw", interface])?;
+            return Ok("dhclient".to_string());
+        }
+        Err(format!("No DHCP client found for {}", interface))
+    }
+
+    pub fn list_network_profiles(&self) -> Result<Vec<crate::types::NmProfile>, String> {
+        crate::network_manager::NetworkManagerClient::connect()
+            .ok_or_else(|| "NetworkManager is not running".to_string())?
+            .list_profiles()
+    }
+
+    pub fn activate_network_profile(&self, profile_path: &str) -> Result<(), String> {
+        crate::network_manager::NetworkManagerClient::connect()
+            .ok_or_else(|| "NetworkManager is not running".to_string())?
+            .activate(profile_path)
+    }
+
+    pub fn deactivate_network_profile(&self, active_path: &str) -> Result<(), String> {
+        crate::network_manager::NetworkManagerClient::connect()
+            .ok_or_else(|| "NetworkManager is not running".to_string())?
+            .deactivate(active_path)
+    }
+
+    pub fn get_fstab(&self) -> Result<String, String> {
+        std::fs::read_to_string(FSTAB_FILE).map_err(|e| e.to_string())
+    }
+
+    /// Validates, backs up the current file as `/etc/fstab.bak.<timestamp>` and writes
+    /// the new content. Returns the backup path.
+    pub fn set_fstab(&self, content: &str) -> Result<String, String> {
+        let errors = crate::fstab::validate_fstab(&crate::fstab::parse_fstab(content));
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let backup_file = format!("{}.bak.{}", FSTAB_FILE, timestamp);
+        // Backup, then write a temp file next to fstab and rename it over, all in
+        // one escalation so a failed write never leaves a truncated fstab
+        let script = r#"cp -p "$1" "$2" || exit 1
+tmp=$(mktemp /etc/.fstab.XXXXXX) || exit 1
+if cat > "$tmp" && chmod 644 "$tmp" && mv "$tmp" "$1"; then exit 0; fi
+rm -f "$tmp"
+exit 1"#;
+
+        let mut cmd = if self.has_sudo {
+            Command::new("sh")
+        } else {
+            let mut cmd = Command::new("pkexec");
+            cmd.arg("sh");
+            cmd
+        };
+        let mut child = cmd
+            .args(["-c", script, "sh", FSTAB_FILE, &backup_file])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(content.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!("Writing {} failed: {}", FSTAB_FILE, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(backup_file)
+    }
+
+    /// Writes each value to its sysfs attribute in order, in one escalation.
+    /// Each value gets its own write(2), which multi-command attributes like
+    /// amdgpu's fan_curve require.
+    pub fn write_sysfs(&self, writes: &[(String, String)]) -> Result<(), String> {
+        if let Some((path, _)) = writes.iter().find(|(p, _)| !p.starts_with("/sys/") || p.contains("..")) {
+            return Err(format!("Refusing to write outside /sys: {}", path));
+        }
+        let mut args = vec![
+            "-c",
+            r#"while [ $# -gt 1 ]; do printf '%s\n' "$2" > "$1" || exit 1; shift 2; done"#,
+            "sh",
+        ];
+        for (path, value) in writes {
+            args.push(path);
+            args.push(value);
+        }
+        self.run_privileged("sh", &args)
+    }
+
+    /// Writes several GPU settings in one escalation, carrying on past failures.
+    /// Returns a "key: error" line for each write that failed.
+    pub fn write_gpu_settings(&self, settings: &[(String, crate::gpu_control::GpuSetting)]) -> Vec<String> {
+        use crate::gpu_control::GpuSetting;
+
+        // key, kind, target, value
+        let mut items: Vec<[String; 4]> = Vec::new();
+        for (key, setting) in settings {
+            match setting {
+                GpuSetting::Sysfs(writes) => {
+                    if let Some((path, _)) = writes.iter().find(|(p, _)| !p.starts_with("/sys/") || p.contains("..")) {
+                        return vec![format!("{}: Refusing to write outside /sys: {}", key, path)];
+                    }
+                    for (path, value) in writes {
+                        items.push([key.clone(), "sysfs".into(), path.clone(), value.clone()]);
+                    }
+                }
+                GpuSetting::NvidiaPowerLimit { index, milliwatts } => {
+                    let watts = format!("{:.2}", *milliwatts as f64 / 1000.0);
+                    items.push([key.clone(), "pl".into(), index.to_string(), watts]);
+                }
+                GpuSetting::NvidiaPersistence { index, enabled } => {
+                    let mode = if *enabled { "1" } else { "0" };
+                    items.push([key.clone(), "pm".into(), index.to_string(), mode.into()]);
+                }
+            }
+        }
+        if items.is_empty() {
+            return Vec::new();
+        }
+
+        let script = r#"status=0
+while [ $# -gt 3 ]; do
+    case "$2" in
+        sysfs) err=$( { printf '%s\n' "$4" > "$3"; } 2>&1 ) ;;
+        pl) err=$(nvidia-smi -i "$3" -pl "$4" 2>&1 >/dev/null) ;;
+        pm) err=$(nvidia-smi -i "$3" -pm "$4" 2>&1 >/dev/null) ;;
+    esac || { echo "$1: $err" >&2; status=1; }
+    shift 4
+done
+exit $status"#;
+        let mut args = vec!["-c", script, "sh"];
+        args.extend(items.iter().flatten().map(String::as_str));
+        match self.run_privileged("sh", &args) {
+            Ok(()) => Vec::new(),
+            Err(e) => e.lines().map(str::to_string).collect(),
+        }
+    }
+
+    /// NVML itself is used when already root; otherwise nvidia-smi runs under pkexec.
+    pub fn nvidia_set_power_limit(&self, index: u32, milliwatts: u32) -> Result<(), String> {
+        if self.has_sudo {
+            let nvml = crate::monitors::nvml::Nvml::load()?;
+            return nvml.set_power_limit(index, milliwatts);
+        }
+        let watts = format!("{:.2}", milliwatts as f64 / 1000.0);
+        self.run_privileged("nvidia-smi", &["-i", &index.to_string(), "-pl", &watts])
+    }
+
+    pub fn nvidia_set_persistence(&self, index: u32, enabled: bool) -> Result<(), String> {
+        if self.has_sudo {
+            let nvml = crate::monitors::nvml::Nvml::load()?;
+            return nvml.set_persistence_mode(index, enabled);
+        }
+        self.run_privileged("nvidia-smi", &["-i", &index.to_string(), "-pm", if enabled { "1" } else { "0" }])
+    }
+
     pub fn set_hostname(&self, new_hostname: &str) -> Result<(), String> {
         if !self.has_sudo {
             return Err("Insufficient privileges (root required)".to_string());
@@ -415,4 +716,11 @@ impl Default for SystemManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+fn command_exists(name: &str) -> bool {
+    std::env::var("PATH").unwrap_or_default()
+        .split(':')
+        .chain(["/usr/sbin", "/sbin"])
+        .any(|dir| Path::new(dir).join(name).is_file())
+}
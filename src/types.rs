@@ -42,6 +42,7 @@ pub struct ContainerInfo {
     pub disk_w: String,
     pub image: String,
     pub ports: String,
+    pub pressure: Option<PressureStats>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -60,9 +61,76 @@ pub struct GpuInfo {
     pub fan_speed: Option<u32>,
     pub utilization_history: Vec<u32>,
     pub memory_history: Vec<u32>,
+    pub temperature_history: Vec<u32>,
+    // mW
+    pub power_history: Vec<u32>,
+    // MHz
+    pub clock_history: Vec<u32>,
     pub pci_link_gen: Option<u32>,
     pub pci_link_width: Option<u32>,
     pub driver_version: String,
+    pub backend: String,
+    pub power_limit: Option<u32>,
+    pub throttle_reasons: Vec<String>,
+    pub ecc_corrected: Option<u64>,
+    pub ecc_uncorrected: Option<u64>,
+    pub encoder_utilization: Option<u32>,
+    pub decoder_utilization: Option<u32>,
+    pub processes: Vec<GpuProcess>,
+    pub engines: Vec<(String, u32)>,
+    pub rc6_residency: Option<u32>,
+    pub telemetry_error: Option<String>,
+    pub hotspot_temperature: Option<u32>,
+    pub fan_pwm: Option<u32>,
+    pub memory_activity: Option<u32>,
+    pub gtt_used: u64,
+    pub gtt_total: u64,
+    pub sclk_levels: Vec<(u32, bool)>,
+    pub mclk_levels: Vec<(u32, bool)>,
+    pub power_rails: Vec<(String, u32)>,
+    pub sysfs_path: Option<String>,
+    pub nvidia_index: Option<u32>,
+}
+
+/// Power limits are in mW, matching `GpuInfo::power_usage`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NvidiaGpuControls {
+    pub power_limit: Option<u32>,
+    pub power_limit_min: Option<u32>,
+    pub power_limit_max: Option<u32>,
+    pub power_limit_default: Option<u32>,
+    pub persistence: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FanCurve {
+    /// (temperature °C, fan speed %)
+    pub points: Vec<(u32, u32)>,
+    pub temp_range: (u32, u32),
+    pub speed_range: (u32, u32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AmdGpuControls {
+    pub device_path: String,
+    pub hwmon_path: Option<String>,
+    pub performance_level: Option<String>,
+    pub power_cap: Option<u32>,
+    pub power_cap_min: Option<u32>,
+    pub power_cap_max: Option<u32>,
+    pub power_cap_default: Option<u32>,
+    pub pwm_enable: Option<u32>,
+    pub pwm: Option<u32>,
+    pub fan_curve: Option<FanCurve>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub name: String,
+    pub kind: String,
+    pub memory_used: Option<u64>,
+    pub sm_utilization: Option<u32>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -92,6 +160,55 @@ pub struct CoreInfo {
     pub temp: Option<f32>,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoreKind {
+    #[default]
+    Standard,
+    Performance,
+    Efficiency,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CacheInfo {
+    pub level: u8,
+    pub cache_type: String,
+    pub size: u64,
+    pub shared_cpus: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LogicalCpu {
+    pub id: usize,
+    pub package_id: u32,
+    pub die_id: u32,
+    pub core_id: u32,
+    pub thread_siblings: Vec<usize>,
+    pub l3_group: Option<usize>,
+    pub kind: CoreKind,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CpuTopology {
+    pub cpus: Vec<LogicalCpu>,
+    pub caches: Vec<CacheInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PhysicalCore {
+    pub package_id: u32,
+    pub core_id: u32,
+    pub kind: CoreKind,
+    pub threads: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CpuDomain {
+    pub label: String,
+    pub package_id: u32,
+    pub l3_size: Option<u64>,
+    pub cores: Vec<PhysicalCore>,
+}
+
 #[derive(Clone, Debug, Default)]
 #[allow(dead_code)]
 pub struct DetailedDiskInfo {
@@ -101,15 +218,205 @@ pub struct DetailedDiskInfo {
     pub total: u64,
     pub free: u64,
     pub used: u64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    pub inodes_free: u64,
     pub read_rate: u64,
     pub write_rate: u64,
     pub read_ops: u64,
     pub write_ops: u64,
+    pub read_latency_ms: f64,
+    pub write_latency_ms: f64,
+    pub queue_depth: f64,
+    pub util_pct: f64,
     pub is_ssd: Option<bool>,
     pub is_nvme: bool,
     pub temp: Option<f32>,
     pub health_pct: Option<u8>,
     pub power_cycles: Option<u64>,
+    pub verdict: HealthVerdict,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HealthVerdict {
+    Pass,
+    Warn,
+    Fail,
+    #[default]
+    Unknown,
+}
+
+impl HealthVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthVerdict::Pass => "PASS",
+            HealthVerdict::Warn => "WARN",
+            HealthVerdict::Fail => "FAIL",
+            HealthVerdict::Unknown => "N/A",
+        }
+    }
+}
+
+/// SMART / NVMe health for one physical drive. Byte counters are in bytes.
+#[derive(Clone, Debug, Default)]
+pub struct DriveHealth {
+    pub device: String,
+    pub model: String,
+    pub serial: String,
+    pub firmware: String,
+    pub source: String,
+    pub verdict: HealthVerdict,
+    pub notes: Vec<String>,
+    pub smart_passed: Option<bool>,
+    pub critical_warning: Option<u8>,
+    pub temperature: Option<f32>,
+    pub power_on_hours: Option<u64>,
+    pub power_cycles: Option<u64>,
+    pub unsafe_shutdowns: Option<u64>,
+    pub media_errors: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub uncorrectable_sectors: Option<u64>,
+    pub data_read: Option<u64>,
+    pub data_written: Option<u64>,
+    pub available_spare: Option<u8>,
+    pub spare_threshold: Option<u8>,
+    pub percentage_used: Option<u8>,
+    pub self_test: Option<String>,
+    pub self_test_passed: Option<bool>,
+}
+
+/// A node of the block-device hierarchy: disk -> partitions -> dm/md -> filesystem.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockDevice {
+    pub name: String,
+    pub display_name: String,
+    pub kind: String,
+    pub size: u64,
+    pub model: String,
+    pub serial: String,
+    pub transport: String,
+    pub scheduler: String,
+    pub rotational: Option<bool>,
+    pub removable: bool,
+    pub read_only: bool,
+    pub fs_type: String,
+    pub label: String,
+    pub uuid: String,
+    pub mountpoints: Vec<String>,
+    pub children: Vec<BlockDevice>,
+}
+
+/// Usage versus limits for one user/group/project on a quota-enabled filesystem.
+/// Limits of 0 mean unlimited.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QuotaEntry {
+    pub mount_point: String,
+    pub kind: String,
+    pub name: String,
+    pub space_used: u64,
+    pub space_soft: u64,
+    pub space_hard: u64,
+    pub files_used: u64,
+    pub files_soft: u64,
+    pub files_hard: u64,
+    pub over_limit: bool,
+}
+
+/// One Linux software RAID array from /proc/mdstat.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MdArray {
+    pub name: String,
+    pub level: String,
+    pub active: bool,
+    pub read_only: bool,
+    pub devices: Vec<String>,
+    pub failed_devices: Vec<String>,
+    pub spare_devices: Vec<String>,
+    pub total_disks: u32,
+    pub active_disks: u32,
+    pub member_status: String,
+    pub sync_action: Option<String>,
+    pub sync_progress: Option<f64>,
+    pub sync_finish: Option<String>,
+}
+
+impl MdArray {
+    pub fn is_degraded(&self) -> bool {
+        self.active_disks < self.total_disks || !self.failed_devices.is_empty()
+    }
+}
+
+/// Per-device error counters of a btrfs filesystem.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BtrfsDevice {
+    pub devid: String,
+    pub missing: bool,
+    pub write_errs: u64,
+    pub read_errs: u64,
+    pub flush_errs: u64,
+    pub corruption_errs: u64,
+    pub generation_errs: u64,
+}
+
+impl BtrfsDevice {
+    pub fn total_errors(&self) -> u64 {
+        self.write_errs + self.read_errs + self.flush_errs + self.corruption_errs + self.generation_errs
+    }
+}
+
+/// Chunk allocation (bytes allocated, bytes used) per block group type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BtrfsFilesystem {
+    pub uuid: String,
+    pub label: String,
+    pub mountpoint: String,
+    pub disks: Vec<String>,
+    pub data: (u64, u64),
+    pub metadata: (u64, u64),
+    pub system: (u64, u64),
+    pub devices: Vec<BtrfsDevice>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZfsPool {
+    pub name: String,
+    pub state: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StorageHealth {
+    pub md_arrays: Vec<MdArray>,
+    pub btrfs: Vec<BtrfsFilesystem>,
+    pub zfs_pools: Vec<ZfsPool>,
+    pub warnings: Vec<String>,
+}
+
+/// One directory or file in a directory-size scan, largest children first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DirUsage {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub files: u64,
+    pub is_dir: bool,
+    pub children: Vec<DirUsage>,
+}
+
+/// Per-interval rates for one /proc/diskstats entry.
+#[derive(Clone, Debug, Default)]
+pub struct BlockDeviceIo {
+    pub name: String,
+    pub physical: bool,
+    pub read_rate: u64,
+    pub write_rate: u64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_latency_ms: f64,
+    pub write_latency_ms: f64,
+    pub queue_depth: f64,
+    pub util_pct: f64,
+    pub in_flight: u64,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -126,6 +433,244 @@ pub struct DetailedNetInfo {
     pub errors_tx: u64,
     pub interface_type: String,
     pub is_up: bool,
+    pub is_virtual: bool,
+    pub operstate: String,
+    pub carrier: Option<bool>,
+    pub speed_mbps: Option<u32>,
+    pub duplex: String,
+    pub mtu: u32,
+    pub mac: String,
+    pub addresses: Vec<String>,
+    pub driver: String,
+}
+
+/// Association details of a wireless interface. `signal_history` holds the
+/// signal as 0-100% so it can share a chart scale with link quality.
+#[derive(Clone, Debug, Default)]
+pub struct WifiInfo {
+    pub interface: String,
+    pub connected: bool,
+    pub ssid: String,
+    pub bssid: String,
+    pub frequency_mhz: Option<u32>,
+    pub channel: Option<u32>,
+    pub band: String,
+    pub signal_dbm: Option<f32>,
+    pub noise_dbm: Option<f32>,
+    pub link_quality: Option<f32>,
+    pub rx_bitrate_mbps: Option<f32>,
+    pub tx_bitrate_mbps: Option<f32>,
+    pub signal_history: VecDeque<f32>,
+}
+
+/// Bytes received/transmitted in one accounting period, keyed `2026-10-18T14`
+/// (hour), `2026-10-18` (day) or `2026-10` (month).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrafficPeriod {
+    pub period: String,
+    pub rx: u64,
+    pub tx: u64,
+}
+
+impl TrafficPeriod {
+    pub fn total(&self) -> u64 {
+        self.rx + self.tx
+    }
+}
+
+/// Persistent per-interface usage. The period lists are oldest first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrafficUsage {
+    pub interface: String,
+    pub today: TrafficPeriod,
+    pub yesterday: TrafficPeriod,
+    pub this_month: TrafficPeriod,
+    pub last_month: TrafficPeriod,
+    pub hours: Vec<TrafficPeriod>,
+    pub days: Vec<TrafficPeriod>,
+    pub months: Vec<TrafficPeriod>,
+    pub monthly_cap: Option<u64>,
+}
+
+/// Summary of a ping run. Round-trip times are in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PingStats {
+    pub sent: u32,
+    pub received: u32,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+    pub jitter_ms: f64,
+}
+
+impl PingStats {
+    pub fn loss_percent(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            (self.sent - self.received) as f64 / self.sent as f64 * 100.0
+        }
+    }
+}
+
+/// One query against one resolver from /etc/resolv.conf.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DnsLookup {
+    pub server: String,
+    pub record_type: String,
+    pub latency_ms: Option<f64>,
+    pub rcode: String,
+    pub answers: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TcpCheck {
+    pub address: String,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// A traceroute hop; `rtts_ms` has one entry per probe, `None` when it timed out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraceHop {
+    pub ttl: u8,
+    pub address: Option<String>,
+    pub rtts_ms: Vec<Option<f64>>,
+    pub note: String,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PsiValues {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total_us: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PsiResource {
+    pub some: PsiValues,
+    pub full: Option<PsiValues>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PressureStats {
+    pub cpu: Option<PsiResource>,
+    pub memory: Option<PsiResource>,
+    pub io: Option<PsiResource>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub swap_cached: u64,
+    pub shmem: u64,
+    pub slab_reclaimable: u64,
+    pub slab_unreclaimable: u64,
+    pub anon: u64,
+    pub mapped: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub kernel_stack: u64,
+    pub page_tables: u64,
+    pub committed: u64,
+    pub commit_limit: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+    pub zswap_pool: Option<u64>,
+    pub zswap_stored: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ZramDevice {
+    pub name: String,
+    pub disk_size: u64,
+    pub orig_data_size: u64,
+    pub compr_data_size: u64,
+    pub mem_used_total: u64,
+    pub algorithm: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ZswapInfo {
+    pub enabled: bool,
+    pub compressor: String,
+    pub max_pool_percent: u32,
+    pub pool_size: u64,
+    pub stored: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SwapDevice {
+    pub name: String,
+    pub swap_type: String,
+    pub size: u64,
+    pub used: u64,
+    pub priority: i32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBreakdown {
+    pub meminfo: MemInfo,
+    pub zram: Vec<ZramDevice>,
+    pub zswap: Option<ZswapInfo>,
+    pub swaps: Vec<SwapDevice>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OomKill {
+    pub timestamp: String,
+    pub pid: u32,
+    pub process: String,
+    pub cgroup: String,
+    pub global: bool,
+    pub total_vm: u64,
+    pub anon_rss: u64,
+    pub file_rss: u64,
+    pub shmem_rss: u64,
+}
+
+/// `growth_rate` is in bytes per minute over `window_secs`.
+#[derive(Clone, Debug, Default)]
+pub struct LeakSuspect {
+    pub pid: u32,
+    pub name: String,
+    pub start_rss: u64,
+    pub current_rss: u64,
+    pub window_secs: u64,
+    pub growth_rate: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OomWatch {
+    pub kill_count: Option<u64>,
+    pub kills: Vec<OomKill>,
+    pub leak_suspects: Vec<LeakSuspect>,
+}
+
+/// One SMBIOS type 17 memory device; `size` is 0 for an empty slot.
+#[derive(Clone, Debug, Default)]
+pub struct DimmInfo {
+    pub locator: String,
+    pub bank_locator: String,
+    pub size: u64,
+    pub memory_type: String,
+    pub form_factor: String,
+    pub speed_mts: Option<u32>,
+    pub configured_speed_mts: Option<u32>,
+    pub manufacturer: String,
+    pub serial: String,
+    pub part_number: String,
+    pub rank: Option<u8>,
+    pub configured_voltage_mv: Option<u16>,
 }
 
 #[derive(Clone, Debug)]
@@ -152,6 +697,7 @@ pub struct GlobalUsage {
     pub disk_write: u64,
     pub disk_read_ops: u64,
     pub disk_write_ops: u64,
+    pub disk_util: f64,
     pub memory_type: String,
     pub memory_generation: String,
     pub memory_speed: String,
@@ -163,6 +709,10 @@ pub struct GlobalUsage {
     pub disk_read_history: VecDeque<u64>,
     pub disk_write_history: VecDeque<u64>,
     pub gpu_history: VecDeque<u32>,
+    pub pressure: Option<PressureStats>,
+    pub cpu_pressure_history: VecDeque<f32>,
+    pub mem_pressure_history: VecDeque<f32>,
+    pub io_pressure_history: VecDeque<f32>,
     pub load_average: (f64, f64, f64),
     pub uptime: u64,
     pub boot_time: u64,
@@ -184,6 +734,7 @@ impl Default for GlobalUsage {
             disk_write: 0,
             disk_read_ops: 0,
             disk_write_ops: 0,
+            disk_util: 0.0,
             memory_type: "N/A".to_string(),
             memory_generation: "N/A".to_string(),
             memory_speed: "N/A".to_string(),
@@ -195,6 +746,10 @@ impl Default for GlobalUsage {
             disk_read_history: VecDeque::from(vec![0; 60]),
             disk_write_history: VecDeque::from(vec![0; 60]),
             gpu_history: VecDeque::from(vec![0; 60]),
+            pressure: None,
+            cpu_pressure_history: VecDeque::from(vec![0.0; 60]),
+            mem_pressure_history: VecDeque::from(vec![0.0; 60]),
+            io_pressure_history: VecDeque::from(vec![0.0; 60]),
             load_average: (0.0, 0.0, 0.0),
             uptime: 0,
             boot_time: 0,
@@ -206,13 +761,21 @@ impl Default for GlobalUsage {
 #[allow(dead_code)]
 pub struct SensorInfo {
     pub label: String,
-    pub chip: String,
+    pub chip: String,        // lm-sensors style, e.g. "k10temp-pci-00c3"
+    pub id: String,          // hwmon attribute, e.g. "temp1" or "in3"
     pub sensor_type: String, // "temp", "fan", "in", "power", "curr", "humidity", "intrusion"
     pub value: f64,
     pub unit: String,        // "°C", "RPM", "V", "W", "A", 
     pub temp: f32,           
+    pub min: Option<f32>,
     pub max: Option<f32>,
     pub critical: Option<f32>,
+    pub alarm: bool,
+    // Ignored in sensors.conf; still collected so the Sensors tab can show it on request
+    pub hidden: bool,
+    // Lowest and highest values seen since puls-g3 started
+    pub session_min: f64,
+    pub session_max: f64,
 }
 
 #[derive(Clone, Debug)]
@@ -228,8 +791,18 @@ pub struct DynamicData {
     pub global_usage: GlobalUsage,
     pub temperatures: SystemTemperatures,
     pub sensors: Vec<SensorInfo>,
+    pub memory: MemoryBreakdown,
+    pub oom: OomWatch,
+    pub block_io: Vec<BlockDeviceIo>,
+    pub drive_health: Vec<DriveHealth>,
+    pub block_devices: Vec<BlockDevice>,
+    pub storage_health: StorageHealth,
+    pub quotas: Result<Vec<QuotaEntry>, String>,
+    pub wifi: Vec<WifiInfo>,
+    pub traffic: Vec<TrafficUsage>,
     pub last_update: std::time::Instant,
     pub docker_error: Option<String>,
+    pub sensor_config_error: Option<String>,
     pub generation: u64,
 }
 
@@ -250,8 +823,18 @@ impl Default for DynamicData {
                 motherboard_temp: None,
             },
             sensors: Vec::new(),
+            memory: MemoryBreakdown::default(),
+            oom: OomWatch::default(),
+            block_io: Vec::new(),
+            drive_health: Vec::new(),
+            block_devices: Vec::new(),
+            storage_health: StorageHealth::default(),
+            quotas: Ok(Vec::new()),
+            wifi: Vec::new(),
+            traffic: Vec::new(),
             last_update: std::time::Instant::now(),
             docker_error: None,
+            sensor_config_error: None,
             generation: 0,
         }
     }
@@ -284,6 +867,8 @@ pub struct AppState {
     pub editing_config: Option<usize>,
     pub edit_buffer: String,
     pub has_sudo: bool,
+    /// `--safe`: privileged write actions are disabled in the GUI
+    pub safe_mode: bool,
     pub log_filter: String,
     pub service_status_modal: Option<(String, String)>,
     pub editing_filter: bool,
@@ -293,6 +878,10 @@ pub struct AppState {
     pub viewing_log: Option<LogEntry>,
     pub pending_config_confirmation: Option<(usize, String)>,
     pub pending_service_action: Option<(String, String)>,
+    pub cpu_topology: CpuTopology,
+    pub dimms: Vec<DimmInfo>,
+    pub firewall: FirewallRuleset,
+    pub firewalld_zones: Option<Vec<FirewalldZone>>,
 }
 
 #[derive(Clone, Debug)]
@@ -304,6 +893,7 @@ pub struct ServiceInfo {
     pub enabled: bool,
     pub can_start: bool,
     pub can_stop: bool,
+    pub pressure: Option<PressureStats>,
 }
 
 impl Default for ServiceInfo {
@@ -315,6 +905,7 @@ impl Default for ServiceInfo {
             enabled: false,
             can_start: false,
             can_stop: false,
+            pressure: None,
         }
     }
 }
@@ -339,6 +930,80 @@ impl Default for LogEntry {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FirewallRule {
+    pub handle: String,
+    pub text: String,
+    pub comment: String,
+    pub packets: Option<u64>,
+    pub bytes: Option<u64>,
+}
+
+/// `hook`/`policy` are empty for regular (non-base) chains. Counters are only
+/// set for iptables built-in chains, whose policy has its own counter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FirewallChain {
+    pub name: String,
+    pub hook: String,
+    pub policy: String,
+    pub packets: Option<u64>,
+    pub bytes: Option<u64>,
+    pub rules: Vec<FirewallRule>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FirewallTable {
+    pub family: String,
+    pub name: String,
+    pub chains: Vec<FirewallChain>,
+}
+
+/// The active packet filter ruleset; `backend` is "nftables" or "iptables".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FirewallRuleset {
+    pub backend: String,
+    pub tables: Vec<FirewallTable>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FirewalldZone {
+    pub name: String,
+    pub active: bool,
+    pub default: bool,
+    pub target: String,
+    pub interfaces: Vec<String>,
+    pub sources: Vec<String>,
+    pub services: Vec<String>,
+    pub ports: Vec<String>,
+}
+
+/// A saved NetworkManager connection profile. `active_path` is the D-Bus path of
+/// its active connection, empty when the profile is not active.
+#[derive(Clone, Debug, Default)]
+pub struct NmProfile {
+    pub path: String,
+    pub id: String,
+    pub uuid: String,
+    pub kind: String,
+    pub interface: String,
+    pub active: bool,
+    pub active_path: String,
+}
+
+/// One /etc/fstab line; `line` is 1-based so errors can point at it.
+#[derive(Clone, Debug, Default)]
+pub struct FstabEntry {
+    pub line: usize,
+    pub spec: String,
+    pub file: String,
+    pub vfstype: String,
+    pub mntops: String,
+    pub freq: String,
+    pub passno: String,
+    pub extra_fields: bool,
+}
+
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
 pub struct ConfigItem {
@@ -384,10 +1049,15 @@ impl Default for ProcessSortBy {
 #[derive(Clone, Debug)]
 pub struct AppConfig {
     pub safe_mode: bool,
+    /// Opt-in cache of the SMBIOS memory slots, see `monitors::smbios`
+    pub cache_dmi: bool,
     pub refresh_rate_ms: u64,
     pub history_length: usize,
     pub enable_docker: bool,
     pub enable_gpu_monitoring: bool,
     pub enable_network_monitoring: bool,
+    pub leak_window_secs: u64,
+    /// Monthly byte caps for metered interfaces
+    pub data_caps: Vec<(String, u64)>,
     pub language: crate::language::Language,
 }
\ No newline at end of file
@@ -331,6 +331,35 @@ pub fn get_system_health(load_avg: f64, cpu_cores: usize, mem_used: u64, mem_tot
     (status, format!("{}", load_per_core))
 }
 
+pub fn get_pressure_status(pressure: &crate::types::PressureStats) -> (String, String) {
+    let some = [pressure.cpu, pressure.memory, pressure.io]
+        .iter()
+        .flatten()
+        .map(|r| r.some.avg10)
+        .fold(0.0_f32, f32::max);
+    let full = [pressure.memory, pressure.io]
+        .iter()
+        .flatten()
+        .filter_map(|r| r.full.map(|f| f.avg10))
+        .fold(0.0_f32, f32::max);
+
+    let (status, color) = if full >= 10.0 || some >= 40.0 {
+        ("OVERLOADED", "red")
+    } else if full >= 1.0 || some >= 10.0 {
+        ("STRAINED", "yellow")
+    } else {
+        ("OK", "green")
+    };
+
+    (status.to_string(), color.to_string())
+}
+
+pub fn format_pressure(resource: Option<crate::types::PsiResource>) -> String {
+    resource
+        .map(|r| format!("{:.1}%", r.some.avg10))
+        .unwrap_or_else(|| "-".to_string())
+}
+
 pub fn get_memory_breakdown(mem_available: u64, mem_total: u64) -> (u64, u64) {
     let mem_used = mem_total.saturating_sub(mem_available);
     (mem_used, mem_available)
@@ -364,6 +393,23 @@ mod tests {
         assert_eq!(safe_percentage(100, 0), 0.0);
     }
 
+    #[test]
+    fn test_pressure_status() {
+        use crate::types::{PressureStats, PsiResource, PsiValues};
+
+        let idle = PressureStats::default();
+        assert_eq!(get_pressure_status(&idle).0, "OK");
+
+        let stalled = PressureStats {
+            memory: Some(PsiResource {
+                some: PsiValues { avg10: 5.0, ..Default::default() },
+                full: Some(PsiValues { avg10: 12.0, ..Default::default() }),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(get_pressure_status(&stalled).0, "OVERLOADED");
+    }
+
     #[test]
     fn test_is_system_process() {
         assert!(is_system_process("kworker/0:1"));